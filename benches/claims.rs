@@ -0,0 +1,69 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use kc_rs::Claims;
+
+fn payload(audience: serde_json::Value, clients: usize) -> Vec<u8> {
+    let resource = (0..clients)
+        .map(|i| {
+            (
+                format!("client-{i}"),
+                serde_json::json!({ "roles": ["read", "write"] }),
+            )
+        })
+        .collect::<serde_json::Map<_, _>>();
+
+    serde_json::to_vec(&serde_json::json!({
+        "iss": "https://keycloak.example.com/realms/app",
+        "sub": "23ce8772-a69c-436e-bad7-3cfa17283a7c",
+        "aud": audience,
+        "exp": 1_900_000_000,
+        "iat": 1_800_000_000,
+        "jti": "0b8a34c4-1f70-4a2b-8b6e-8d3f5b2c3a11",
+        "azp": "client-0",
+        "typ": "Bearer",
+        "preferred_username": "alice",
+        "realm_access": { "roles": ["user"] },
+        "resource_access": resource,
+    }))
+    .unwrap()
+}
+
+fn deserialize(c: &mut Criterion) {
+    let cases = [
+        ("one_audience_no_resource", payload("app".into(), 0)),
+        ("one_audience_one_resource", payload("app".into(), 1)),
+        (
+            "three_audiences_four_resources",
+            payload(serde_json::json!(["app", "account", "api"]), 4),
+        ),
+    ];
+
+    let mut group = c.benchmark_group("claims");
+    for (name, payload) in &cases {
+        group.bench_with_input(
+            BenchmarkId::new("deserialize", name),
+            payload,
+            |b, payload| {
+                b.iter(|| {
+                    serde_json::from_slice::<Claims>(black_box(payload))
+                        .unwrap()
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn has_role(c: &mut Criterion) {
+    let claims =
+        serde_json::from_slice::<Claims>(&payload("app".into(), 1)).unwrap();
+
+    c.bench_function("claims/has_role", |b| {
+        b.iter(|| black_box(&claims).has_role("client-0", "write"))
+    });
+}
+
+criterion_group!(benches, deserialize, has_role);
+criterion_main!(benches);
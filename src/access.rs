@@ -0,0 +1,169 @@
+use std::fmt;
+
+use arcstr::ArcStr;
+use serde::{de, ser::SerializeMap, Deserialize, Deserializer, Serialize};
+use serde_with::de::DeserializeAsWrap;
+use smallvec::SmallVec;
+
+use crate::{intern::Interned, RolesClaim};
+
+#[derive(Clone, Default)]
+pub struct ResourceAccess(SmallVec<[(ArcStr, RolesClaim); 1]>);
+
+impl ResourceAccess {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    #[inline]
+    pub fn get(&self, client_id: &str) -> Option<&RolesClaim> {
+        self.0
+            .iter()
+            .find(|(client, _)| client == client_id)
+            .map(|(_, roles)| roles)
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, client_id: &str) -> Option<&mut RolesClaim> {
+        self.0
+            .iter_mut()
+            .find(|(client, _)| client == client_id)
+            .map(|(_, roles)| roles)
+    }
+
+    #[inline]
+    pub fn contains_key(&self, client_id: &str) -> bool {
+        self.get(client_id).is_some()
+    }
+
+    pub fn insert(
+        &mut self,
+        client_id: impl Into<ArcStr>,
+        roles: RolesClaim,
+    ) -> Option<RolesClaim> {
+        let client_id = client_id.into();
+        match self.get_mut(&client_id) {
+            | Some(existing) => Some(std::mem::replace(existing, roles)),
+            | None => {
+                self.0.push((client_id, roles));
+                None
+            }
+        }
+    }
+
+    pub fn remove(&mut self, client_id: &str) -> Option<RolesClaim> {
+        let index =
+            self.0.iter().position(|(client, _)| client == client_id)?;
+
+        Some(self.0.remove(index).1)
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&ArcStr, &RolesClaim)> {
+        self.0.iter().map(|(client, roles)| (client, roles))
+    }
+
+    #[inline]
+    pub fn keys(&self) -> impl Iterator<Item = &ArcStr> {
+        self.0.iter().map(|(client, _)| client)
+    }
+}
+
+impl<'a> IntoIterator for &'a ResourceAccess {
+    type IntoIter = std::iter::Map<
+        std::slice::Iter<'a, (ArcStr, RolesClaim)>,
+        fn(&'a (ArcStr, RolesClaim)) -> (&'a ArcStr, &'a RolesClaim),
+    >;
+    type Item = (&'a ArcStr, &'a RolesClaim);
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter().map(|(client, roles)| (client, roles))
+    }
+}
+
+impl IntoIterator for ResourceAccess {
+    type IntoIter = smallvec::IntoIter<[(ArcStr, RolesClaim); 1]>;
+    type Item = (ArcStr, RolesClaim);
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<K: Into<ArcStr>> FromIterator<(K, RolesClaim)> for ResourceAccess {
+    fn from_iter<I: IntoIterator<Item = (K, RolesClaim)>>(iter: I) -> Self {
+        let mut access = Self::new();
+        for (client_id, roles) in iter {
+            access.insert(client_id, roles);
+        }
+
+        access
+    }
+}
+
+impl fmt::Debug for ResourceAccess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'de> Deserialize<'de> for ResourceAccess {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = ResourceAccess;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a map of client roles")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::MapAccess<'de>,
+            {
+                let mut access = ResourceAccess::new();
+                while let Some((client_id, roles)) = map.next_entry::<
+                    DeserializeAsWrap<ArcStr, Interned>,
+                    RolesClaim,
+                >()? {
+                    access.insert(client_id.into_inner(), roles);
+                }
+
+                Ok(access)
+            }
+        }
+
+        deserializer.deserialize_map(Visitor)
+    }
+}
+
+impl Serialize for ResourceAccess {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (client_id, roles) in self {
+            map.serialize_entry(client_id.as_str(), roles)?;
+        }
+
+        map.end()
+    }
+}
@@ -0,0 +1,157 @@
+use std::fmt;
+
+use serde_with::TimestampSeconds;
+
+use crate::{
+    telemetry::{self, TOKEN_TARGET},
+    Error,
+    ReCloak,
+    Result,
+};
+
+const NONCE_CLAIM: &str = "nonce";
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize)]
+#[serde(from = "String")]
+pub enum ActionTokenKind {
+    VerifyEmail,
+    ExecuteActions,
+    ResetCredentials,
+    Other(String),
+}
+
+#[serde_with::serde_as]
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ActionToken {
+    #[serde(rename = "typ")]
+    pub kind: ActionTokenKind,
+
+    #[serde(rename = "iss")]
+    pub issuer: String,
+
+    #[serde(rename = "sub")]
+    pub user_id: uuid::Uuid,
+
+    #[serde(rename = "aud", default)]
+    #[serde_as(as = "serde_with::OneOrMany<_>")]
+    pub audience: Vec<String>,
+
+    #[serde(rename = "exp")]
+    #[serde_as(as = "TimestampSeconds<i64>")]
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+
+    #[serde(rename = "iat", default)]
+    #[serde_as(as = "Option<TimestampSeconds<i64>>")]
+    pub issued_at: Option<chrono::DateTime<chrono::Utc>>,
+
+    #[serde(rename = "azp")]
+    pub client_id: Option<String>,
+
+    #[serde(rename = "nonce")]
+    pub nonce: Option<uuid::Uuid>,
+
+    #[serde(rename = "asid")]
+    pub auth_session_id: Option<String>,
+
+    #[serde(rename = "oasid")]
+    pub original_auth_session_id: Option<String>,
+
+    #[serde(rename = "eml")]
+    pub email: Option<String>,
+
+    #[serde(rename = "rqac", default)]
+    pub required_actions: Vec<String>,
+
+    #[serde(rename = "reduri")]
+    pub redirect_uri: Option<String>,
+
+    #[serde(flatten)]
+    pub extra: std::collections::HashMap<String, serde_json::Value>,
+}
+
+impl ActionTokenKind {
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        match self {
+            | Self::VerifyEmail => "verify-email",
+            | Self::ExecuteActions => "execute-actions",
+            | Self::ResetCredentials => "reset-credentials",
+            | Self::Other(kind) => kind,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn is_oidc_token(&self) -> bool {
+        matches!(
+            self,
+            Self::Other(kind)
+                if ["Bearer", "ID", "Refresh", "Offline", "Logout"]
+                    .contains(&kind.as_str())
+        )
+    }
+}
+
+impl From<String> for ActionTokenKind {
+    fn from(kind: String) -> Self {
+        match kind.as_str() {
+            | "verify-email" => Self::VerifyEmail,
+            | "execute-actions" => Self::ExecuteActions,
+            | "reset-credentials" => Self::ResetCredentials,
+            | _ => Self::Other(kind),
+        }
+    }
+}
+
+impl fmt::Display for ActionTokenKind {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl ReCloak {
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, token)))]
+    pub fn decode_action_token(
+        &self,
+        token: &str,
+        kind: ActionTokenKind,
+    ) -> Result<ActionToken> {
+        let action = self.decoder.current().decode_action(token)?;
+
+        if action.kind != kind {
+            return Err(Error::InvalidTokenType(Some(action.kind.to_string())));
+        }
+
+        Ok(action)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, token)))]
+    pub async fn consume_action_token(
+        &self,
+        token: &str,
+        kind: ActionTokenKind,
+    ) -> Result<ActionToken> {
+        let action = self.decode_action_token(token, kind)?;
+        let nonce = action.nonce.ok_or_else(|| {
+            jsonwebtoken::errors::Error::from(
+                jsonwebtoken::errors::ErrorKind::MissingRequiredClaim(
+                    NONCE_CLAIM.to_owned(),
+                ),
+            )
+        })?;
+
+        let ttl = self.ttl_until(action.expires_at);
+        if !self.jti_store()?.consume(&nonce.to_string(), ttl).await? {
+            telemetry::trace_warn!(
+                target: TOKEN_TARGET,
+                %nonce,
+                kind = %action.kind,
+                "rejected replayed action token"
+            );
+
+            return Err(Error::ReplayedToken);
+        }
+
+        Ok(action)
+    }
+}
@@ -0,0 +1,59 @@
+use serde::Deserialize;
+
+use super::AdminClient;
+use crate::Result;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BruteForceStatus {
+    #[serde(default)]
+    pub num_failures: u32,
+
+    #[serde(default)]
+    pub disabled: bool,
+
+    #[serde(rename = "lastIPFailure")]
+    pub last_ip_failure: Option<String>,
+
+    pub last_failure: Option<i64>,
+}
+
+impl BruteForceStatus {
+    #[inline]
+    pub fn last_failure_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.last_failure.filter(|ms| *ms > 0).and_then(|ms| {
+            chrono::DateTime::from_timestamp(
+                ms.div_euclid(1000),
+                ms.rem_euclid(1000) as u32 * 1_000_000,
+            )
+        })
+    }
+}
+
+impl AdminClient<'_> {
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn brute_force_status(
+        &self,
+        user_id: uuid::Uuid,
+    ) -> Result<BruteForceStatus> {
+        let id = user_id.to_string();
+
+        self.get(&["attack-detection", "brute-force", "users", &id])
+            .await
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn clear_brute_force(&self, user_id: uuid::Uuid) -> Result<()> {
+        let id = user_id.to_string();
+
+        self.delete(&["attack-detection", "brute-force", "users", &id])
+            .await
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn clear_all_brute_force(&self) -> Result<()> {
+        self.delete(&["attack-detection", "brute-force", "users"])
+            .await
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use super::AdminClient;
+use crate::{
+    representations::{
+        AuthenticationExecutionInfo,
+        AuthenticationFlowRepresentation,
+        RequiredActionProviderRepresentation,
+    },
+    Result,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowBinding {
+    Browser,
+    Registration,
+    DirectGrant,
+    ResetCredentials,
+    ClientAuthentication,
+    DockerAuthentication,
+    FirstBrokerLogin,
+}
+
+impl FlowBinding {
+    #[inline]
+    const fn realm_attribute(self) -> &'static str {
+        match self {
+            | FlowBinding::Browser => "browserFlow",
+            | FlowBinding::Registration => "registrationFlow",
+            | FlowBinding::DirectGrant => "directGrantFlow",
+            | FlowBinding::ResetCredentials => "resetCredentialsFlow",
+            | FlowBinding::ClientAuthentication => "clientAuthenticationFlow",
+            | FlowBinding::DockerAuthentication => "dockerAuthenticationFlow",
+            | FlowBinding::FirstBrokerLogin => "firstBrokerLoginFlow",
+        }
+    }
+}
+
+impl AdminClient<'_> {
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn authentication_flows(
+        &self,
+    ) -> Result<Vec<AuthenticationFlowRepresentation>> {
+        self.get(&["authentication", "flows"]).await
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn copy_authentication_flow(
+        &self,
+        alias: &str,
+        new_name: &str,
+    ) -> Result<()> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct CopyDto<'a> {
+            new_name: &'a str,
+        }
+
+        self.post(
+            &["authentication", "flows", alias, "copy"],
+            &CopyDto { new_name },
+        )
+        .await
+        .map(|_| ())
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn delete_authentication_flow(&self, id: &str) -> Result<()> {
+        self.delete(&["authentication", "flows", id]).await
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn flow_executions(
+        &self,
+        alias: &str,
+    ) -> Result<Vec<AuthenticationExecutionInfo>> {
+        self.get(&["authentication", "flows", alias, "executions"])
+            .await
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, execution)))]
+    pub async fn update_flow_execution(
+        &self,
+        alias: &str,
+        execution: &AuthenticationExecutionInfo,
+    ) -> Result<()> {
+        self.put(&["authentication", "flows", alias, "executions"], execution)
+            .await
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn bind_flow(
+        &self,
+        binding: FlowBinding,
+        alias: &str,
+    ) -> Result<()> {
+        let realm = HashMap::from([(binding.realm_attribute(), alias)]);
+
+        self.put(&[], &realm).await
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn required_actions(
+        &self,
+    ) -> Result<Vec<RequiredActionProviderRepresentation>> {
+        self.get(&["authentication", "required-actions"]).await
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, action)))]
+    pub async fn update_required_action(
+        &self,
+        action: &RequiredActionProviderRepresentation,
+    ) -> Result<()> {
+        self.put(
+            &["authentication", "required-actions", &action.alias],
+            action,
+        )
+        .await
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn register_required_action(
+        &self,
+        provider_id: &str,
+        name: &str,
+    ) -> Result<()> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct RegisterDto<'a> {
+            provider_id: &'a str,
+            name: &'a str,
+        }
+
+        self.post(
+            &["authentication", "register-required-action"],
+            &RegisterDto { provider_id, name },
+        )
+        .await
+        .map(|_| ())
+    }
+}
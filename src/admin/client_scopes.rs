@@ -0,0 +1,188 @@
+use super::{created_id, AdminClient};
+use crate::{
+    representations::{
+        ClientScopeRepresentation,
+        ProtocolMapperRepresentation,
+    },
+    Result,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientScopeKind {
+    Default,
+    Optional,
+}
+
+impl ClientScopeKind {
+    #[inline]
+    const fn path_segment(self) -> &'static str {
+        match self {
+            | ClientScopeKind::Default => "default-client-scopes",
+            | ClientScopeKind::Optional => "optional-client-scopes",
+        }
+    }
+}
+
+impl AdminClient<'_> {
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn client_scopes(
+        &self,
+    ) -> Result<Vec<ClientScopeRepresentation>> {
+        self.get(&["client-scopes"]).await
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn client_scope(
+        &self,
+        id: &str,
+    ) -> Result<ClientScopeRepresentation> {
+        self.get(&["client-scopes", id]).await
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, scope)))]
+    pub async fn create_client_scope(
+        &self,
+        scope: &ClientScopeRepresentation,
+    ) -> Result<Option<String>> {
+        let resp = self.post(&["client-scopes"], scope).await?;
+
+        Ok(created_id(&resp))
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, scope)))]
+    pub async fn update_client_scope(
+        &self,
+        id: &str,
+        scope: &ClientScopeRepresentation,
+    ) -> Result<()> {
+        self.put(&["client-scopes", id], scope).await
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn delete_client_scope(&self, id: &str) -> Result<()> {
+        self.delete(&["client-scopes", id]).await
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn scope_protocol_mappers(
+        &self,
+        scope_id: &str,
+    ) -> Result<Vec<ProtocolMapperRepresentation>> {
+        self.get(&["client-scopes", scope_id, "protocol-mappers", "models"])
+            .await
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, mapper)))]
+    pub async fn create_scope_protocol_mapper(
+        &self,
+        scope_id: &str,
+        mapper: &ProtocolMapperRepresentation,
+    ) -> Result<Option<String>> {
+        let path = ["client-scopes", scope_id, "protocol-mappers", "models"];
+        let resp = self.post(&path, mapper).await?;
+
+        Ok(created_id(&resp))
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, mapper)))]
+    pub async fn update_scope_protocol_mapper(
+        &self,
+        scope_id: &str,
+        mapper_id: &str,
+        mapper: &ProtocolMapperRepresentation,
+    ) -> Result<()> {
+        let path = [
+            "client-scopes",
+            scope_id,
+            "protocol-mappers",
+            "models",
+            mapper_id,
+        ];
+
+        self.put(&path, mapper).await
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn delete_scope_protocol_mapper(
+        &self,
+        scope_id: &str,
+        mapper_id: &str,
+    ) -> Result<()> {
+        let path = [
+            "client-scopes",
+            scope_id,
+            "protocol-mappers",
+            "models",
+            mapper_id,
+        ];
+
+        self.delete(&path).await
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn client_protocol_mappers(
+        &self,
+        client_uuid: &str,
+    ) -> Result<Vec<ProtocolMapperRepresentation>> {
+        self.get(&["clients", client_uuid, "protocol-mappers", "models"])
+            .await
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, mapper)))]
+    pub async fn create_client_protocol_mapper(
+        &self,
+        client_uuid: &str,
+        mapper: &ProtocolMapperRepresentation,
+    ) -> Result<Option<String>> {
+        let path = ["clients", client_uuid, "protocol-mappers", "models"];
+        let resp = self.post(&path, mapper).await?;
+
+        Ok(created_id(&resp))
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn assigned_client_scopes(
+        &self,
+        client_uuid: &str,
+        kind: ClientScopeKind,
+    ) -> Result<Vec<ClientScopeRepresentation>> {
+        self.get(&["clients", client_uuid, kind.path_segment()])
+            .await
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn assign_client_scope(
+        &self,
+        client_uuid: &str,
+        scope_id: &str,
+        kind: ClientScopeKind,
+    ) -> Result<()> {
+        let path = ["clients", client_uuid, kind.path_segment(), scope_id];
+
+        self.send(self.request(reqwest::Method::PUT, &path))
+            .await
+            .map(|_| ())
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn unassign_client_scope(
+        &self,
+        client_uuid: &str,
+        scope_id: &str,
+        kind: ClientScopeKind,
+    ) -> Result<()> {
+        self.delete(&["clients", client_uuid, kind.path_segment(), scope_id])
+            .await
+    }
+}
@@ -0,0 +1,58 @@
+use reqwest::{Method, StatusCode};
+
+use super::AdminClient;
+use crate::{representations::CredentialRepresentation, Error, Result};
+
+impl AdminClient<'_> {
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn client_secret(
+        &self,
+        client_uuid: &str,
+    ) -> Result<CredentialRepresentation> {
+        self.get(&["clients", client_uuid, "client-secret"]).await
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn regenerate_client_secret(
+        &self,
+        client_uuid: &str,
+    ) -> Result<CredentialRepresentation> {
+        self.send(
+            self.request(
+                Method::POST,
+                &["clients", client_uuid, "client-secret"],
+            ),
+        )
+        .await?
+        .json()
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn rotated_client_secret(
+        &self,
+        client_uuid: &str,
+    ) -> Result<Option<CredentialRepresentation>> {
+        match self
+            .get(&["clients", client_uuid, "client-secret", "rotated"])
+            .await
+        {
+            | Ok(secret) => Ok(Some(secret)),
+            | Err(Error::Admin {
+                status: StatusCode::NOT_FOUND,
+                ..
+            }) => Ok(None),
+            | Err(err) => Err(err),
+        }
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn invalidate_rotated_client_secret(
+        &self,
+        client_uuid: &str,
+    ) -> Result<()> {
+        self.delete(&["clients", client_uuid, "client-secret", "rotated"])
+            .await
+    }
+}
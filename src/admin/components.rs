@@ -0,0 +1,59 @@
+use super::{created_id, AdminClient};
+use crate::{representations::ComponentRepresentation, Result};
+
+impl AdminClient<'_> {
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn components(
+        &self,
+        provider_type: Option<&str>,
+    ) -> Result<Vec<ComponentRepresentation>> {
+        let mut query = vec![("parent", self.realm_id().await?)];
+        if let Some(provider_type) = provider_type {
+            query.push(("type", provider_type.to_owned()));
+        }
+
+        self.send(
+            self.request(reqwest::Method::GET, &["components"])
+                .query(&query),
+        )
+        .await?
+        .json()
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn component(&self, id: &str) -> Result<ComponentRepresentation> {
+        self.get(&["components", id]).await
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, component)))]
+    pub async fn create_component(
+        &self,
+        component: &ComponentRepresentation,
+    ) -> Result<Option<String>> {
+        let mut component = component.clone();
+        if component.parent_id.is_none() {
+            component.parent_id = Some(self.realm_id().await?);
+        }
+
+        let resp = self.post(&["components"], &component).await?;
+
+        Ok(created_id(&resp))
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, component)))]
+    pub async fn update_component(
+        &self,
+        id: &str,
+        component: &ComponentRepresentation,
+    ) -> Result<()> {
+        self.put(&["components", id], component).await
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn delete_component(&self, id: &str) -> Result<()> {
+        self.delete(&["components", id]).await
+    }
+}
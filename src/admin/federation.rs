@@ -0,0 +1,123 @@
+use serde::Deserialize;
+
+use super::AdminClient;
+use crate::{representations::ComponentRepresentation, Result};
+
+const USER_STORAGE_PROVIDER_TYPE: &str =
+    "org.keycloak.storage.UserStorageProvider";
+const LAST_SYNC_CONFIG_KEY: &str = "lastSync";
+const FULL_SYNC_PERIOD_CONFIG_KEY: &str = "fullSyncPeriod";
+const CHANGED_SYNC_PERIOD_CONFIG_KEY: &str = "changedSyncPeriod";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncAction {
+    Full,
+    ChangedUsers,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SynchronizationResult {
+    #[serde(default)]
+    pub ignored: bool,
+
+    #[serde(default)]
+    pub added: u64,
+
+    #[serde(default)]
+    pub updated: u64,
+
+    #[serde(default)]
+    pub removed: u64,
+
+    #[serde(default)]
+    pub failed: u64,
+
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FederationSyncStatus {
+    pub last_sync: Option<chrono::DateTime<chrono::Utc>>,
+    pub full_sync_period: Option<chrono::Duration>,
+    pub changed_sync_period: Option<chrono::Duration>,
+}
+
+impl SyncAction {
+    #[inline]
+    const fn as_str(self) -> &'static str {
+        match self {
+            | SyncAction::Full => "triggerFullSync",
+            | SyncAction::ChangedUsers => "triggerChangedUsersSync",
+        }
+    }
+}
+
+impl AdminClient<'_> {
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn user_federation_providers(
+        &self,
+    ) -> Result<Vec<ComponentRepresentation>> {
+        self.components(Some(USER_STORAGE_PROVIDER_TYPE)).await
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, provider)))]
+    pub async fn create_user_federation(
+        &self,
+        provider: &ComponentRepresentation,
+    ) -> Result<Option<String>> {
+        let mut provider = provider.clone();
+        provider.provider_type = Some(USER_STORAGE_PROVIDER_TYPE.to_owned());
+
+        self.create_component(&provider).await
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, provider)))]
+    pub async fn update_user_federation(
+        &self,
+        id: &str,
+        provider: &ComponentRepresentation,
+    ) -> Result<()> {
+        let mut provider = provider.clone();
+        provider.provider_type = Some(USER_STORAGE_PROVIDER_TYPE.to_owned());
+
+        self.update_component(id, &provider).await
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn sync_user_federation(
+        &self,
+        id: &str,
+        action: SyncAction,
+    ) -> Result<SynchronizationResult> {
+        let req = self
+            .request(reqwest::Method::POST, &["user-storage", id, "sync"])
+            .query(&[("action", action.as_str())]);
+
+        self.send(req).await?.json()
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn user_federation_sync_status(
+        &self,
+        id: &str,
+    ) -> Result<FederationSyncStatus> {
+        let provider = self.component(id).await?;
+
+        let seconds = |key| {
+            provider
+                .config_value(key)
+                .and_then(|v| v.parse::<i64>().ok())
+                .filter(|v| *v > 0)
+        };
+
+        Ok(FederationSyncStatus {
+            last_sync: seconds(LAST_SYNC_CONFIG_KEY)
+                .and_then(|s| chrono::DateTime::from_timestamp(s, 0)),
+            full_sync_period: seconds(FULL_SYNC_PERIOD_CONFIG_KEY)
+                .map(chrono::Duration::seconds),
+            changed_sync_period: seconds(CHANGED_SYNC_PERIOD_CONFIG_KEY)
+                .map(chrono::Duration::seconds),
+        })
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use super::AdminClient;
+use crate::{representations::ComponentRepresentation, telemetry, Result};
+
+const KEY_PROVIDER_TYPE: &str = "org.keycloak.keys.KeyProvider";
+const PRIORITY_CONFIG_KEY: &str = "priority";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeysMetadata {
+    #[serde(default)]
+    pub active: HashMap<String, String>,
+
+    #[serde(default)]
+    pub keys: Vec<KeyMetadata>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyMetadata {
+    pub provider_id: Option<String>,
+    pub provider_priority: Option<i64>,
+    pub kid: Option<String>,
+    pub status: Option<String>,
+    #[serde(rename = "type")]
+    pub key_type: Option<String>,
+    pub algorithm: Option<String>,
+    pub public_key: Option<String>,
+    pub certificate: Option<String>,
+    #[serde(rename = "use")]
+    pub key_use: Option<String>,
+    pub valid_to: Option<i64>,
+}
+
+impl AdminClient<'_> {
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn realm_keys(&self) -> Result<KeysMetadata> {
+        self.get(&["keys"]).await
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn key_providers(&self) -> Result<Vec<ComponentRepresentation>> {
+        self.components(Some(KEY_PROVIDER_TYPE)).await
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, provider)))]
+    pub async fn set_key_provider(
+        &self,
+        provider: &ComponentRepresentation,
+    ) -> Result<Option<String>> {
+        let mut provider = provider.clone();
+        provider.provider_type = Some(KEY_PROVIDER_TYPE.to_owned());
+
+        match provider.id.clone() {
+            | Some(id) => {
+                self.update_component(&id, &provider).await?;
+
+                Ok(Some(id))
+            }
+            | None => self.create_component(&provider).await,
+        }
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn rotate_keys(
+        &self,
+        provider_id: &str,
+    ) -> Result<Option<String>> {
+        let priority = self
+            .key_providers()
+            .await?
+            .iter()
+            .filter_map(|p| p.config_value(PRIORITY_CONFIG_KEY))
+            .filter_map(|p| p.parse::<i64>().ok())
+            .max()
+            .unwrap_or(0)
+            + 1;
+
+        let mut provider = ComponentRepresentation {
+            name: Some(format!("{provider_id}-{priority}")),
+            provider_id: Some(provider_id.to_owned()),
+            ..Default::default()
+        };
+        provider.set_config_value(PRIORITY_CONFIG_KEY, priority.to_string());
+
+        let id = self.set_key_provider(&provider).await?;
+
+        telemetry::trace_info!(?id, priority, "rotated realm keys");
+
+        self.kc.reload_jwks().await?;
+
+        Ok(id)
+    }
+}
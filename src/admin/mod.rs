@@ -0,0 +1,163 @@
+mod attack_detection;
+mod authentication;
+mod client_scopes;
+mod clients;
+mod components;
+mod federation;
+mod keys;
+mod organizations;
+mod realm;
+mod roles;
+mod settings;
+mod sync;
+mod user_profile;
+mod users;
+
+use reqwest::{header::LOCATION, Method, RequestBuilder};
+use serde::{de::DeserializeOwned, Serialize};
+
+pub(crate) use self::roles::RoleCache;
+pub use self::{
+    attack_detection::BruteForceStatus,
+    authentication::FlowBinding,
+    client_scopes::ClientScopeKind,
+    federation::{FederationSyncStatus, SyncAction, SynchronizationResult},
+    keys::{KeyMetadata, KeysMetadata},
+    realm::{
+        ExportOptions,
+        ImportStrategy,
+        PartialImportResult,
+        PartialImportResults,
+    },
+    roles::EffectiveRoles,
+    settings::{PasswordPolicyRule, RealmSettings, SettingConflict},
+    sync::{SyncFailure, SyncOptions, SyncOutcome, SyncReport},
+};
+use crate::{ReCloak, Result, TransportResponse};
+
+#[derive(Debug, Clone, Copy)]
+pub struct AdminClient<'a> {
+    kc: &'a ReCloak,
+}
+
+impl<'a> AdminClient<'a> {
+    #[inline]
+    pub(crate) const fn new(kc: &'a ReCloak) -> Self {
+        Self { kc }
+    }
+
+    fn request(&self, method: Method, path: &[&str]) -> RequestBuilder {
+        let mut url = self.kc.urls.load().admin.clone();
+        url.path_segments_mut().unwrap().extend(path);
+
+        self.kc.client.request(method, url)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, req)))]
+    async fn send(&self, req: RequestBuilder) -> Result<TransportResponse> {
+        self.kc.config.load().require_keycloak("the admin api")?;
+
+        #[derive(serde::Deserialize)]
+        struct ErrorDto {
+            #[serde(alias = "errorMessage")]
+            error: Option<String>,
+            error_description: Option<String>,
+        }
+
+        let token = self.kc.authenticate().await?;
+        let resp = self.kc.governor.send(req.bearer_auth(token)).await?;
+
+        let status = resp.status;
+        if status.is_success() {
+            return Ok(resp);
+        }
+
+        let message = resp
+            .json::<ErrorDto>()
+            .ok()
+            .and_then(|err| err.error_description.or(err.error));
+
+        Err(crate::Error::Admin { status, message })
+    }
+
+    #[inline]
+    async fn get<T>(&self, path: &[&str]) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        self.send(self.request(Method::GET, path)).await?.json()
+    }
+
+    #[inline]
+    async fn post<B>(
+        &self,
+        path: &[&str],
+        body: &B,
+    ) -> Result<TransportResponse>
+    where
+        B: Serialize + ?Sized,
+    {
+        self.send(self.request(Method::POST, path).json(body)).await
+    }
+
+    #[inline]
+    async fn put<B>(&self, path: &[&str], body: &B) -> Result<()>
+    where
+        B: Serialize + ?Sized,
+    {
+        self.send(self.request(Method::PUT, path).json(body))
+            .await
+            .map(|_| ())
+    }
+
+    #[inline]
+    async fn delete(&self, path: &[&str]) -> Result<()> {
+        self.send(self.request(Method::DELETE, path))
+            .await
+            .map(|_| ())
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn client_uuid(&self, client_id: &str) -> Result<Option<String>> {
+        #[derive(serde::Deserialize)]
+        struct ClientDto {
+            id: String,
+        }
+
+        let clients = self
+            .send(
+                self.request(Method::GET, &["clients"])
+                    .query(&[("clientId", client_id)]),
+            )
+            .await?
+            .json::<Vec<ClientDto>>()?;
+
+        Ok(clients.into_iter().next().map(|client| client.id))
+    }
+
+    async fn realm_id(&self) -> Result<String> {
+        #[derive(serde::Deserialize)]
+        struct RealmDto {
+            id: String,
+        }
+
+        self.get::<RealmDto>(&[]).await.map(|realm| realm.id)
+    }
+}
+
+fn created_id(resp: &TransportResponse) -> Option<String> {
+    let location = resp.headers.get(LOCATION)?.to_str().ok()?;
+
+    location
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .map(str::to_owned)
+}
+
+impl ReCloak {
+    #[inline]
+    pub const fn admin(&self) -> AdminClient<'_> {
+        AdminClient::new(self)
+    }
+}
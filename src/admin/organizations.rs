@@ -0,0 +1,157 @@
+use reqwest::Method;
+
+use super::{created_id, AdminClient};
+use crate::{
+    representations::{
+        IdentityProviderRepresentation,
+        MemberRepresentation,
+        OrganizationRepresentation,
+    },
+    Result,
+};
+
+impl AdminClient<'_> {
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn organizations(
+        &self,
+        search: Option<&str>,
+    ) -> Result<Vec<OrganizationRepresentation>> {
+        let mut query = vec![("briefRepresentation", "false")];
+        if let Some(search) = search {
+            query.push(("search", search));
+        }
+
+        self.send(self.request(Method::GET, &["organizations"]).query(&query))
+            .await?
+            .json()
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn organization(
+        &self,
+        id: &str,
+    ) -> Result<OrganizationRepresentation> {
+        self.get(&["organizations", id]).await
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self, organization), fields(name = %organization.name))
+    )]
+    pub async fn create_organization(
+        &self,
+        organization: &OrganizationRepresentation,
+    ) -> Result<Option<String>> {
+        let resp = self.post(&["organizations"], organization).await?;
+
+        Ok(created_id(&resp))
+    }
+
+    #[inline]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self, organization))
+    )]
+    pub async fn update_organization(
+        &self,
+        id: &str,
+        organization: &OrganizationRepresentation,
+    ) -> Result<()> {
+        self.put(&["organizations", id], organization).await
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn delete_organization(&self, id: &str) -> Result<()> {
+        self.delete(&["organizations", id]).await
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn organization_members(
+        &self,
+        org_id: &str,
+    ) -> Result<Vec<MemberRepresentation>> {
+        self.get(&["organizations", org_id, "members"]).await
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn add_organization_member(
+        &self,
+        org_id: &str,
+        user_id: uuid::Uuid,
+    ) -> Result<()> {
+        self.post(&["organizations", org_id, "members"], &user_id.to_string())
+            .await
+            .map(|_| ())
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, email)))]
+    pub async fn invite_organization_member(
+        &self,
+        org_id: &str,
+        email: &str,
+    ) -> Result<()> {
+        let path = ["organizations", org_id, "members", "invite-user"];
+
+        self.send(self.request(Method::POST, &path).form(&[("email", email)]))
+            .await
+            .map(|_| ())
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn remove_organization_member(
+        &self,
+        org_id: &str,
+        user_id: uuid::Uuid,
+    ) -> Result<()> {
+        let id = user_id.to_string();
+
+        self.delete(&["organizations", org_id, "members", &id])
+            .await
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn user_organizations(
+        &self,
+        user_id: uuid::Uuid,
+    ) -> Result<Vec<OrganizationRepresentation>> {
+        let id = user_id.to_string();
+
+        self.get(&["organizations", "members", &id, "organizations"])
+            .await
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn organization_identity_providers(
+        &self,
+        org_id: &str,
+    ) -> Result<Vec<IdentityProviderRepresentation>> {
+        self.get(&["organizations", org_id, "identity-providers"])
+            .await
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn link_organization_identity_provider(
+        &self,
+        org_id: &str,
+        alias: &str,
+    ) -> Result<()> {
+        self.post(&["organizations", org_id, "identity-providers"], alias)
+            .await
+            .map(|_| ())
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn unlink_organization_identity_provider(
+        &self,
+        org_id: &str,
+        alias: &str,
+    ) -> Result<()> {
+        self.delete(&["organizations", org_id, "identity-providers", alias])
+            .await
+    }
+}
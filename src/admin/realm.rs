@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+
+use super::AdminClient;
+use crate::{representations::RealmRepresentation, Result};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExportOptions {
+    pub clients: bool,
+    pub groups_and_roles: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ImportStrategy {
+    #[default]
+    Fail,
+    Skip,
+    Overwrite,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartialImportResults {
+    #[serde(default)]
+    pub added: u64,
+
+    #[serde(default)]
+    pub skipped: u64,
+
+    #[serde(default)]
+    pub overwritten: u64,
+
+    #[serde(default)]
+    pub results: Vec<PartialImportResult>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartialImportResult {
+    pub action: Option<String>,
+    pub resource_type: Option<String>,
+    pub resource_name: Option<String>,
+    pub id: Option<String>,
+}
+
+impl AdminClient<'_> {
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn realm(&self) -> Result<RealmRepresentation> {
+        self.get(&[]).await
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn export_realm(
+        &self,
+        options: ExportOptions,
+    ) -> Result<RealmRepresentation> {
+        let req = self
+            .request(reqwest::Method::POST, &["partial-export"])
+            .query(&[
+                ("exportClients", options.clients),
+                ("exportGroupsAndRoles", options.groups_and_roles),
+            ]);
+
+        self.send(req).await?.json()
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self, realm), fields(realm = %realm.realm))
+    )]
+    pub async fn import_realm(
+        &self,
+        realm: &RealmRepresentation,
+        strategy: ImportStrategy,
+    ) -> Result<PartialImportResults> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct PartialImportDto<'a> {
+            if_resource_exists: ImportStrategy,
+
+            #[serde(flatten)]
+            realm: &'a RealmRepresentation,
+        }
+
+        self.post(
+            &["partialImport"],
+            &PartialImportDto {
+                if_resource_exists: strategy,
+                realm,
+            },
+        )
+        .await?
+        .json()
+    }
+}
@@ -0,0 +1,155 @@
+use std::{collections::HashMap, sync::Arc};
+
+use serde::Deserialize;
+
+use super::AdminClient;
+use crate::{
+    cache::TtlCache,
+    representations::RoleRepresentation,
+    ReCloak,
+    Result,
+};
+
+pub(crate) type RoleCache = TtlCache<uuid::Uuid, Arc<EffectiveRoles>>;
+
+#[derive(Debug, Clone, Default)]
+pub struct EffectiveRoles {
+    pub realm: Vec<String>,
+    pub clients: HashMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MappingsRepresentation {
+    #[serde(default)]
+    client_mappings: HashMap<String, ClientMappingsRepresentation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClientMappingsRepresentation {
+    id: String,
+}
+
+impl EffectiveRoles {
+    #[inline]
+    pub fn has_realm_role(&self, role: impl AsRef<str>) -> bool {
+        self.realm.iter().any(|r| r == role.as_ref())
+    }
+
+    #[inline]
+    pub fn has_role(
+        &self,
+        client_id: impl AsRef<str>,
+        role: impl AsRef<str>,
+    ) -> bool {
+        self.clients
+            .get(client_id.as_ref())
+            .map(|r| r.iter().any(|r| r == role.as_ref()))
+            .unwrap_or(false)
+    }
+}
+
+impl AdminClient<'_> {
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn user_realm_roles(
+        &self,
+        user_id: uuid::Uuid,
+    ) -> Result<Vec<RoleRepresentation>> {
+        let id = user_id.to_string();
+
+        self.get(&["users", &id, "role-mappings", "realm", "composite"])
+            .await
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn user_client_roles(
+        &self,
+        user_id: uuid::Uuid,
+        client_uuid: &str,
+    ) -> Result<Vec<RoleRepresentation>> {
+        let id = user_id.to_string();
+
+        self.get(&[
+            "users",
+            &id,
+            "role-mappings",
+            "clients",
+            client_uuid,
+            "composite",
+        ])
+        .await
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn effective_roles(
+        &self,
+        user_id: uuid::Uuid,
+    ) -> Result<EffectiveRoles> {
+        let id = user_id.to_string();
+
+        let realm = self
+            .user_realm_roles(user_id)
+            .await?
+            .into_iter()
+            .map(|r| r.name)
+            .collect();
+
+        let mut clients = self
+            .get::<MappingsRepresentation>(&["users", &id, "role-mappings"])
+            .await?
+            .client_mappings
+            .into_iter()
+            .map(|(client_id, mapping)| (client_id, mapping.id))
+            .collect::<HashMap<_, _>>();
+
+        let config = self.kc.config.load_full();
+        let own_client_id = config.client.id.as_str();
+        if !clients.contains_key(own_client_id) {
+            if let Some(uuid) = self.client_uuid(own_client_id).await? {
+                clients.insert(own_client_id.to_owned(), uuid);
+            }
+        }
+
+        let mut roles = EffectiveRoles {
+            realm,
+            clients: HashMap::with_capacity(clients.len()),
+        };
+
+        for (client_id, client_uuid) in clients {
+            let client_roles = self
+                .user_client_roles(user_id, &client_uuid)
+                .await?
+                .into_iter()
+                .map(|r| r.name)
+                .collect();
+
+            roles.clients.insert(client_id, client_roles);
+        }
+
+        Ok(roles)
+    }
+}
+
+impl ReCloak {
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn effective_roles(
+        &self,
+        user_id: uuid::Uuid,
+    ) -> Result<Arc<EffectiveRoles>> {
+        if let Some(roles) = self.roles.get(&user_id).await {
+            return Ok(roles);
+        }
+
+        let roles = Arc::new(self.admin().effective_roles(user_id).await?);
+
+        self.roles
+            .insert(
+                user_id,
+                roles.clone(),
+                self.config.load().admin.role_cache_ttl,
+            )
+            .await;
+
+        Ok(roles)
+    }
+}
@@ -0,0 +1,172 @@
+use std::{fmt, time::Duration};
+
+use serde::{Deserialize, Deserializer};
+use serde_with::DurationSeconds;
+
+use super::AdminClient;
+use crate::{telemetry, Config, ReCloak, Result};
+
+#[serde_with::serde_as]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RealmSettings {
+    pub realm: String,
+
+    #[serde(default)]
+    #[serde_as(as = "Option<DurationSeconds<u64>>")]
+    pub access_token_lifespan: Option<Duration>,
+
+    #[serde(default)]
+    #[serde_as(as = "Option<DurationSeconds<u64>>")]
+    pub access_code_lifespan: Option<Duration>,
+
+    #[serde(default)]
+    #[serde_as(as = "Option<DurationSeconds<u64>>")]
+    pub sso_session_idle_timeout: Option<Duration>,
+
+    #[serde(default)]
+    #[serde_as(as = "Option<DurationSeconds<u64>>")]
+    pub sso_session_max_lifespan: Option<Duration>,
+
+    #[serde(default)]
+    #[serde_as(as = "Option<DurationSeconds<u64>>")]
+    pub client_session_idle_timeout: Option<Duration>,
+
+    #[serde(default)]
+    #[serde_as(as = "Option<DurationSeconds<u64>>")]
+    pub client_session_max_lifespan: Option<Duration>,
+
+    #[serde(default)]
+    #[serde_as(as = "Option<DurationSeconds<u64>>")]
+    pub offline_session_idle_timeout: Option<Duration>,
+
+    #[serde(default)]
+    pub offline_session_max_lifespan_enabled: bool,
+
+    #[serde(default)]
+    #[serde_as(as = "Option<DurationSeconds<u64>>")]
+    pub offline_session_max_lifespan: Option<Duration>,
+
+    #[serde(default)]
+    pub revoke_refresh_token: bool,
+
+    #[serde(default)]
+    pub refresh_token_max_reuse: u32,
+
+    #[serde(default, deserialize_with = "deserialize_password_policy")]
+    pub password_policy: Vec<PasswordPolicyRule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswordPolicyRule {
+    pub name: String,
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettingConflict {
+    pub setting: &'static str,
+    pub configured: Duration,
+    pub realm_setting: &'static str,
+    pub realm_value: Duration,
+}
+
+impl RealmSettings {
+    #[inline]
+    pub fn password_rule(&self, name: impl AsRef<str>) -> Option<&str> {
+        self.password_policy
+            .iter()
+            .find(|rule| rule.name == name.as_ref())
+            .map(|rule| rule.value.as_deref().unwrap_or_default())
+    }
+
+    #[inline]
+    pub fn min_password_length(&self) -> Option<u32> {
+        self.password_rule("length")?.parse().ok()
+    }
+
+    pub fn conflicts(&self, config: &Config) -> Vec<SettingConflict> {
+        let Some(lifespan) =
+            self.access_token_lifespan.filter(|d| !d.is_zero())
+        else {
+            return Vec::new();
+        };
+
+        [
+            ("admin.role_cache_ttl", config.admin.role_cache_ttl),
+            ("introspection.cache_ttl", config.introspection.cache_ttl),
+        ]
+        .into_iter()
+        .filter(|(_, configured)| *configured > lifespan)
+        .map(|(setting, configured)| SettingConflict {
+            setting,
+            configured,
+            realm_setting: "accessTokenLifespan",
+            realm_value: lifespan,
+        })
+        .collect()
+    }
+}
+
+impl fmt::Display for SettingConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}s) exceeds the realm's {} ({}s)",
+            self.setting,
+            self.configured.as_secs(),
+            self.realm_setting,
+            self.realm_value.as_secs(),
+        )
+    }
+}
+
+impl AdminClient<'_> {
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn realm_settings(&self) -> Result<RealmSettings> {
+        self.get(&[]).await
+    }
+}
+
+impl ReCloak {
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn check_realm_settings(&self) -> Result<Vec<SettingConflict>> {
+        let settings = self.admin().realm_settings().await?;
+        let conflicts = settings.conflicts(&self.config.load());
+
+        for conflict in &conflicts {
+            telemetry::trace_warn!(%conflict, "configuration conflicts with realm settings");
+        }
+
+        Ok(conflicts)
+    }
+}
+
+fn deserialize_password_policy<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Vec<PasswordPolicyRule>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Some(policy) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(Vec::new());
+    };
+
+    Ok(policy
+        .split(" and ")
+        .map(str::trim)
+        .filter(|rule| !rule.is_empty())
+        .map(|rule| match rule.split_once('(') {
+            | Some((name, value)) => PasswordPolicyRule {
+                name: name.trim().to_owned(),
+                value: Some(value.trim_end_matches(')').to_owned())
+                    .filter(|value| value != "undefined"),
+            },
+            | None => PasswordPolicyRule {
+                name: rule.to_owned(),
+                value: None,
+            },
+        })
+        .collect())
+}
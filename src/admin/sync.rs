@@ -0,0 +1,257 @@
+use std::{fmt, sync::Arc, time::Duration};
+
+use futures_util::{stream, StreamExt};
+use tokio::{
+    sync::Mutex,
+    time::{Interval, MissedTickBehavior},
+};
+
+use super::AdminClient;
+use crate::{representations::UserRepresentation, telemetry, Error, Result};
+
+const DEFAULT_CONCURRENCY: usize = 4;
+
+type ProgressFn = Arc<dyn Fn(&SyncReport) + Send + Sync>;
+
+#[derive(Clone)]
+pub struct SyncOptions {
+    concurrency: usize,
+    interval: Option<Duration>,
+    update_existing: bool,
+    progress: Option<ProgressFn>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncOutcome {
+    Created,
+    Updated,
+    Unchanged,
+}
+
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+    pub unchanged: usize,
+    pub failures: Vec<SyncFailure>,
+}
+
+#[derive(Debug)]
+pub struct SyncFailure {
+    pub username: Option<String>,
+    pub error: Error,
+}
+
+impl SyncOptions {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    #[inline]
+    pub fn rate_limit(mut self, per_second: u32) -> Self {
+        self.interval =
+            (per_second > 0).then(|| Duration::from_secs(1) / per_second);
+        self
+    }
+
+    #[inline]
+    pub fn create_only(mut self) -> Self {
+        self.update_existing = false;
+        self
+    }
+
+    #[inline]
+    pub fn on_progress<F>(mut self, progress: F) -> Self
+    where
+        F: Fn(&SyncReport) + Send + Sync + 'static,
+    {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
+    fn pacer(&self) -> Option<Mutex<Interval>> {
+        self.interval.map(|period| {
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            Mutex::new(interval)
+        })
+    }
+}
+
+impl Default for SyncOptions {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            concurrency: DEFAULT_CONCURRENCY,
+            interval: None,
+            update_existing: true,
+            progress: None,
+        }
+    }
+}
+
+impl fmt::Debug for SyncOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SyncOptions")
+            .field("concurrency", &self.concurrency)
+            .field("interval", &self.interval)
+            .field("update_existing", &self.update_existing)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SyncReport {
+    #[inline]
+    pub fn processed(&self) -> usize {
+        self.created.len()
+            + self.updated.len()
+            + self.unchanged
+            + self.failures.len()
+    }
+
+    #[inline]
+    pub fn is_success(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    fn record(
+        &mut self,
+        username: Option<String>,
+        outcome: Result<SyncOutcome>,
+    ) {
+        match outcome {
+            | Ok(SyncOutcome::Created) => {
+                self.created.push(username.unwrap_or_default())
+            }
+            | Ok(SyncOutcome::Updated) => {
+                self.updated.push(username.unwrap_or_default())
+            }
+            | Ok(SyncOutcome::Unchanged) => self.unchanged += 1,
+            | Err(error) => self.failures.push(SyncFailure { username, error }),
+        }
+    }
+}
+
+impl AdminClient<'_> {
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, users)))]
+    pub async fn sync_users<I>(
+        &self,
+        users: I,
+        options: SyncOptions,
+    ) -> SyncReport
+    where
+        I: IntoIterator<Item = UserRepresentation>,
+    {
+        let pacer = options.pacer();
+        let update_existing = options.update_existing;
+
+        let mut outcomes = stream::iter(users)
+            .map(|user| {
+                let pacer = pacer.as_ref();
+
+                async move {
+                    if let Some(pacer) = pacer {
+                        pacer.lock().await.tick().await;
+                    }
+
+                    let outcome = self.sync_user(&user, update_existing).await;
+                    (user.username, outcome)
+                }
+            })
+            .buffer_unordered(options.concurrency);
+
+        let mut report = SyncReport::default();
+        while let Some((username, outcome)) = outcomes.next().await {
+            if let Err(ref err) = outcome {
+                telemetry::trace_warn!(?username, error = %err, "failed to sync user");
+            }
+
+            report.record(username, outcome);
+
+            if let Some(ref progress) = options.progress {
+                progress(&report);
+            }
+        }
+
+        telemetry::trace_info!(
+            created = report.created.len(),
+            updated = report.updated.len(),
+            unchanged = report.unchanged,
+            failed = report.failures.len(),
+            "user sync finished"
+        );
+
+        report
+    }
+
+    async fn sync_user(
+        &self,
+        user: &UserRepresentation,
+        update_existing: bool,
+    ) -> Result<SyncOutcome> {
+        let username = user
+            .username
+            .as_deref()
+            .ok_or(Error::InvalidUser("missing username"))?;
+
+        let Some(existing) = self.find_user(username).await? else {
+            self.create_user(user).await?;
+
+            return Ok(SyncOutcome::Created);
+        };
+
+        if !update_existing {
+            return Ok(SyncOutcome::Unchanged);
+        }
+
+        let Some(merged) = merge_user(&existing, user) else {
+            return Ok(SyncOutcome::Unchanged);
+        };
+
+        let id = existing
+            .id
+            .as_deref()
+            .ok_or(Error::InvalidUser("missing id"))?;
+        self.update_user(id, &merged).await?;
+
+        Ok(SyncOutcome::Updated)
+    }
+}
+
+fn merge_user(
+    existing: &UserRepresentation,
+    desired: &UserRepresentation,
+) -> Option<UserRepresentation> {
+    let mut merged = existing.clone();
+
+    overlay(&mut merged.email, &desired.email);
+    overlay(&mut merged.first_name, &desired.first_name);
+    overlay(&mut merged.last_name, &desired.last_name);
+    overlay(&mut merged.enabled, &desired.enabled);
+    overlay(&mut merged.email_verified, &desired.email_verified);
+
+    merged.attributes.extend(desired.attributes.clone());
+
+    for action in &desired.required_actions {
+        if !merged.required_actions.contains(action) {
+            merged.required_actions.push(action.clone());
+        }
+    }
+
+    (merged != *existing).then_some(merged)
+}
+
+#[inline]
+fn overlay<T: Clone>(target: &mut Option<T>, value: &Option<T>) {
+    if value.is_some() {
+        target.clone_from(value);
+    }
+}
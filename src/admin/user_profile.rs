@@ -0,0 +1,19 @@
+use super::AdminClient;
+use crate::{representations::UserProfileConfig, Result};
+
+impl AdminClient<'_> {
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn user_profile(&self) -> Result<UserProfileConfig> {
+        self.get(&["users", "profile"]).await
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, config)))]
+    pub async fn update_user_profile(
+        &self,
+        config: &UserProfileConfig,
+    ) -> Result<()> {
+        self.put(&["users", "profile"], config).await
+    }
+}
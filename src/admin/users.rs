@@ -0,0 +1,58 @@
+use reqwest::Method;
+
+use super::{created_id, AdminClient};
+use crate::{representations::UserRepresentation, Result};
+
+impl AdminClient<'_> {
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn user(&self, id: &str) -> Result<UserRepresentation> {
+        self.get(&["users", id]).await
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn find_user(
+        &self,
+        username: &str,
+    ) -> Result<Option<UserRepresentation>> {
+        let users = self
+            .send(self.request(Method::GET, &["users"]).query(&[
+                ("username", username),
+                ("exact", "true"),
+                ("briefRepresentation", "false"),
+            ]))
+            .await?
+            .json::<Vec<UserRepresentation>>()?;
+
+        Ok(users.into_iter().next())
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self, user), fields(username = ?user.username))
+    )]
+    pub async fn create_user(
+        &self,
+        user: &UserRepresentation,
+    ) -> Result<Option<String>> {
+        let resp = self.post(&["users"], user).await?;
+
+        Ok(created_id(&resp))
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, user)))]
+    pub async fn update_user(
+        &self,
+        id: &str,
+        user: &UserRepresentation,
+    ) -> Result<()> {
+        self.put(&["users", id], user).await
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn delete_user(&self, id: &str) -> Result<()> {
+        self.delete(&["users", id]).await
+    }
+}
@@ -0,0 +1,236 @@
+use base64::{
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+    Engine,
+};
+use jsonwebtoken::{
+    self as jwt,
+    jwk::{Jwk, JwkSet},
+    Algorithm,
+    EncodingKey,
+};
+use ring::{
+    rand::SystemRandom,
+    rsa::PublicKeyComponents,
+    signature::{
+        EcdsaKeyPair,
+        Ed25519KeyPair,
+        KeyPair,
+        RsaKeyPair,
+        ECDSA_P256_SHA256_FIXED_SIGNING,
+        ECDSA_P384_SHA384_FIXED_SIGNING,
+    },
+};
+use serde::Serialize;
+
+use crate::{
+    config::{ClientKey, ClientSecret},
+    Error,
+    ReCloak,
+    Result,
+};
+
+pub(crate) const JWT_BEARER_ASSERTION: &str =
+    "urn:ietf:params:oauth:client-assertion-type:jwt-bearer";
+
+const ASSERTION_LIFETIME_SECS: i64 = 60;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum ClientAuth<'a> {
+    Secret {
+        #[serde(rename = "client_secret")]
+        secret: &'a str,
+    },
+    Assertion {
+        client_assertion_type: &'static str,
+        client_assertion: String,
+    },
+}
+
+pub(crate) struct SigningKey {
+    key: EncodingKey,
+    header: jwt::Header,
+    public: Jwk,
+}
+
+impl ClientKey {
+    pub(crate) fn load(&self) -> Result<SigningKey> {
+        let pem = std::fs::read(&self.private_key).map_err(|err| {
+            Error::Config(format!(
+                "failed to read `{}`: {err}",
+                self.private_key.display()
+            ))
+        })?;
+
+        let (alg, mut public) = public_key(&pem)?;
+        let alg = self.algorithm.unwrap_or(alg);
+        public["alg"] = serde_json::json!(alg);
+        public["use"] = serde_json::json!("sig");
+
+        let key = match alg {
+            | Algorithm::ES256 | Algorithm::ES384 => {
+                EncodingKey::from_ec_pem(&pem)?
+            }
+            | Algorithm::EdDSA => EncodingKey::from_ed_pem(&pem)?,
+            | _ => EncodingKey::from_rsa_pem(&pem)?,
+        };
+
+        let mut public = serde_json::from_value::<Jwk>(public)
+            .map_err(|err| Error::Config(err.to_string()))?;
+        let kid = match self.key_id {
+            | Some(ref kid) => kid.clone(),
+            | None => crate::jwt::thumbprint(&public).ok_or_else(|| {
+                Error::Config("unsupported client key type".to_owned())
+            })?,
+        };
+        public.common.key_id = Some(kid.clone());
+
+        let mut header = jwt::Header::new(alg);
+        header.kid = Some(kid);
+
+        Ok(SigningKey {
+            key,
+            header,
+            public,
+        })
+    }
+
+    #[inline]
+    pub fn public_jwks(&self) -> Result<JwkSet> {
+        Ok(JwkSet {
+            keys: vec![self.load()?.public],
+        })
+    }
+}
+
+impl SigningKey {
+    fn assertion(
+        &self,
+        client_id: &str,
+        audience: &str,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<String> {
+        let issued_at = now.timestamp();
+        let claims = serde_json::json!({
+            "iss": client_id,
+            "sub": client_id,
+            "aud": audience,
+            "jti": uuid::Uuid::new_v4(),
+            "iat": issued_at,
+            "exp": issued_at + ASSERTION_LIFETIME_SECS,
+        });
+
+        Ok(jwt::encode(&self.header, &claims, &self.key)?)
+    }
+}
+
+impl ReCloak {
+    pub(crate) fn client_auth<'a>(
+        &self,
+        client_id: &str,
+        secret: &'a ClientSecret,
+        grant: &'static str,
+    ) -> Result<ClientAuth<'a>> {
+        match secret {
+            | ClientSecret::Basic(secret) => Ok(ClientAuth::Secret { secret }),
+            | ClientSecret::Jwt(key) => {
+                let audience = self.urls.load().issuer.to_string();
+                let client_assertion = key.load()?.assertion(
+                    client_id,
+                    &audience,
+                    self.clock.now(),
+                )?;
+
+                Ok(ClientAuth::Assertion {
+                    client_assertion_type: JWT_BEARER_ASSERTION,
+                    client_assertion,
+                })
+            }
+            | ClientSecret::Public => Err(Error::PublicClient {
+                client: client_id.to_owned(),
+                grant,
+            }),
+        }
+    }
+
+    pub fn client_jwks(&self) -> Result<JwkSet> {
+        match self.config.load().client.secret {
+            | ClientSecret::Jwt(ref key) => key.public_jwks(),
+            | _ => Err(Error::Config(
+                "client is not configured for private_key_jwt".to_owned(),
+            )),
+        }
+    }
+}
+
+fn public_key(pem: &[u8]) -> Result<(Algorithm, serde_json::Value)> {
+    let (label, der) = pem_der(pem).ok_or_else(|| {
+        Error::Config("client key is not a valid pem file".to_owned())
+    })?;
+
+    let rsa = match label.as_str() {
+        | "RSA PRIVATE KEY" => RsaKeyPair::from_der(&der).ok(),
+        | _ => RsaKeyPair::from_pkcs8(&der).ok(),
+    };
+    if let Some(rsa) = rsa {
+        let components: PublicKeyComponents<Vec<u8>> = rsa.public().into();
+
+        return Ok((
+            Algorithm::RS256,
+            serde_json::json!({
+                "kty": "RSA",
+                "n": URL_SAFE_NO_PAD.encode(components.n),
+                "e": URL_SAFE_NO_PAD.encode(components.e),
+            }),
+        ));
+    }
+
+    let rng = SystemRandom::new();
+    for (alg, signing, curve) in [
+        (Algorithm::ES256, &ECDSA_P256_SHA256_FIXED_SIGNING, "P-256"),
+        (Algorithm::ES384, &ECDSA_P384_SHA384_FIXED_SIGNING, "P-384"),
+    ] {
+        if let Ok(ec) = EcdsaKeyPair::from_pkcs8(signing, &der, &rng) {
+            // uncompressed point: 0x04 || x || y
+            let point = &ec.public_key().as_ref()[1..];
+            let (x, y) = point.split_at(point.len() / 2);
+
+            return Ok((
+                alg,
+                serde_json::json!({
+                    "kty": "EC",
+                    "crv": curve,
+                    "x": URL_SAFE_NO_PAD.encode(x),
+                    "y": URL_SAFE_NO_PAD.encode(y),
+                }),
+            ));
+        }
+    }
+
+    if let Ok(ed) = Ed25519KeyPair::from_pkcs8_maybe_unchecked(&der) {
+        return Ok((
+            Algorithm::EdDSA,
+            serde_json::json!({
+                "kty": "OKP",
+                "crv": "Ed25519",
+                "x": URL_SAFE_NO_PAD.encode(ed.public_key()),
+            }),
+        ));
+    }
+
+    Err(Error::Config("unsupported client key type".to_owned()))
+}
+
+fn pem_der(pem: &[u8]) -> Option<(String, Vec<u8>)> {
+    let pem = std::str::from_utf8(pem).ok()?;
+    let (_, rest) = pem.split_once("-----BEGIN ")?;
+    let (label, rest) = rest.split_once("-----")?;
+    let (body, _) = rest.split_once("-----END ")?;
+
+    let body = body
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>();
+
+    Some((label.to_owned(), STANDARD.decode(body).ok()?))
+}
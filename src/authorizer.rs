@@ -0,0 +1,287 @@
+use arcstr::ArcStr;
+
+use crate::Claims;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Authorizer;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Requirement {
+    RealmRole(ArcStr),
+    ClientRole { client_id: ArcStr, role: ArcStr },
+    Scope(ArcStr),
+    Acr(ArcStr),
+    Organization(ArcStr),
+    AnyOf(Vec<Requirement>),
+    AllOf(Vec<Requirement>),
+    Not(Box<Requirement>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decision {
+    Allow,
+    Deny(DenyReason),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DenyReason {
+    MissingRealmRole(ArcStr),
+    MissingClientRole { client_id: ArcStr, role: ArcStr },
+    MissingScope(ArcStr),
+    InsufficientAcr(ArcStr),
+    MissingOrganization(ArcStr),
+    EmailNotVerified,
+    NoneSatisfied,
+    Excluded,
+    Policy(ArcStr),
+    Rejected(ArcStr),
+}
+
+impl Authorizer {
+    pub fn decide(
+        &self,
+        claims: &Claims,
+        requirement: &Requirement,
+    ) -> Decision {
+        use Requirement::*;
+
+        match requirement {
+            | RealmRole(role) => {
+                Decision::check(claims.has_realm_role(role), || {
+                    DenyReason::MissingRealmRole(role.clone())
+                })
+            }
+            | ClientRole { client_id, role } => {
+                Decision::check(claims.has_role(client_id, role), || {
+                    DenyReason::MissingClientRole {
+                        client_id: client_id.clone(),
+                        role: role.clone(),
+                    }
+                })
+            }
+            | Scope(scope) => Decision::check(claims.has_scope(scope), || {
+                DenyReason::MissingScope(scope.clone())
+            }),
+            | Acr(level) => Decision::check(claims.meets_acr(level), || {
+                DenyReason::InsufficientAcr(level.clone())
+            }),
+            | Organization(alias) => {
+                Decision::check(claims.has_organization(alias), || {
+                    DenyReason::MissingOrganization(alias.clone())
+                })
+            }
+            | AnyOf(requirements) => {
+                let allowed = requirements
+                    .iter()
+                    .any(|r| self.decide(claims, r).is_allowed());
+
+                Decision::check(allowed, || DenyReason::NoneSatisfied)
+            }
+            | AllOf(requirements) => requirements
+                .iter()
+                .map(|r| self.decide(claims, r))
+                .find(|d| !d.is_allowed())
+                .unwrap_or(Decision::Allow),
+            | Not(requirement) => Decision::check(
+                !self.decide(claims, requirement).is_allowed(),
+                || DenyReason::Excluded,
+            ),
+        }
+    }
+}
+
+impl Requirement {
+    #[inline]
+    pub fn realm_role(role: impl Into<ArcStr>) -> Self {
+        Self::RealmRole(role.into())
+    }
+
+    #[inline]
+    pub fn client_role(
+        client_id: impl Into<ArcStr>,
+        role: impl Into<ArcStr>,
+    ) -> Self {
+        Self::ClientRole {
+            client_id: client_id.into(),
+            role: role.into(),
+        }
+    }
+
+    #[inline]
+    pub fn scope(scope: impl Into<ArcStr>) -> Self {
+        Self::Scope(scope.into())
+    }
+
+    #[inline]
+    pub fn acr(level: impl Into<ArcStr>) -> Self {
+        Self::Acr(level.into())
+    }
+
+    #[inline]
+    pub fn organization(alias: impl Into<ArcStr>) -> Self {
+        Self::Organization(alias.into())
+    }
+
+    #[inline]
+    pub fn any_of(requirements: impl IntoIterator<Item = Self>) -> Self {
+        Self::AnyOf(requirements.into_iter().collect())
+    }
+
+    #[inline]
+    pub fn all_of(requirements: impl IntoIterator<Item = Self>) -> Self {
+        Self::AllOf(requirements.into_iter().collect())
+    }
+
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(requirement: Self) -> Self {
+        Self::Not(Box::new(requirement))
+    }
+
+    pub fn and(self, rhs: Self) -> Self {
+        match self {
+            | Self::AllOf(mut requirements) => {
+                requirements.push(rhs);
+                Self::AllOf(requirements)
+            }
+            | lhs => Self::AllOf(vec![lhs, rhs]),
+        }
+    }
+
+    pub fn or(self, rhs: Self) -> Self {
+        match self {
+            | Self::AnyOf(mut requirements) => {
+                requirements.push(rhs);
+                Self::AnyOf(requirements)
+            }
+            | lhs => Self::AnyOf(vec![lhs, rhs]),
+        }
+    }
+}
+
+impl Decision {
+    #[inline]
+    fn check(allowed: bool, reason: impl FnOnce() -> DenyReason) -> Self {
+        if allowed {
+            Self::Allow
+        } else {
+            Self::Deny(reason())
+        }
+    }
+
+    #[inline]
+    pub const fn is_allowed(&self) -> bool {
+        matches!(self, Self::Allow)
+    }
+
+    #[inline]
+    pub fn into_result(self) -> Result<(), DenyReason> {
+        match self {
+            | Self::Allow => Ok(()),
+            | Self::Deny(reason) => Err(reason),
+        }
+    }
+}
+
+impl DenyReason {
+    #[inline]
+    pub const fn is_authentication(&self) -> bool {
+        matches!(self, Self::InsufficientAcr(_))
+    }
+}
+
+impl std::fmt::Display for DenyReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use DenyReason::*;
+
+        match self {
+            | MissingRealmRole(role) => {
+                write!(f, "missing realm role `{role}`")
+            }
+            | MissingClientRole { client_id, role } => {
+                write!(f, "missing client role `{client_id}:{role}`")
+            }
+            | MissingScope(scope) => write!(f, "missing scope `{scope}`"),
+            | InsufficientAcr(_) => {
+                write!(f, "insufficient authentication level")
+            }
+            | MissingOrganization(_) => {
+                write!(f, "organization membership required")
+            }
+            | EmailNotVerified => write!(f, "email address is not verified"),
+            | NoneSatisfied => {
+                write!(f, "no alternative requirement satisfied")
+            }
+            | Excluded => write!(f, "excluded by requirement"),
+            | Policy(reason) => write!(f, "denied by policy: {reason}"),
+            | Rejected(reason) => write!(f, "rejected: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for DenyReason {}
+
+#[cfg(test)]
+mod tests {
+    use super::{Authorizer, Decision, DenyReason, Requirement};
+    use crate::testing;
+
+    fn claims() -> crate::Claims {
+        let mut claims = testing::claims(chrono::Utc::now());
+        claims["resource_access"] =
+            serde_json::json!({ "app": { "roles": ["reader"] } });
+        claims["scope"] = "openid profile".into();
+
+        serde_json::from_value(claims).unwrap()
+    }
+
+    fn decide(requirement: &Requirement) -> Decision {
+        Authorizer.decide(&claims(), requirement)
+    }
+
+    #[test]
+    fn checks_roles_and_scopes() {
+        assert!(decide(&Requirement::realm_role("user")).is_allowed());
+        assert!(decide(&Requirement::client_role("app", "reader")).is_allowed());
+        assert!(decide(&Requirement::scope("profile")).is_allowed());
+
+        assert_eq!(
+            decide(&Requirement::client_role("app", "writer")),
+            Decision::Deny(DenyReason::MissingClientRole {
+                client_id: "app".into(),
+                role: "writer".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn composes_requirements() {
+        let admin = Requirement::realm_role("admin");
+        let user = Requirement::realm_role("user");
+
+        assert!(decide(&admin.clone().or(user.clone())).is_allowed());
+        assert_eq!(
+            decide(&user.clone().and(admin.clone())),
+            Decision::Deny(DenyReason::MissingRealmRole("admin".into()))
+        );
+        assert_eq!(
+            decide(&Requirement::any_of([admin.clone()])),
+            Decision::Deny(DenyReason::NoneSatisfied)
+        );
+        assert_eq!(
+            decide(&Requirement::not(user)),
+            Decision::Deny(DenyReason::Excluded)
+        );
+        assert!(decide(&Requirement::not(admin)).is_allowed());
+    }
+
+    #[test]
+    fn classifies_step_up_denials_as_authentication() {
+        let Decision::Deny(reason) = decide(&Requirement::acr("gold")) else {
+            panic!("expected the acr requirement to be denied");
+        };
+
+        assert!(reason.is_authentication());
+        assert!(!DenyReason::MissingScope("admin".into()).is_authentication());
+    }
+}
@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AuthorizationClaim {
+    #[serde(rename = "permissions", default)]
+    pub permissions: Vec<Permission>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Permission {
+    #[serde(rename = "rsid")]
+    pub resource_id: Option<String>,
+
+    #[serde(rename = "rsname")]
+    pub resource_name: Option<String>,
+
+    #[serde(rename = "scopes", default)]
+    pub scopes: Vec<String>,
+
+    #[serde(rename = "claims", default)]
+    pub claims: HashMap<String, Vec<String>>,
+}
+
+impl AuthorizationClaim {
+    #[inline]
+    pub fn permission(&self, resource: impl AsRef<str>) -> Option<&Permission> {
+        self.permissions
+            .iter()
+            .find(|p| p.is_for(resource.as_ref()))
+    }
+}
+
+impl Permission {
+    #[inline]
+    pub fn is_for(&self, resource: impl AsRef<str>) -> bool {
+        let resource = resource.as_ref();
+
+        self.resource_id.as_deref() == Some(resource)
+            || self.resource_name.as_deref() == Some(resource)
+    }
+
+    #[inline]
+    pub fn has_scope(&self, scope: impl AsRef<str>) -> bool {
+        self.scopes.iter().any(|s| s == scope.as_ref())
+    }
+}
+
+impl crate::Claims {
+    #[inline]
+    pub fn granted(&self, resource: impl AsRef<str>) -> bool {
+        self.authorization
+            .as_ref()
+            .and_then(|authz| authz.permission(resource))
+            .is_some()
+    }
+
+    #[inline]
+    pub fn granted_scope(
+        &self,
+        resource: impl AsRef<str>,
+        scope: impl AsRef<str>,
+    ) -> bool {
+        self.authorization
+            .as_ref()
+            .map(|authz| {
+                authz
+                    .permissions
+                    .iter()
+                    .filter(|p| p.is_for(resource.as_ref()))
+                    .any(|p| p.has_scope(scope.as_ref()))
+            })
+            .unwrap_or(false)
+    }
+}
@@ -0,0 +1,49 @@
+use std::{fmt, future::Future, pin::Pin, sync::Arc};
+
+use crate::Result;
+
+pub type BearerFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<OAuthBearerToken>> + Send + 'a>>;
+
+#[derive(Debug, Clone)]
+pub struct OAuthBearerToken {
+    pub token: arcstr::ArcStr,
+    pub principal: String,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+pub trait OAuthBearerProvider: fmt::Debug + Send + Sync + 'static {
+    fn bearer_token(&self) -> BearerFuture<'_>;
+}
+
+impl OAuthBearerProvider for crate::ReCloak {
+    fn bearer_token(&self) -> BearerFuture<'_> {
+        Box::pin(async move {
+            let token = self.authenticate().await?;
+            let expires_at = self
+                .token
+                .read()
+                .await
+                .as_ref()
+                .filter(|current| current.access_token == token)
+                .map(crate::TokenResponse::expires_at)
+                .unwrap_or_else(|| self.clock.now());
+
+            Ok(OAuthBearerToken {
+                token,
+                principal: self.config.load().client.id.clone(),
+                expires_at,
+            })
+        })
+    }
+}
+
+impl<P> OAuthBearerProvider for Arc<P>
+where
+    P: OAuthBearerProvider + ?Sized,
+{
+    #[inline]
+    fn bearer_token(&self) -> BearerFuture<'_> {
+        (**self).bearer_token()
+    }
+}
@@ -0,0 +1,301 @@
+use std::{fmt, sync::Arc};
+
+use arc_swap::{ArcSwap, ArcSwapOption};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+
+use crate::{
+    admin,
+    clock::{Clock, SystemClock},
+    discovery,
+    enrich,
+    exchange,
+    governor::Governor,
+    introspect,
+    jti::JtiStore,
+    jwks::{self, SharedDecoder},
+    principal::RoleRegistry,
+    secret::{FileSecret, SecretSource},
+    telemetry,
+    validator::ClaimsValidators,
+    ClaimsValidator,
+    ClientCredentials,
+    Config,
+    Error,
+    HopTokens,
+    HttpTransport,
+    ReCloak,
+    Result,
+    TransportRequest,
+};
+
+#[derive(Clone)]
+pub(crate) struct RequestDecorator(
+    Arc<dyn Fn(&mut TransportRequest) + Send + Sync>,
+);
+
+#[derive(Debug)]
+pub struct ReCloakBuilder {
+    config: Config,
+    transport: Option<Arc<dyn HttpTransport>>,
+    decorator: Option<RequestDecorator>,
+    clock: Option<Arc<dyn Clock>>,
+    decoder: Option<SharedDecoder>,
+    secret_source: Option<Arc<dyn SecretSource>>,
+    validators: ClaimsValidators,
+    jti_store: Option<Arc<dyn JtiStore>>,
+    hop_tokens: Option<Arc<HopTokens>>,
+}
+
+impl ReCloakBuilder {
+    #[inline]
+    pub(crate) const fn new(config: Config) -> Self {
+        Self {
+            config,
+            transport: None,
+            decorator: None,
+            clock: None,
+            decoder: None,
+            secret_source: None,
+            validators: ClaimsValidators::new(),
+            jti_store: None,
+            hop_tokens: None,
+        }
+    }
+
+    #[inline]
+    pub fn transport(mut self, transport: impl HttpTransport) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    #[inline]
+    pub fn decorator<F>(mut self, decorator: F) -> Self
+    where
+        F: Fn(&mut TransportRequest) + Send + Sync + 'static,
+    {
+        self.decorator = Some(RequestDecorator(Arc::new(decorator)));
+        self
+    }
+
+    #[inline]
+    pub fn credentials(
+        mut self,
+        name: impl Into<String>,
+        credentials: ClientCredentials,
+    ) -> Self {
+        self.config.credentials.insert(name.into(), credentials);
+        self
+    }
+
+    #[inline]
+    pub fn decoder(mut self, decoder: SharedDecoder) -> Self {
+        self.decoder = Some(decoder);
+        self
+    }
+
+    #[inline]
+    pub fn secret_source(mut self, source: impl SecretSource) -> Self {
+        self.secret_source = Some(Arc::new(source));
+        self
+    }
+
+    #[inline]
+    pub fn claims_validator(mut self, validator: impl ClaimsValidator) -> Self {
+        self.validators.push(validator);
+        self
+    }
+
+    #[inline]
+    pub fn jti_store(mut self, store: impl JtiStore) -> Self {
+        self.jti_store = Some(Arc::new(store));
+        self
+    }
+
+    #[inline]
+    pub fn hop_tokens(mut self, hop_tokens: HopTokens) -> Self {
+        self.hop_tokens = Some(Arc::new(hop_tokens));
+        self
+    }
+
+    #[inline]
+    pub fn clock(mut self, clock: impl Clock) -> Self {
+        self.clock = Some(Arc::new(clock));
+        self
+    }
+
+    pub async fn build(self) -> Result<Arc<ReCloak>> {
+        let Self {
+            config,
+            transport,
+            decorator,
+            clock,
+            decoder,
+            secret_source,
+            validators,
+            jti_store,
+            hop_tokens,
+        } = self;
+
+        telemetry::trace_debug!(
+            agent = %config.http.user_agent,
+            auth_server_url = %config.http.auth_server_url,
+            realm = %config.client.realm,
+            client_id = %config.client.id,
+            "creating keycloak client",
+        );
+
+        check_key_material(&config)?;
+
+        if let Some(ref store) = jti_store {
+            store.check()?;
+        }
+
+        let secret_source = secret_source.or_else(|| {
+            config.client.secret_file.as_ref().map(|path| {
+                Arc::new(FileSecret::new(path)) as Arc<dyn SecretSource>
+            })
+        });
+
+        let clock = clock.unwrap_or_else(|| Arc::new(SystemClock));
+
+        #[cfg(feature = "dev")]
+        let dev = match config.dev {
+            | Some(_) => {
+                telemetry::trace_warn!(
+                    "using the embedded dev issuer, no requests will reach \
+                     keycloak"
+                );
+
+                Some(crate::DevIssuer::new(&config, clock.clone())?)
+            }
+            | None => None,
+        };
+        #[cfg(feature = "dev")]
+        let transport = match dev {
+            | Some(ref dev) => {
+                Some(Arc::new(dev.clone()) as Arc<dyn HttpTransport>)
+            }
+            | None => transport,
+        };
+
+        let client = ReCloak::http_client(&config)?;
+        let transport = transport.unwrap_or_else(|| Arc::new(client.clone()));
+        let headers = default_headers(&config)?;
+        let jwks_client = jwks::tls_client(&config)?;
+
+        let governor =
+            Governor::new(&config.governor, transport, headers, decorator)?;
+        let config = discovery::resolve(&client, &governor, config).await?;
+        let urls = config.urls()?;
+        let principals = RoleRegistry::new(&config.principal)?;
+        let config = Arc::new(config);
+        let decoder = match decoder {
+            | Some(decoder) => decoder,
+            | None => {
+                let jwks = jwks::fetch_initial(
+                    &client,
+                    &governor,
+                    urls.jwks.clone(),
+                    jwks_client.as_ref(),
+                )
+                .await?;
+                let federated =
+                    jwks::fetch_federated(&client, &governor, &config).await?;
+
+                SharedDecoder::new(
+                    jwks,
+                    federated,
+                    config.clone(),
+                    clock.clone(),
+                )
+            }
+        };
+
+        let roles = admin::RoleCache::new(config.admin.role_cache_capacity);
+        let introspections = introspect::IntrospectionCache::new(
+            config.introspection.cache_capacity,
+        );
+        let enrichments =
+            enrich::EnrichmentCache::new(config.introspection.cache_capacity);
+        let exchanges =
+            exchange::ExchangeCache::new(config.introspection.cache_capacity);
+
+        Ok(Arc::new(ReCloak {
+            config: ArcSwap::new(config),
+            client,
+            jwks_client: ArcSwapOption::new(jwks_client.map(Arc::new)),
+            decoder,
+            urls: ArcSwap::from_pointee(urls),
+            token: Default::default(),
+            token_updates: tokio::sync::watch::Sender::new(None),
+            tokens: Default::default(),
+            scoped_tokens: Default::default(),
+            secret_source,
+            validators,
+            jti_store,
+            hop_tokens,
+            roles,
+            principals: ArcSwap::from_pointee(principals),
+            introspections,
+            enrichments,
+            exchanges,
+            governor,
+            clock,
+            #[cfg(feature = "dev")]
+            dev,
+        }))
+    }
+}
+
+impl RequestDecorator {
+    #[inline]
+    pub(crate) fn apply(&self, req: &mut TransportRequest) {
+        (self.0)(req)
+    }
+}
+
+impl fmt::Debug for RequestDecorator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RequestDecorator")
+    }
+}
+
+#[cfg_attr(not(any(feature = "x5c", feature = "jwe")), allow(unused_variables))]
+pub(crate) fn check_key_material(config: &Config) -> Result<()> {
+    if let crate::config::ClientSecret::Jwt(ref key) = config.client.secret {
+        key.load()?;
+    }
+
+    #[cfg(feature = "x5c")]
+    if let Some(ref path) = config.token.trusted_ca {
+        crate::x5c::TrustStore::load(path)?;
+    }
+
+    #[cfg(feature = "jwe")]
+    if let Some(ref path) = config.token.decryption_key {
+        crate::jwe::Decryptor::load(path)?;
+    }
+
+    Ok(())
+}
+
+fn default_headers(config: &Config) -> Result<HeaderMap> {
+    config
+        .http
+        .headers
+        .iter()
+        .map(|(name, value)| {
+            let name = HeaderName::try_from(name.as_str()).map_err(|err| {
+                Error::Config(format!("invalid header name `{name}`: {err}"))
+            })?;
+            let value =
+                HeaderValue::try_from(value.as_str()).map_err(|err| {
+                    Error::Config(format!(
+                        "invalid value for header `{name}`: {err}"
+                    ))
+                })?;
+
+            Ok((name, value))
+        })
+        .collect()
+}
@@ -0,0 +1,251 @@
+use std::{
+    borrow::Borrow,
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+    time::{Duration, Instant},
+};
+
+use tokio::sync::RwLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CacheFull;
+
+// the sequence number breaks ties between entries expiring at the same instant.
+type Deadline = (Instant, u64);
+
+#[derive(Debug)]
+pub(crate) struct TtlCache<K, V> {
+    entries: RwLock<Entries<K, V>>,
+    capacity: usize,
+}
+
+#[derive(Debug)]
+struct Entries<K, V> {
+    map: HashMap<K, (Deadline, V)>,
+    expiry: BTreeMap<Deadline, K>,
+    seq: u64,
+}
+
+impl<K, V> Default for Entries<K, V> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            map: HashMap::new(),
+            expiry: BTreeMap::new(),
+            seq: 0,
+        }
+    }
+}
+
+impl<K, V> Entries<K, V>
+where
+    K: Eq + Hash + Clone,
+{
+    #[inline]
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    #[inline]
+    fn contains_key(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    #[inline]
+    fn expires_at(&self, key: &K) -> Option<Instant> {
+        self.map.get(key).map(|((expires_at, _), _)| *expires_at)
+    }
+
+    fn insert(&mut self, key: K, expires_at: Instant, value: V) {
+        self.seq += 1;
+
+        let deadline = (expires_at, self.seq);
+        self.expiry.insert(deadline, key.clone());
+
+        if let Some((old, _)) = self.map.insert(key, (deadline, value)) {
+            self.expiry.remove(&old);
+        }
+    }
+
+    fn purge_expired(&mut self, now: Instant) {
+        while let Some(entry) = self.expiry.first_entry() {
+            if entry.key().0 > now {
+                break;
+            }
+
+            self.map.remove(&entry.remove());
+        }
+    }
+
+    fn evict_oldest(&mut self) {
+        if let Some((_, key)) = self.expiry.pop_first() {
+            self.map.remove(&key);
+        }
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.map.clear();
+        self.expiry.clear();
+    }
+}
+
+impl<K, V> TtlCache<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    #[inline]
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            entries: Default::default(),
+            capacity,
+        }
+    }
+
+    pub(crate) async fn get<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.entries
+            .read()
+            .await
+            .map
+            .get(key)
+            .filter(|((expires_at, _), _)| *expires_at > Instant::now())
+            .map(|(_, value)| value.clone())
+    }
+
+    pub(crate) async fn insert(&self, key: K, value: V, ttl: Duration) {
+        if ttl.is_zero() || self.capacity == 0 {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut entries = self.entries.write().await;
+        self.make_room(&mut entries, now, true);
+
+        entries.insert(key, now + ttl, value);
+    }
+
+    // unlike `insert`, never evicts live entries to make room.
+    pub(crate) async fn try_insert(
+        &self,
+        key: K,
+        value: V,
+        ttl: Duration,
+    ) -> Result<(), CacheFull> {
+        let now = Instant::now();
+        let mut entries = self.entries.write().await;
+
+        if !entries.contains_key(&key)
+            && !self.make_room(&mut entries, now, false)
+        {
+            return Err(CacheFull);
+        }
+
+        entries.insert(key, now + ttl, value);
+
+        Ok(())
+    }
+
+    pub(crate) async fn insert_new(
+        &self,
+        key: K,
+        value: V,
+        ttl: Duration,
+    ) -> Result<bool, CacheFull> {
+        let now = Instant::now();
+        let mut entries = self.entries.write().await;
+
+        if entries
+            .expires_at(&key)
+            .is_some_and(|expires_at| expires_at > now)
+        {
+            return Ok(false);
+        }
+
+        if !entries.contains_key(&key)
+            && !self.make_room(&mut entries, now, false)
+        {
+            return Err(CacheFull);
+        }
+
+        entries.insert(key, now + ttl, value);
+
+        Ok(true)
+    }
+
+    fn make_room(
+        &self,
+        entries: &mut Entries<K, V>,
+        now: Instant,
+        evict_live: bool,
+    ) -> bool {
+        if entries.len() >= self.capacity {
+            entries.purge_expired(now);
+        }
+
+        if entries.len() >= self.capacity && evict_live {
+            entries.evict_oldest();
+        }
+
+        entries.len() < self.capacity
+    }
+
+    #[inline]
+    pub(crate) async fn clear(&self) {
+        self.entries.write().await.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{CacheFull, TtlCache};
+
+    const SHORT: Duration = Duration::from_secs(10);
+    const LONG: Duration = Duration::from_secs(60);
+
+    #[tokio::test]
+    async fn evicts_the_soonest_expiring_entry() {
+        let cache = TtlCache::new(2);
+        cache.insert("a", 1, SHORT).await;
+        cache.insert("b", 2, LONG).await;
+        cache.insert("c", 3, LONG).await;
+
+        assert_eq!(cache.get("a").await, None);
+        assert_eq!(cache.get("b").await, Some(2));
+        assert_eq!(cache.get("c").await, Some(3));
+    }
+
+    #[tokio::test]
+    async fn reinserting_moves_the_deadline() {
+        let cache = TtlCache::new(2);
+        cache.insert("a", 1, SHORT).await;
+        cache.insert("b", 2, LONG).await;
+        cache.insert("a", 1, LONG * 2).await;
+        cache.insert("c", 3, LONG).await;
+
+        assert_eq!(cache.get("a").await, Some(1));
+        assert_eq!(cache.get("b").await, None);
+        assert_eq!(cache.entries.read().await.expiry.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn reclaims_expired_entries_without_evicting_live_ones() {
+        let cache = TtlCache::new(2);
+        cache.insert("a", 1, Duration::from_millis(1)).await;
+        cache.insert("b", 2, LONG).await;
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        assert_eq!(cache.try_insert("c", 3, LONG).await, Ok(()));
+        assert_eq!(cache.try_insert("d", 4, LONG).await, Err(CacheFull));
+        assert_eq!(cache.insert_new("b", 2, LONG).await, Ok(false));
+
+        cache.clear().await;
+        assert_eq!(cache.entries.read().await.expiry.len(), 0);
+    }
+}
@@ -1,34 +1,206 @@
+use std::{collections::HashMap, fmt, time::Duration};
+
 use serde::Deserialize;
+use serde_with::{DurationMilliSeconds, DurationSeconds};
 use url::Url;
 
 use crate::Result;
 
-#[derive(Debug, Deserialize)]
+pub(crate) const OFFLINE_ACCESS_SCOPE: &str = "offline_access";
+const REDACTED: &str = "[redacted]";
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct Config {
+    #[serde(default)]
+    pub provider: Provider,
+
     pub client: ClientConfig,
     pub token: TokenConfig,
     pub http: HttpConfig,
+
+    #[serde(default)]
+    pub credentials: HashMap<String, ClientCredentials>,
+
+    #[serde(default)]
+    pub endpoints: EndpointOverrides,
+
+    #[serde(default)]
+    pub admin: AdminConfig,
+
+    #[serde(default)]
+    pub introspection: IntrospectionConfig,
+
+    #[serde(default)]
+    pub governor: GovernorConfig,
+
+    #[serde(default)]
+    pub logging: LoggingConfig,
+
+    #[serde(default)]
+    pub principal: PrincipalConfig,
+
+    #[cfg(feature = "dev")]
+    #[serde(default)]
+    pub dev: Option<DevConfig>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct ClientConfig {
     pub id: String,
+    #[serde(default)]
     pub secret: ClientSecret,
+    pub secret_file: Option<std::path::PathBuf>,
     #[serde(default = "default_scope")]
     pub scope: String,
+    #[serde(default)]
     pub realm: String,
+
+    #[serde(default)]
+    pub offline_access: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClientCredentials {
+    pub id: String,
+    #[serde(default)]
+    pub secret: ClientSecret,
+    #[serde(default = "default_scope")]
+    pub scope: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct TokenConfig {
     pub issuer: Option<Vec<String>>,
     pub audience: Option<Vec<String>>,
+
+    #[serde(default)]
+    pub validation: ValidationMode,
+
+    pub hs_secret: Option<SecretString>,
+
+    #[serde(default)]
+    pub require_bearer: bool,
+
+    #[serde(default)]
+    pub require_username: bool,
+
+    #[serde(default)]
+    pub require_roles: bool,
+
+    // keycloak omits `resource_access` for users without client roles, so an
+    // empty claim counts as missing.
+    #[serde(default)]
+    pub require_resource_access: bool,
+
+    pub allowed_parties: Option<Vec<String>>,
+
+    #[serde(default)]
+    pub limits: TokenLimits,
+
+    pub offload: Option<OffloadConfig>,
+
+    pub enrichment: Option<EnrichmentSource>,
+
+    #[serde(default)]
+    pub trusted_issuers: Vec<TrustedIssuer>,
+
+    pub key_pinning: Option<KeyPinning>,
+
+    pub jwks_tls: Option<JwksTlsConfig>,
+
+    #[cfg(feature = "x5c")]
+    pub trusted_ca: Option<std::path::PathBuf>,
+
+    #[cfg(feature = "jwe")]
+    pub decryption_key: Option<std::path::PathBuf>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TrustedIssuer {
+    pub issuer: String,
+    pub jwks_url: Url,
+    pub audience: Option<Vec<String>>,
+    pub algorithms: Option<Vec<jsonwebtoken::Algorithm>>,
+    pub allowed_parties: Option<Vec<String>>,
+    pub key_pinning: Option<KeyPinning>,
+
+    #[serde(default)]
+    pub require_username: bool,
+
+    #[serde(default)]
+    pub require_roles: bool,
+
+    #[serde(default)]
+    pub require_resource_access: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct KeyPinning {
+    #[serde(default)]
+    pub key_ids: Vec<String>,
+
+    #[serde(default)]
+    pub thumbprints: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct JwksTlsConfig {
+    pub certificate: std::path::PathBuf,
+    pub private_key: std::path::PathBuf,
+    pub ca_certificate: Option<std::path::PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Provider {
+    #[default]
+    Keycloak,
+    Generic,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationMode {
+    #[default]
+    Local,
+    Introspection,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnrichmentSource {
+    Introspection,
+    Userinfo,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct TokenLimits {
+    #[serde(default = "default_token_max_size")]
+    pub max_token_size: usize,
+
+    #[serde(default = "default_token_max_header_size")]
+    pub max_header_size: usize,
+
+    #[serde(default = "default_token_max_kid_length")]
+    pub max_kid_length: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OffloadConfig {
+    #[serde(default)]
+    pub min_token_size: usize,
+
+    pub algorithms: Option<Vec<jsonwebtoken::Algorithm>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct HttpConfig {
     pub auth_server_url: Url,
 
+    pub public_issuer_url: Option<Url>,
+
+    pub management_url: Option<Url>,
+
     #[serde(default = "default_http_user_agent")]
     pub user_agent: String,
 
@@ -37,12 +209,150 @@ pub struct HttpConfig {
 
     #[serde(default = "default_http_allow_insecure")]
     pub allow_insecure: bool,
+
+    pub proxy: Option<ProxyConfig>,
+
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProxyConfig {
+    pub url: Url,
+
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+
+    pub username: Option<String>,
+    pub password: Option<SecretString>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EndpointOverrides {
+    pub issuer_url: Option<Url>,
+    pub authorization_url: Option<Url>,
+    pub token_url: Option<Url>,
+    pub jwks_url: Option<Url>,
+    pub introspection_url: Option<Url>,
+    pub userinfo_url: Option<Url>,
+    pub registration_url: Option<Url>,
+}
+
+#[serde_with::serde_as]
+#[derive(Debug, Clone, Deserialize)]
+pub struct AdminConfig {
+    #[serde(default = "default_admin_role_cache_ttl")]
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub role_cache_ttl: Duration,
+
+    #[serde(default = "default_admin_role_cache_capacity")]
+    pub role_cache_capacity: usize,
+}
+
+#[serde_with::serde_as]
+#[derive(Debug, Clone, Deserialize)]
+pub struct IntrospectionConfig {
+    #[serde(default = "default_introspection_cache_capacity")]
+    pub cache_capacity: usize,
+
+    #[serde(default = "default_introspection_cache_ttl")]
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub cache_ttl: Duration,
+
+    #[serde(default = "default_introspection_negative_ttl")]
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub negative_ttl: Duration,
+
+    #[serde(default)]
+    pub opaque_tokens: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PrincipalConfig {
+    #[serde(default)]
+    pub realm_roles: Vec<String>,
+
+    #[serde(default)]
+    pub client_roles: HashMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub struct LoggingConfig {
+    #[serde(default)]
+    pub redact_subject: bool,
+
+    #[serde(default)]
+    pub redact_username: bool,
+}
+
+#[serde_with::serde_as]
+#[derive(Debug, Clone, Deserialize)]
+pub struct GovernorConfig {
+    #[serde(default = "default_governor_max_concurrent_requests")]
+    pub max_concurrent_requests: usize,
+
+    #[serde(default = "default_governor_failure_threshold")]
+    pub failure_threshold: u32,
+
+    #[serde(default = "default_governor_cooldown")]
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub cooldown: Duration,
+
+    #[serde(default = "default_governor_max_retries")]
+    pub max_retries: u32,
+
+    #[serde(default = "default_governor_retry_backoff")]
+    #[serde_as(as = "DurationMilliSeconds<u64>")]
+    pub retry_backoff: Duration,
+}
+
+#[cfg(feature = "dev")]
+#[serde_with::serde_as]
+#[derive(Debug, Clone, Deserialize)]
+pub struct DevConfig {
+    #[serde(default)]
+    pub users: Vec<DevUser>,
+
+    #[serde(default = "default_dev_token_lifetime")]
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub token_lifetime: Duration,
 }
 
-#[derive(Debug, Deserialize)]
+#[cfg(feature = "dev")]
+#[derive(Debug, Clone, Deserialize)]
+pub struct DevUser {
+    pub username: String,
+    pub id: Option<uuid::Uuid>,
+    pub email: Option<String>,
+
+    #[serde(default)]
+    pub realm_roles: Vec<String>,
+
+    #[serde(default)]
+    pub client_roles: HashMap<String, Vec<String>>,
+
+    #[serde(default)]
+    pub organizations: Vec<String>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(transparent)]
+pub struct SecretString(String);
+
+#[derive(Clone, Default, Deserialize)]
 #[serde(untagged)]
 pub enum ClientSecret {
     Basic(String),
+    Jwt(ClientKey),
+    #[default]
+    Public,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClientKey {
+    pub private_key: std::path::PathBuf,
+    pub key_id: Option<String>,
+    pub algorithm: Option<jsonwebtoken::Algorithm>,
 }
 
 #[derive(Debug, Clone)]
@@ -50,40 +360,328 @@ pub struct ServerEndpoints {
     pub issuer: Url,
     pub auth: Url,
     pub token: Url,
-    pub introspect: Url,
-    pub userinfo: Url,
+    pub introspect: Option<Url>,
+    pub userinfo: Option<Url>,
     pub jwks: Url,
+    pub registration: Option<Url>,
+    pub discovery: Url,
+    pub admin: Url,
+    pub health: Url,
+    pub metrics: Url,
+}
+
+#[cfg(feature = "figment")]
+impl Config {
+    pub const ENV_PREFIX: &'static str = "RECLOAK_";
+
+    pub fn figment(path: impl AsRef<std::path::Path>) -> figment::Figment {
+        use figment::providers::{Env, Format, Toml, Yaml};
+
+        let path = path.as_ref();
+        let figment = match path.extension().and_then(|ext| ext.to_str()) {
+            | Some("yaml" | "yml") => figment::Figment::from(Yaml::file(path)),
+            | _ => figment::Figment::from(Toml::file(path)),
+        };
+
+        figment.merge(Env::prefixed(Self::ENV_PREFIX).split("__"))
+    }
+
+    #[inline]
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        Self::figment(path)
+            .extract()
+            .map_err(|err| crate::Error::Config(err.to_string()))
+    }
+}
+
+impl ClientConfig {
+    pub(crate) fn requested_scope(&self) -> std::borrow::Cow<'_, str> {
+        let has_offline = self
+            .scope
+            .split_whitespace()
+            .any(|scope| scope == OFFLINE_ACCESS_SCOPE);
+
+        if self.offline_access && !has_offline {
+            format!("{} {OFFLINE_ACCESS_SCOPE}", self.scope).into()
+        } else {
+            self.scope.as_str().into()
+        }
+    }
+}
+
+impl ClientSecret {
+    #[inline]
+    pub const fn is_public(&self) -> bool {
+        matches!(self, Self::Public)
+    }
+}
+
+impl fmt::Debug for ClientSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            | Self::Basic(_) => {
+                f.debug_tuple("Basic").field(&REDACTED).finish()
+            }
+            | Self::Jwt(key) => f.debug_tuple("Jwt").field(key).finish(),
+            | Self::Public => f.write_str("Public"),
+        }
+    }
+}
+
+impl SecretString {
+    #[inline]
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for SecretString {
+    #[inline]
+    fn from(secret: String) -> Self {
+        Self(secret)
+    }
+}
+
+impl From<&str> for SecretString {
+    #[inline]
+    fn from(secret: &str) -> Self {
+        Self(secret.to_owned())
+    }
+}
+
+impl fmt::Debug for SecretString {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl GovernorConfig {
+    pub(crate) fn check(&self) -> Result<()> {
+        if !(1..=tokio::sync::Semaphore::MAX_PERMITS)
+            .contains(&self.max_concurrent_requests)
+        {
+            return Err(crate::Error::Config(format!(
+                "governor.max_concurrent_requests must be between 1 and {}",
+                tokio::sync::Semaphore::MAX_PERMITS
+            )));
+        }
+
+        Ok(())
+    }
 }
 
 impl Config {
     pub(crate) fn urls(&self) -> Result<ServerEndpoints> {
-        if self.http.auth_server_url.cannot_be_a_base() {
+        self.check_base_urls()?;
+
+        match self.provider {
+            | Provider::Keycloak => self.keycloak_urls(),
+            | Provider::Generic => self.generic_urls(),
+        }
+    }
+
+    pub(crate) fn check_base_urls(&self) -> Result<()> {
+        let public = self.http.public_issuer_url.as_ref();
+        let management = self.http.management_url.as_ref();
+        if self.http.auth_server_url.cannot_be_a_base()
+            || public.is_some_and(Url::cannot_be_a_base)
+            || management.is_some_and(Url::cannot_be_a_base)
+        {
             return Err(url::ParseError::RelativeUrlWithoutBase)?;
         }
 
-        let mut issuer = self.http.auth_server_url.clone();
-        issuer
+        Ok(())
+    }
+
+    pub(crate) fn discovery_url(&self) -> Url {
+        let mut discovery = match self.provider {
+            | Provider::Keycloak => self.realm_url(&self.http.auth_server_url),
+            | Provider::Generic => self.http.auth_server_url.clone(),
+        };
+        discovery
+            .path_segments_mut()
+            .unwrap()
+            .pop_if_empty()
+            .extend([".well-known", "openid-configuration"]);
+
+        discovery
+    }
+
+    #[inline]
+    pub(crate) fn require_keycloak(&self, feature: &'static str) -> Result<()> {
+        match self.provider {
+            | Provider::Keycloak => Ok(()),
+            | Provider::Generic => Err(crate::Error::Unsupported(feature)),
+        }
+    }
+
+    fn realm_url(&self, base: &Url) -> Url {
+        let mut realm = base.clone();
+        realm
             .path_segments_mut()
             .unwrap()
             .push("realms")
             .push(&self.client.realm);
 
-        let oidc = build_url(issuer.clone(), "protocol/openid-connect");
-        let auth = build_url(oidc.clone(), "auth");
-        let token = build_url(oidc.clone(), "token");
-        let introspect = build_url(oidc.clone(), "introspect");
-        let userinfo = build_url(oidc.clone(), "userinfo");
-        let jwks = build_url(oidc.clone(), "certs");
+        realm
+    }
+
+    fn keycloak_urls(&self) -> Result<ServerEndpoints> {
+        if self.client.realm.is_empty() {
+            return Err(crate::Error::Config(
+                "the keycloak provider requires a realm".to_owned(),
+            ));
+        }
+
+        let realm = self.realm_url(&self.http.auth_server_url);
+        let issuer = match self.endpoints.issuer_url {
+            | Some(ref issuer) => issuer.clone(),
+            | None => match self.http.public_issuer_url {
+                | Some(ref public) => self.realm_url(public),
+                | None => realm.clone(),
+            },
+        };
+
+        let oidc = build_url(realm.clone(), "protocol/openid-connect");
+        let endpoints = &self.endpoints;
+        let auth = endpoints
+            .authorization_url
+            .clone()
+            .unwrap_or_else(|| build_url(oidc.clone(), "auth"));
+        let token = endpoints
+            .token_url
+            .clone()
+            .unwrap_or_else(|| build_url(oidc.clone(), "token"));
+        let introspect = endpoints
+            .introspection_url
+            .clone()
+            .unwrap_or_else(|| build_url(oidc.clone(), "introspect"));
+        let userinfo = endpoints
+            .userinfo_url
+            .clone()
+            .unwrap_or_else(|| build_url(oidc.clone(), "userinfo"));
+        let jwks = endpoints
+            .jwks_url
+            .clone()
+            .unwrap_or_else(|| build_url(oidc.clone(), "certs"));
+        let registration =
+            endpoints.registration_url.clone().unwrap_or_else(|| {
+                build_url(realm, "clients-registrations/openid-connect")
+            });
+
+        let (admin, health, metrics) = self.management_urls();
 
         Ok(ServerEndpoints {
             issuer,
             auth,
             token,
-            introspect,
-            userinfo,
+            introspect: Some(introspect),
+            userinfo: Some(userinfo),
             jwks,
+            registration: Some(registration),
+            discovery: self.discovery_url(),
+            admin,
+            health,
+            metrics,
         })
     }
+
+    fn generic_urls(&self) -> Result<ServerEndpoints> {
+        let required = |url: &Option<Url>, name: &str| {
+            url.clone().ok_or_else(|| {
+                crate::Error::Config(format!(
+                    "the generic provider requires a discovered or configured \
+                     {name} endpoint"
+                ))
+            })
+        };
+
+        let endpoints = &self.endpoints;
+        let (admin, health, metrics) = self.management_urls();
+
+        Ok(ServerEndpoints {
+            issuer: endpoints
+                .issuer_url
+                .clone()
+                .unwrap_or_else(|| self.http.auth_server_url.clone()),
+            auth: required(&endpoints.authorization_url, "authorization")?,
+            token: required(&endpoints.token_url, "token")?,
+            introspect: endpoints.introspection_url.clone(),
+            userinfo: endpoints.userinfo_url.clone(),
+            jwks: required(&endpoints.jwks_url, "jwks")?,
+            registration: endpoints.registration_url.clone(),
+            discovery: self.discovery_url(),
+            admin,
+            health,
+            metrics,
+        })
+    }
+
+    fn management_urls(&self) -> (Url, Url, Url) {
+        let mut admin = self.http.auth_server_url.clone();
+        admin.path_segments_mut().unwrap().extend([
+            "admin",
+            "realms",
+            &self.client.realm,
+        ]);
+
+        let management = self
+            .http
+            .management_url
+            .as_ref()
+            .unwrap_or(&self.http.auth_server_url);
+        let health = build_url(management.clone(), "health");
+        let metrics = build_url(management.clone(), "metrics");
+
+        (admin, health, metrics)
+    }
+}
+
+impl Default for TokenLimits {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            max_token_size: default_token_max_size(),
+            max_header_size: default_token_max_header_size(),
+            max_kid_length: default_token_max_kid_length(),
+        }
+    }
+}
+
+impl Default for AdminConfig {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            role_cache_ttl: default_admin_role_cache_ttl(),
+            role_cache_capacity: default_admin_role_cache_capacity(),
+        }
+    }
+}
+
+impl Default for IntrospectionConfig {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            cache_capacity: default_introspection_cache_capacity(),
+            cache_ttl: default_introspection_cache_ttl(),
+            negative_ttl: default_introspection_negative_ttl(),
+            opaque_tokens: false,
+        }
+    }
+}
+
+impl Default for GovernorConfig {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            max_concurrent_requests: default_governor_max_concurrent_requests(),
+            failure_threshold: default_governor_failure_threshold(),
+            cooldown: default_governor_cooldown(),
+            max_retries: default_governor_max_retries(),
+            retry_backoff: default_governor_retry_backoff(),
+        }
+    }
 }
 
 #[inline]
@@ -105,6 +703,21 @@ fn default_scope() -> String {
     "openid".to_owned()
 }
 
+#[inline]
+fn default_token_max_size() -> usize {
+    16 * 1024
+}
+
+#[inline]
+fn default_token_max_header_size() -> usize {
+    1024
+}
+
+#[inline]
+fn default_token_max_kid_length() -> usize {
+    256
+}
+
 #[inline]
 fn default_http_https_only() -> bool {
     false
@@ -114,3 +727,118 @@ fn default_http_https_only() -> bool {
 fn default_http_allow_insecure() -> bool {
     false
 }
+
+#[inline]
+fn default_admin_role_cache_ttl() -> Duration {
+    Duration::from_secs(60)
+}
+
+#[inline]
+fn default_admin_role_cache_capacity() -> usize {
+    1024
+}
+
+#[inline]
+fn default_introspection_cache_capacity() -> usize {
+    4096
+}
+
+#[inline]
+fn default_introspection_cache_ttl() -> Duration {
+    Duration::from_secs(30)
+}
+
+#[inline]
+fn default_introspection_negative_ttl() -> Duration {
+    Duration::from_secs(10)
+}
+
+#[inline]
+fn default_governor_max_concurrent_requests() -> usize {
+    32
+}
+
+#[inline]
+fn default_governor_failure_threshold() -> u32 {
+    5
+}
+
+#[inline]
+fn default_governor_cooldown() -> Duration {
+    Duration::from_secs(30)
+}
+
+#[cfg(feature = "dev")]
+#[inline]
+fn default_dev_token_lifetime() -> Duration {
+    Duration::from_secs(3600)
+}
+
+#[inline]
+fn default_governor_max_retries() -> u32 {
+    2
+}
+
+#[inline]
+fn default_governor_retry_backoff() -> Duration {
+    Duration::from_millis(200)
+}
+
+#[cfg(test)]
+impl Config {
+    pub(crate) fn for_tests() -> Self {
+        serde_json::from_value(serde_json::json!({
+            "client": { "id": "app", "realm": "test" },
+            "token": {},
+            "http": { "auth_server_url": "https://sso.internal:8443" },
+        }))
+        .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ClientSecret, Config, ProxyConfig, TokenConfig};
+
+    #[test]
+    fn claim_requirements_are_opt_in() {
+        let token: TokenConfig =
+            serde_json::from_value(serde_json::json!({})).unwrap();
+
+        assert!(!token.require_bearer);
+        assert!(!token.require_username);
+        assert!(!token.require_roles);
+        assert!(!token.require_resource_access);
+    }
+
+    #[test]
+    fn redacts_proxy_password_in_debug() {
+        let mut config = Config::for_tests();
+        config.http.proxy = Some(ProxyConfig {
+            url: "http://proxy.internal:3128".parse().unwrap(),
+            no_proxy: Vec::new(),
+            username: Some("egress".to_owned()),
+            password: Some("proxy-password".into()),
+        });
+
+        let debug = format!("{config:?}");
+        assert!(debug.contains("egress"));
+        assert!(!debug.contains("proxy-password"));
+    }
+
+    #[test]
+    fn redacts_secrets_in_debug() {
+        let mut config = Config::for_tests();
+        config.token.hs_secret = Some("hs-signing-key".into());
+        config.client.secret = ClientSecret::Basic("client-secret".to_owned());
+
+        let debug = format!("{config:?}");
+        assert!(!debug.contains("hs-signing-key"));
+        assert!(!debug.contains("client-secret"));
+        assert!(debug.contains("[redacted]"));
+        assert_eq!(
+            config.token.hs_secret.as_ref().map(|s| s.expose()),
+            Some("hs-signing-key")
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use tokio::sync::RwLock;
+
+use crate::{
+    exchange::ACCESS_TOKEN_TYPE,
+    ClientGrant,
+    Error,
+    ReCloak,
+    Result,
+    TokenResponse,
+};
+
+pub(crate) type TokenStore<K = String> = RwLock<HashMap<K, TokenResponse>>;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct TokenRequirements {
+    pub audience: Option<String>,
+    pub scopes: Vec<String>,
+}
+
+impl TokenRequirements {
+    #[inline]
+    pub fn audience(mut self, audience: impl Into<String>) -> Self {
+        self.audience = Some(audience.into());
+        self
+    }
+
+    #[inline]
+    pub fn scope(mut self, scope: impl Into<String>) -> Self {
+        self.scopes.push(scope.into());
+        self
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.audience.is_none() && self.scopes.is_empty()
+    }
+
+    fn requested_scope(&self, base: &str) -> String {
+        let mut scope = base.to_owned();
+        for extra in &self.scopes {
+            if !base.split_whitespace().any(|s| s == extra) {
+                scope.push(' ');
+                scope.push_str(extra);
+            }
+        }
+
+        scope
+    }
+}
+
+impl ReCloak {
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn authenticate_as(&self, name: &str) -> Result<arcstr::ArcStr> {
+        if let Some(token) = self.tokens.read().await.get(name) {
+            if !token.is_access_expired(self.clock.now()) {
+                return Ok(token.access_token.clone());
+            }
+        }
+
+        let config = self.config.load_full();
+        let creds = config
+            .credentials
+            .get(name)
+            .ok_or_else(|| Error::UnknownClient(name.to_owned()))?;
+        let auth =
+            self.client_auth(&creds.id, &creds.secret, "client_credentials")?;
+
+        let token_resp = self
+            .login_client(ClientGrant::ClientCredentials {
+                id: &creds.id,
+                auth,
+                scope: Some(&creds.scope),
+            })
+            .await?;
+        let access_token = token_resp.access_token.clone();
+
+        self.tokens
+            .write()
+            .await
+            .insert(name.to_owned(), token_resp);
+
+        Ok(access_token)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn authenticate_for(
+        &self,
+        requirements: &TokenRequirements,
+    ) -> Result<arcstr::ArcStr> {
+        if requirements.is_empty() {
+            return self.authenticate().await;
+        }
+
+        if let Some(token) = self.scoped_tokens.read().await.get(requirements) {
+            if !token.is_access_expired(self.clock.now()) {
+                return Ok(token.access_token.clone());
+            }
+        }
+
+        let config = self.refresh_secret().await?;
+        let id = config.client.id.as_str();
+        let auth =
+            self.client_auth(id, &config.client.secret, "client_credentials")?;
+        let scope =
+            requirements.requested_scope(&config.client.requested_scope());
+
+        let token_resp = match requirements.audience.as_deref() {
+            | Some(audience) => {
+                let subject_token = self.authenticate().await?;
+
+                self.login_client(ClientGrant::TokenExchange {
+                    id,
+                    auth,
+                    subject_token: Some(&subject_token),
+                    subject_token_type: Some(ACCESS_TOKEN_TYPE),
+                    requested_subject: None,
+                    requested_token_type: Some(ACCESS_TOKEN_TYPE),
+                    audience: Some(audience),
+                    scope: Some(&scope),
+                })
+                .await?
+            }
+            | None => {
+                self.login_client(ClientGrant::ClientCredentials {
+                    id,
+                    auth,
+                    scope: Some(&scope),
+                })
+                .await?
+            }
+        };
+        let access_token = token_resp.access_token.clone();
+
+        self.scoped_tokens
+            .write()
+            .await
+            .insert(requirements.clone(), token_resp);
+
+        Ok(access_token)
+    }
+}
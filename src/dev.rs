@@ -0,0 +1,372 @@
+use std::{collections::HashMap, fmt, sync::Arc, time::Duration};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{self as jwt, jwk::JwkSet, Algorithm};
+use reqwest::{
+    header::{HeaderValue, AUTHORIZATION, CONTENT_TYPE},
+    Method,
+    StatusCode,
+};
+use ring::{
+    digest,
+    rand::SystemRandom,
+    signature::{Ed25519KeyPair, KeyPair},
+};
+use serde_json::{json, Value};
+
+use crate::{
+    config::DevUser,
+    telemetry,
+    Claims,
+    Clock,
+    Config,
+    Error,
+    HttpTransport,
+    JwtEncoder,
+    Organization,
+    Result,
+    RolesClaim,
+    TransportFuture,
+    TransportRequest,
+    TransportResponse,
+};
+
+const KEY_ID: &str = "dev";
+const SERVICE_ACCOUNT_PREFIX: &str = "service-account-";
+const TOKEN_EXCHANGE_GRANT: &str =
+    "urn:ietf:params:oauth:grant-type:token-exchange";
+const DEFAULT_TOKEN_LIFETIME: Duration = Duration::from_secs(3600);
+
+#[derive(Debug, Clone)]
+pub struct DevIssuer(Arc<DevState>);
+
+struct DevState {
+    encoder: JwtEncoder,
+    key: jwt::DecodingKey,
+    jwks: JwkSet,
+    users: HashMap<String, DevUser>,
+    issuer: String,
+    audience: String,
+    client_id: String,
+    lifetime: chrono::Duration,
+    clock: Arc<dyn Clock>,
+}
+
+type DevResponse = (StatusCode, Value);
+
+impl DevIssuer {
+    pub fn new(config: &Config, clock: Arc<dyn Clock>) -> Result<Self> {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+            .map_err(|_| key_error())?;
+        let pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref())
+            .map_err(|_| key_error())?;
+        let public = pair.public_key().as_ref();
+
+        let jwks = serde_json::from_value(json!({
+            "keys": [{
+                "kty": "OKP",
+                "crv": "Ed25519",
+                "use": "sig",
+                "alg": "EdDSA",
+                "kid": KEY_ID,
+                "x": URL_SAFE_NO_PAD.encode(public),
+            }],
+        }))
+        .map_err(|_| key_error())?;
+
+        let issuer = match config.token.issuer.as_deref() {
+            | Some([issuer, ..]) => issuer.clone(),
+            | _ => config.urls()?.issuer.to_string(),
+        };
+        let audience = match config.token.audience.as_deref() {
+            | Some([audience, ..]) => audience.clone(),
+            | _ => config.client.id.clone(),
+        };
+
+        let dev = config.dev.as_ref();
+        let users = dev
+            .into_iter()
+            .flat_map(|dev| dev.users.iter())
+            .map(|user| (user.username.clone(), user.clone()))
+            .collect();
+        let lifetime = chrono::Duration::from_std(
+            dev.map_or(DEFAULT_TOKEN_LIFETIME, |dev| dev.token_lifetime),
+        )
+        .map_err(|_| {
+            Error::Config("dev token lifetime is out of range".to_owned())
+        })?;
+
+        Ok(Self(Arc::new(DevState {
+            encoder: JwtEncoder::new(
+                Algorithm::EdDSA,
+                jwt::EncodingKey::from_ed_der(pkcs8.as_ref()),
+            )
+            .key_id(KEY_ID),
+            key: jwt::DecodingKey::from_ed_der(public),
+            jwks,
+            users,
+            issuer,
+            audience,
+            client_id: config.client.id.clone(),
+            lifetime,
+            clock,
+        })))
+    }
+
+    #[inline]
+    pub fn jwks(&self) -> &JwkSet {
+        &self.0.jwks
+    }
+
+    pub fn mint(&self, username: &str) -> Result<String> {
+        let user = self.0.users.get(username).ok_or_else(|| {
+            Error::Config(format!("unknown dev user `{username}`"))
+        })?;
+
+        self.0.encoder.encode(&self.claims(
+            username,
+            Some(user),
+            &self.0.client_id,
+            None,
+            None,
+        ))
+    }
+
+    fn claims(
+        &self,
+        username: &str,
+        user: Option<&DevUser>,
+        client_id: &str,
+        scope: Option<&str>,
+        audience: Option<&str>,
+    ) -> Claims {
+        let now = self.0.clock.now();
+        let subject = user
+            .and_then(|user| user.id)
+            .unwrap_or_else(|| stable_id(username));
+
+        let mut claims = Claims::new(
+            &self.0.issuer,
+            subject.to_string(),
+            audience.unwrap_or(&self.0.audience),
+            now,
+            now + self.0.lifetime,
+        );
+        claims.username = Some(username.into());
+        claims.authorized_party = Some(client_id.to_owned());
+        claims.realm = RolesClaim::new(
+            user.into_iter()
+                .flat_map(|user| user.realm_roles.iter().cloned()),
+        );
+
+        if let Some(user) = user {
+            claims.email.clone_from(&user.email);
+            claims.email_verified = user.email.as_ref().map(|_| true);
+            claims.resource = user
+                .client_roles
+                .iter()
+                .map(|(client, roles)| {
+                    (client.as_str(), RolesClaim::new(roles.iter().cloned()))
+                })
+                .collect();
+            claims.organizations = user
+                .organizations
+                .iter()
+                .map(|alias| Organization::new(alias.as_str(), None))
+                .collect();
+        }
+
+        if let Some(scope) = scope {
+            claims.extra.insert("scope".to_owned(), json!(scope));
+        }
+
+        claims
+    }
+
+    fn respond(&self, req: &TransportRequest) -> DevResponse {
+        let endpoint = req.url.path().rsplit('/').next().unwrap_or_default();
+
+        match (&req.method, endpoint) {
+            | (&Method::GET, "certs") => (
+                StatusCode::OK,
+                serde_json::to_value(&self.0.jwks).unwrap_or_default(),
+            ),
+            | (&Method::POST, "token") => self.token(&form(req)),
+            | (&Method::POST, "introspect") => {
+                let form = form(req);
+                let token = form.get("token").map(String::as_str);
+
+                (StatusCode::OK, self.introspect(token.unwrap_or_default()))
+            }
+            | (_, "userinfo") => {
+                let token = req
+                    .headers
+                    .get(AUTHORIZATION)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.split_once(' '))
+                    .map(|(_, token)| token.trim());
+
+                match token.and_then(|token| self.verify(token)) {
+                    | Some(claims) => (StatusCode::OK, Value::Object(claims)),
+                    | None => {
+                        oauth_error(StatusCode::UNAUTHORIZED, "invalid_token")
+                    }
+                }
+            }
+            | _ => oauth_error(StatusCode::NOT_FOUND, "not_found"),
+        }
+    }
+
+    fn token(&self, form: &HashMap<String, String>) -> DevResponse {
+        let client_id = form
+            .get("client_id")
+            .map_or(self.0.client_id.as_str(), String::as_str);
+        let scope = form.get("scope").map(String::as_str);
+        let audience = form.get("audience").map(String::as_str);
+        let service_account = format!("{SERVICE_ACCOUNT_PREFIX}{client_id}");
+        let subject = form
+            .get("subject_token")
+            .and_then(|token| self.verify(token))
+            .and_then(|claims| match claims.get("preferred_username") {
+                | Some(Value::String(username)) => Some(username.clone()),
+                | _ => None,
+            });
+
+        let username = match form.get("grant_type").map(String::as_str) {
+            | Some("client_credentials") => service_account.as_str(),
+            | Some(TOKEN_EXCHANGE_GRANT) => {
+                match (form.get("requested_subject"), subject.as_ref()) {
+                    | (Some(user), _) if self.0.users.contains_key(user) => {
+                        user
+                    }
+                    | (Some(_), _) => {
+                        return oauth_error(
+                            StatusCode::BAD_REQUEST,
+                            "invalid_request",
+                        )
+                    }
+                    | (None, Some(subject)) => subject,
+                    | (None, None) => service_account.as_str(),
+                }
+            }
+            | _ => {
+                return oauth_error(
+                    StatusCode::BAD_REQUEST,
+                    "unsupported_grant_type",
+                )
+            }
+        };
+
+        let claims = self.claims(
+            username,
+            self.0.users.get(username),
+            client_id,
+            scope,
+            audience,
+        );
+
+        match self.0.encoder.encode(&claims) {
+            | Ok(token) => (
+                StatusCode::OK,
+                json!({
+                    "access_token": token,
+                    "token_type": "Bearer",
+                    "expires_in": self.0.lifetime.num_seconds(),
+                    "scope": scope,
+                }),
+            ),
+            | Err(err) => {
+                telemetry::trace_error!(error = %err, "failed to mint dev token");
+
+                oauth_error(StatusCode::INTERNAL_SERVER_ERROR, "server_error")
+            }
+        }
+    }
+
+    fn introspect(&self, token: &str) -> Value {
+        let Some(mut claims) = self.verify(token) else {
+            return json!({ "active": false });
+        };
+
+        let username = claims.get("preferred_username").cloned();
+        let client_id = claims.get("azp").cloned();
+
+        claims.insert("active".to_owned(), Value::Bool(true));
+        claims.insert("username".to_owned(), username.unwrap_or_default());
+        claims.insert("client_id".to_owned(), client_id.unwrap_or_default());
+        claims.insert("token_type".to_owned(), json!("Bearer"));
+
+        Value::Object(claims)
+    }
+
+    fn verify(&self, token: &str) -> Option<serde_json::Map<String, Value>> {
+        let mut vld = jwt::Validation::new(Algorithm::EdDSA);
+        vld.validate_exp = false;
+        vld.validate_aud = false;
+
+        let claims = jwt::decode::<serde_json::Map<String, Value>>(
+            token,
+            &self.0.key,
+            &vld,
+        )
+        .ok()?
+        .claims;
+        let exp = claims.get("exp").and_then(Value::as_i64)?;
+
+        (exp > self.0.clock.now().timestamp()).then_some(claims)
+    }
+}
+
+impl HttpTransport for DevIssuer {
+    fn execute(&self, req: TransportRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let (status, body) = self.respond(&req);
+
+            telemetry::trace_debug!(method = %req.method, url = %req.url, %status, "served dev issuer request");
+
+            let mut resp = TransportResponse::new(status, body.to_string());
+            resp.headers.insert(
+                CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            );
+
+            Ok(resp)
+        })
+    }
+}
+
+impl fmt::Debug for DevState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DevState")
+            .field("key", &"[redacted]")
+            .field("users", &self.users.keys())
+            .field("issuer", &self.issuer)
+            .field("audience", &self.audience)
+            .field("lifetime", &self.lifetime)
+            .finish_non_exhaustive()
+    }
+}
+
+fn form(req: &TransportRequest) -> HashMap<String, String> {
+    let body = req.body.as_deref().unwrap_or_default();
+
+    url::form_urlencoded::parse(body).into_owned().collect()
+}
+
+fn stable_id(username: &str) -> uuid::Uuid {
+    let digest = digest::digest(&digest::SHA256, username.as_bytes());
+    let mut bytes = [0; 16];
+    bytes.copy_from_slice(&digest.as_ref()[..16]);
+
+    uuid::Builder::from_random_bytes(bytes).into_uuid()
+}
+
+#[inline]
+fn oauth_error(status: StatusCode, code: &str) -> DevResponse {
+    (status, json!({ "error": code }))
+}
+
+#[inline]
+fn key_error() -> Error {
+    Error::Config("failed to generate dev signing key".to_owned())
+}
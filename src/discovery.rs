@@ -0,0 +1,125 @@
+use url::Url;
+
+use crate::{config::Provider, governor::Governor, telemetry, Config, Result};
+
+#[derive(Debug, serde::Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: Url,
+    token_endpoint: Url,
+    jwks_uri: Url,
+    introspection_endpoint: Option<Url>,
+    userinfo_endpoint: Option<Url>,
+    registration_endpoint: Option<Url>,
+}
+
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(skip_all, fields(provider = ?config.provider))
+)]
+pub(crate) async fn resolve(
+    client: &reqwest::Client,
+    governor: &Governor,
+    mut config: Config,
+) -> Result<Config> {
+    if config.provider == Provider::Keycloak {
+        return Ok(config);
+    }
+
+    config.check_base_urls()?;
+    let url = config.discovery_url();
+    telemetry::trace_debug!(%url, "discovering provider endpoints");
+
+    let resp = governor.send(client.get(url)).await?;
+    if !resp.status.is_success() {
+        return Err(crate::Error::unexpected_response(resp.status, &resp.body));
+    }
+
+    let metadata: ProviderMetadata = resp.json()?;
+
+    check_issuer(&config, &metadata.issuer)?;
+
+    let endpoints = &mut config.endpoints;
+    endpoints
+        .authorization_url
+        .get_or_insert(metadata.authorization_endpoint);
+    endpoints.token_url.get_or_insert(metadata.token_endpoint);
+    endpoints.jwks_url.get_or_insert(metadata.jwks_uri);
+    endpoints.introspection_url = endpoints
+        .introspection_url
+        .take()
+        .or(metadata.introspection_endpoint);
+    endpoints.userinfo_url =
+        endpoints.userinfo_url.take().or(metadata.userinfo_endpoint);
+    endpoints.registration_url = endpoints
+        .registration_url
+        .take()
+        .or(metadata.registration_endpoint);
+
+    if endpoints.issuer_url.is_none() {
+        endpoints.issuer_url = Some(metadata.issuer.parse()?);
+    }
+
+    // the discovered issuer is matched verbatim, `Url` would normalize it.
+    config
+        .token
+        .issuer
+        .get_or_insert_with(|| vec![metadata.issuer]);
+
+    Ok(config)
+}
+
+// the issuer must be the url the document was discovered from, unless a
+// public issuer is configured for a split-horizon deployment.
+fn check_issuer(config: &Config, issuer: &str) -> Result<()> {
+    let expected = config
+        .endpoints
+        .issuer_url
+        .as_ref()
+        .or(config.http.public_issuer_url.as_ref())
+        .unwrap_or(&config.http.auth_server_url)
+        .as_str();
+
+    if issuer.trim_end_matches('/') != expected.trim_end_matches('/') {
+        return Err(crate::Error::Config(format!(
+            "discovered issuer `{issuer}` does not match `{expected}`"
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn issuer_must_match_auth_server_url() {
+        let config = Config::for_tests();
+
+        assert!(check_issuer(&config, "https://sso.internal:8443/").is_ok());
+        assert!(check_issuer(&config, "https://evil.example.com").is_err());
+    }
+
+    #[test]
+    fn issuer_matches_public_issuer_when_split_horizon() {
+        let mut config = Config::for_tests();
+        config.http.public_issuer_url =
+            Some("https://sso.example.com".parse().unwrap());
+
+        assert!(check_issuer(&config, "https://sso.example.com").is_ok());
+        assert!(check_issuer(&config, "https://sso.internal:8443").is_err());
+    }
+
+    #[test]
+    fn issuer_override_takes_precedence() {
+        let mut config = Config::for_tests();
+        config.http.public_issuer_url =
+            Some("https://sso.example.com".parse().unwrap());
+        config.endpoints.issuer_url =
+            Some("https://id.example.com/tenant".parse().unwrap());
+
+        assert!(check_issuer(&config, "https://id.example.com/tenant").is_ok());
+        assert!(check_issuer(&config, "https://sso.example.com").is_err());
+    }
+}
@@ -0,0 +1,429 @@
+use std::{fmt, time::Duration};
+
+use jsonwebtoken::jwk::PublicKeyUse;
+use reqwest::header::DATE;
+use serde::Serialize;
+
+use crate::{config::ClientSecret, telemetry, ReCloak};
+
+const SKEW_WARNING: Duration = Duration::from_secs(5);
+const SKEW_FAILURE: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DoctorCheckKind {
+    Reachability,
+    ClockSkew,
+    Issuer,
+    ClientCredentials,
+    Jwks,
+    Audience,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckOutcome {
+    Skipped,
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorCheck {
+    pub kind: DoctorCheckKind,
+    pub outcome: CheckOutcome,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorReport {
+    pub checked_at: chrono::DateTime<chrono::Utc>,
+    pub checks: Vec<DoctorCheck>,
+}
+
+#[derive(serde::Deserialize)]
+struct Discovery {
+    issuer: String,
+}
+
+impl DoctorCheckKind {
+    #[inline]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            | Self::Reachability => "reachability",
+            | Self::ClockSkew => "clock_skew",
+            | Self::Issuer => "issuer",
+            | Self::ClientCredentials => "client_credentials",
+            | Self::Jwks => "jwks",
+            | Self::Audience => "audience",
+        }
+    }
+}
+
+impl CheckOutcome {
+    #[inline]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            | Self::Skipped => "skipped",
+            | Self::Pass => "pass",
+            | Self::Warn => "warn",
+            | Self::Fail => "fail",
+        }
+    }
+}
+
+impl DoctorCheck {
+    #[inline]
+    fn new(
+        kind: DoctorCheckKind,
+        outcome: CheckOutcome,
+        detail: impl Into<String>,
+    ) -> Self {
+        Self {
+            kind,
+            outcome,
+            detail: detail.into(),
+        }
+    }
+}
+
+impl DoctorReport {
+    #[inline]
+    pub fn is_healthy(&self) -> bool {
+        self.outcome() < CheckOutcome::Fail
+    }
+
+    #[inline]
+    pub fn outcome(&self) -> CheckOutcome {
+        self.checks
+            .iter()
+            .map(|check| check.outcome)
+            .max()
+            .unwrap_or(CheckOutcome::Pass)
+    }
+
+    #[inline]
+    pub fn check(&self, kind: DoctorCheckKind) -> Option<&DoctorCheck> {
+        self.checks.iter().find(|check| check.kind == kind)
+    }
+
+    #[inline]
+    pub fn failures(&self) -> impl Iterator<Item = &DoctorCheck> {
+        self.checks
+            .iter()
+            .filter(|check| check.outcome == CheckOutcome::Fail)
+    }
+}
+
+impl fmt::Display for DoctorCheckKind {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Display for CheckOutcome {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Display for DoctorCheck {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} ({})", self.kind, self.outcome, self.detail)
+    }
+}
+
+impl fmt::Display for DoctorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "doctor {}", self.outcome())?;
+        for check in &self.checks {
+            write!(f, "; {check}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ReCloak {
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn doctor(&self) -> DoctorReport {
+        let mut checks = self.check_discovery().await;
+
+        let (check, token) = self.check_credentials().await;
+        checks.push(check);
+
+        checks.push(self.check_jwks().await);
+        checks.push(match token {
+            | Some(token) => self.check_audience(&token).await,
+            | None => DoctorCheck::new(
+                DoctorCheckKind::Audience,
+                CheckOutcome::Skipped,
+                "no client token to introspect",
+            ),
+        });
+
+        for check in &checks {
+            match check.outcome {
+                | CheckOutcome::Fail => {
+                    telemetry::trace_error!(%check, "doctor check failed")
+                }
+                | CheckOutcome::Warn => {
+                    telemetry::trace_warn!(%check, "doctor check raised a warning")
+                }
+                | _ => {
+                    telemetry::trace_debug!(%check, "doctor check completed")
+                }
+            }
+        }
+
+        DoctorReport {
+            checked_at: self.clock.now(),
+            checks,
+        }
+    }
+
+    async fn check_discovery(&self) -> Vec<DoctorCheck> {
+        let url = self.urls.load().discovery.clone();
+        let sent_at = self.clock.now();
+        let resp = self.governor.send(self.client.get(url.clone())).await;
+        let received_at = self.clock.now();
+
+        let resp = match resp {
+            | Ok(resp) if resp.status.is_success() => resp,
+            | Ok(resp) => {
+                return unreachable(format!(
+                    "{url} responded with status {}",
+                    resp.status
+                ));
+            }
+            | Err(err) => return unreachable(format!("{url}: {err}")),
+        };
+
+        let latency = (received_at - sent_at).to_std().unwrap_or_default();
+        let mut checks = vec![DoctorCheck::new(
+            DoctorCheckKind::Reachability,
+            CheckOutcome::Pass,
+            format!("{url} responded in {}ms", latency.as_millis()),
+        )];
+
+        let server_date = resp
+            .headers
+            .get(DATE)
+            .and_then(|date| date.to_str().ok())
+            .and_then(|date| chrono::DateTime::parse_from_rfc2822(date).ok());
+        checks.push(match server_date {
+            | Some(date) => {
+                let local = sent_at + (received_at - sent_at) / 2;
+                let skew = (local - date.with_timezone(&chrono::Utc))
+                    .abs()
+                    .to_std()
+                    .unwrap_or_default();
+                let outcome = match skew {
+                    | skew if skew >= SKEW_FAILURE => CheckOutcome::Fail,
+                    | skew if skew >= SKEW_WARNING => CheckOutcome::Warn,
+                    | _ => CheckOutcome::Pass,
+                };
+
+                DoctorCheck::new(
+                    DoctorCheckKind::ClockSkew,
+                    outcome,
+                    format!("local clock is {}s off keycloak", skew.as_secs()),
+                )
+            }
+            | None => DoctorCheck::new(
+                DoctorCheckKind::ClockSkew,
+                CheckOutcome::Skipped,
+                "keycloak sent no date header",
+            ),
+        });
+
+        checks.push(match resp.json::<Discovery>() {
+            | Ok(discovery) => {
+                let config = self.config.load();
+                let expected = match config.token.issuer {
+                    | Some(ref issuers) => issuers.clone(),
+                    | None => vec![self.urls.load().issuer.to_string()],
+                };
+
+                if expected.contains(&discovery.issuer) {
+                    DoctorCheck::new(
+                        DoctorCheckKind::Issuer,
+                        CheckOutcome::Pass,
+                        format!(
+                            "keycloak issues tokens as {}",
+                            discovery.issuer
+                        ),
+                    )
+                } else {
+                    DoctorCheck::new(
+                        DoctorCheckKind::Issuer,
+                        CheckOutcome::Fail,
+                        format!(
+                            "keycloak issues tokens as {}, expected one of \
+                             {expected:?}",
+                            discovery.issuer
+                        ),
+                    )
+                }
+            }
+            | Err(err) => DoctorCheck::new(
+                DoctorCheckKind::Issuer,
+                CheckOutcome::Fail,
+                format!("invalid discovery document: {err}"),
+            ),
+        });
+
+        checks
+    }
+
+    async fn check_credentials(&self) -> (DoctorCheck, Option<arcstr::ArcStr>) {
+        if let ClientSecret::Public = self.config.load().client.secret {
+            if self.secret_source.is_none() {
+                let check = DoctorCheck::new(
+                    DoctorCheckKind::ClientCredentials,
+                    CheckOutcome::Skipped,
+                    "client is public",
+                );
+
+                return (check, None);
+            }
+        }
+
+        match self.authenticate().await {
+            | Ok(token) => {
+                let check = DoctorCheck::new(
+                    DoctorCheckKind::ClientCredentials,
+                    CheckOutcome::Pass,
+                    "client credentials grant succeeded",
+                );
+
+                (check, Some(token))
+            }
+            | Err(err) => {
+                let check = DoctorCheck::new(
+                    DoctorCheckKind::ClientCredentials,
+                    CheckOutcome::Fail,
+                    err.to_string(),
+                );
+
+                (check, None)
+            }
+        }
+    }
+
+    async fn check_jwks(&self) -> DoctorCheck {
+        let jwks = match self.reload_jwks().await {
+            | Ok(_) => self.jwks().await,
+            | Err(err) => Err(err),
+        };
+
+        match jwks {
+            | Ok(jwks) => {
+                let signing = jwks
+                    .keys
+                    .iter()
+                    .filter(|jwk| {
+                        matches!(
+                            jwk.common.public_key_use,
+                            None | Some(PublicKeyUse::Signature)
+                        )
+                    })
+                    .count();
+                let outcome = match signing {
+                    | 0 => CheckOutcome::Fail,
+                    | _ => CheckOutcome::Pass,
+                };
+
+                DoctorCheck::new(
+                    DoctorCheckKind::Jwks,
+                    outcome,
+                    format!(
+                        "{} keys published, {signing} usable for signatures",
+                        jwks.keys.len()
+                    ),
+                )
+            }
+            | Err(err) => DoctorCheck::new(
+                DoctorCheckKind::Jwks,
+                CheckOutcome::Fail,
+                err.to_string(),
+            ),
+        }
+    }
+
+    async fn check_audience(&self, token: &str) -> DoctorCheck {
+        let introspection = match self.introspect(token).await {
+            | Ok(introspection) if introspection.active => introspection,
+            | Ok(_) => {
+                return DoctorCheck::new(
+                    DoctorCheckKind::Audience,
+                    CheckOutcome::Fail,
+                    "keycloak reports the client token as inactive",
+                );
+            }
+            | Err(err) => {
+                return DoctorCheck::new(
+                    DoctorCheckKind::Audience,
+                    CheckOutcome::Fail,
+                    err.to_string(),
+                );
+            }
+        };
+
+        let audience = match introspection.extra.get("aud") {
+            | Some(serde_json::Value::String(aud)) => vec![aud.clone()],
+            | Some(serde_json::Value::Array(aud)) => aud
+                .iter()
+                .filter_map(|aud| aud.as_str().map(ToOwned::to_owned))
+                .collect(),
+            | _ => Vec::new(),
+        };
+
+        let config = self.config.load();
+        let expected = match config.token.audience {
+            | Some(ref audience) => audience.clone(),
+            | None => vec![config.client.id.clone()],
+        };
+
+        if expected.iter().any(|aud| audience.contains(aud)) {
+            DoctorCheck::new(
+                DoctorCheckKind::Audience,
+                CheckOutcome::Pass,
+                format!("client tokens carry audience {audience:?}"),
+            )
+        } else {
+            DoctorCheck::new(
+                DoctorCheckKind::Audience,
+                CheckOutcome::Fail,
+                format!(
+                    "client tokens carry audience {audience:?}, expected one \
+                     of {expected:?}; add an audience mapper"
+                ),
+            )
+        }
+    }
+}
+
+fn unreachable(detail: String) -> Vec<DoctorCheck> {
+    vec![
+        DoctorCheck::new(
+            DoctorCheckKind::Reachability,
+            CheckOutcome::Fail,
+            detail,
+        ),
+        DoctorCheck::new(
+            DoctorCheckKind::ClockSkew,
+            CheckOutcome::Skipped,
+            "keycloak is unreachable",
+        ),
+        DoctorCheck::new(
+            DoctorCheckKind::Issuer,
+            CheckOutcome::Skipped,
+            "keycloak is unreachable",
+        ),
+    ]
+}
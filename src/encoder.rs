@@ -0,0 +1,67 @@
+use std::fmt;
+
+use jsonwebtoken::{self as jwt, Algorithm, EncodingKey};
+
+use crate::{Claims, Result};
+
+#[derive(Clone)]
+pub struct JwtEncoder {
+    key: EncodingKey,
+    pub(crate) header: jwt::Header,
+}
+
+impl JwtEncoder {
+    #[inline]
+    pub fn new(algorithm: Algorithm, key: EncodingKey) -> Self {
+        Self {
+            key,
+            header: jwt::Header::new(algorithm),
+        }
+    }
+
+    #[inline]
+    pub fn from_rsa_pem(pem: &[u8]) -> Result<Self> {
+        Ok(Self::new(Algorithm::RS256, EncodingKey::from_rsa_pem(pem)?))
+    }
+
+    #[inline]
+    pub fn from_ec_pem(pem: &[u8]) -> Result<Self> {
+        Ok(Self::new(Algorithm::ES256, EncodingKey::from_ec_pem(pem)?))
+    }
+
+    #[inline]
+    pub fn from_ed_pem(pem: &[u8]) -> Result<Self> {
+        Ok(Self::new(Algorithm::EdDSA, EncodingKey::from_ed_pem(pem)?))
+    }
+
+    #[inline]
+    pub fn from_secret(secret: &[u8]) -> Self {
+        Self::new(Algorithm::HS256, EncodingKey::from_secret(secret))
+    }
+
+    #[inline]
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.header.alg = algorithm;
+        self
+    }
+
+    #[inline]
+    pub fn key_id(mut self, kid: impl Into<String>) -> Self {
+        self.header.kid = Some(kid.into());
+        self
+    }
+
+    #[inline]
+    pub fn encode(&self, claims: &Claims) -> Result<String> {
+        Ok(jwt::encode(&self.header, claims, &self.key)?)
+    }
+}
+
+impl fmt::Debug for JwtEncoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JwtEncoder")
+            .field("key", &"[redacted]")
+            .field("header", &self.header)
+            .finish()
+    }
+}
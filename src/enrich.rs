@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use crate::{
+    cache::TtlCache,
+    config::EnrichmentSource,
+    Claims,
+    Error,
+    ReCloak,
+    Result,
+};
+
+pub(crate) type EnrichmentCache = TtlCache<arcstr::ArcStr, Arc<Claims>>;
+
+impl ReCloak {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self, token, claims), fields(jti = ?claims.id))
+    )]
+    pub async fn enrich_claims(
+        &self,
+        token: &str,
+        mut claims: Claims,
+    ) -> Result<Claims> {
+        let Some(source) = self.config.load().token.enrichment else {
+            return Ok(claims);
+        };
+
+        if !claims.is_lightweight() {
+            return Ok(claims);
+        }
+
+        if let Some(enriched) = match claims.id {
+            | Some(ref jti) => self.enrichments.get(jti).await,
+            | None => None,
+        } {
+            return Ok(Arc::unwrap_or_clone(enriched));
+        }
+
+        let source = match source {
+            | EnrichmentSource::Introspection => {
+                let result = self.introspect(token).await?;
+                if !result.active {
+                    return Err(Error::InactiveToken);
+                }
+
+                result.claims_map()
+            }
+            | EnrichmentSource::Userinfo => self.user_info_as(token).await?,
+        };
+
+        claims.hydrate(source).map_err(|err| {
+            Error::Jwt(
+                jsonwebtoken::errors::ErrorKind::Json(Arc::new(err)).into(),
+            )
+        })?;
+
+        if let (Some(jti), Ok(ttl)) = (
+            claims.id.clone(),
+            (claims.expires_at - self.clock.now()).to_std(),
+        ) {
+            self.enrichments
+                .insert(jti, Arc::new(claims.clone()), ttl)
+                .await;
+        }
+
+        Ok(claims)
+    }
+}
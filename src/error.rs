@@ -1,22 +1,272 @@
+use std::{fmt, time::Duration};
+
+const BODY_SNIPPET_LEN: usize = 512;
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
+    #[error("invalid configuration: {0}")]
+    Config(String),
+
     #[error("url parse error: {0}")]
     UrlParse(#[from] url::ParseError),
 
     #[error("http error: {0}")]
     Http(#[from] reqwest::Error),
 
+    #[error("transport error: {0}")]
+    Transport(Box<dyn std::error::Error + Send + Sync>),
+
+    #[cfg(feature = "x5c")]
+    #[error("certificate error: {0}")]
+    Certificate(String),
+
+    #[cfg(feature = "jwe")]
+    #[error("token decryption error: {0}")]
+    Decryption(String),
+
+    #[cfg(feature = "watch")]
+    #[error("file watch error: {0}")]
+    Watch(#[from] notify::Error),
+
     #[error("jwt error: {0}")]
     Jwt(#[from] jsonwebtoken::errors::Error),
 
     #[error("uuid error: {0}")]
     Uuid(#[from] uuid::Error),
 
-    #[error("authentication error: code={code}, description={description:?}")]
-    Authentication {
+    #[error("admin api error: status={status}, message={message:?}")]
+    Admin {
+        status: reqwest::StatusCode,
+        message: Option<String>,
+    },
+
+    #[error("keycloak circuit breaker is open")]
+    CircuitOpen,
+
+    #[error("token is not active")]
+    InactiveToken,
+
+    #[error("token has been revoked")]
+    RevokedToken,
+
+    #[error("token has already been used")]
+    ReplayedToken,
+
+    #[error("token claims rejected: {0}")]
+    ClaimsRejected(crate::DenyReason),
+
+    #[error("token exceeds the configured {0} limit")]
+    LimitExceeded(&'static str),
+
+    #[error("unexpected token type: {0:?}")]
+    InvalidTokenType(Option<String>),
+
+    #[error("token issued to an unauthorized party: {0:?}")]
+    UnauthorizedParty(Option<String>),
+
+    #[error("no credentials registered for client `{0}`")]
+    UnknownClient(String),
+
+    #[error("{0} is not supported by the configured provider")]
+    Unsupported(&'static str),
+
+    #[error(
+        "{grant} requires a confidential client, but `{client}` is configured \
+         as public"
+    )]
+    PublicClient { client: String, grant: &'static str },
+
+    #[error("secret source error: {0}")]
+    SecretSource(String),
+
+    #[error("invalid user representation: {0}")]
+    InvalidUser(&'static str),
+
+    #[error("jti store error: {0}")]
+    JtiStore(String),
+
+    #[error("verification task failed: {0}")]
+    Blocking(#[from] tokio::task::JoinError),
+
+    #[error("policy evaluation error: {0}")]
+    Policy(String),
+
+    #[error(
+        "client registration error: code={code}, description={description:?}"
+    )]
+    Registration {
         code: String,
         description: Option<String>,
     },
+
+    #[error(
+        "authentication error: status={status}, code={code}, \
+         description={description:?}"
+    )]
+    Authentication {
+        status: reqwest::StatusCode,
+        code: OAuthErrorCode,
+        description: Option<String>,
+    },
+
+    #[error("rate limited: status={status}, retry_after={retry_after:?}")]
+    RateLimited {
+        status: reqwest::StatusCode,
+        retry_after: Option<Duration>,
+    },
+
+    #[error("unexpected response: status={status}, body={body_snippet:?}")]
+    UnexpectedResponse {
+        status: reqwest::StatusCode,
+        body_snippet: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize)]
+#[serde(from = "String")]
+pub enum OAuthErrorCode {
+    InvalidRequest,
+    InvalidClient,
+    InvalidGrant,
+    UnauthorizedClient,
+    UnsupportedGrantType,
+    InvalidScope,
+    InvalidToken,
+    AccessDenied,
+    ServerError,
+    TemporarilyUnavailable,
+    AuthorizationPending,
+    SlowDown,
+    ExpiredToken,
+    Other(String),
+}
+
+impl OAuthErrorCode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            | Self::InvalidRequest => "invalid_request",
+            | Self::InvalidClient => "invalid_client",
+            | Self::InvalidGrant => "invalid_grant",
+            | Self::UnauthorizedClient => "unauthorized_client",
+            | Self::UnsupportedGrantType => "unsupported_grant_type",
+            | Self::InvalidScope => "invalid_scope",
+            | Self::InvalidToken => "invalid_token",
+            | Self::AccessDenied => "access_denied",
+            | Self::ServerError => "server_error",
+            | Self::TemporarilyUnavailable => "temporarily_unavailable",
+            | Self::AuthorizationPending => "authorization_pending",
+            | Self::SlowDown => "slow_down",
+            | Self::ExpiredToken => "expired_token",
+            | Self::Other(code) => code,
+        }
+    }
+}
+
+impl From<String> for OAuthErrorCode {
+    fn from(code: String) -> Self {
+        match code.as_str() {
+            | "invalid_request" => Self::InvalidRequest,
+            | "invalid_client" => Self::InvalidClient,
+            | "invalid_grant" => Self::InvalidGrant,
+            | "unauthorized_client" => Self::UnauthorizedClient,
+            | "unsupported_grant_type" => Self::UnsupportedGrantType,
+            | "invalid_scope" => Self::InvalidScope,
+            | "invalid_token" => Self::InvalidToken,
+            | "access_denied" => Self::AccessDenied,
+            | "server_error" => Self::ServerError,
+            | "temporarily_unavailable" => Self::TemporarilyUnavailable,
+            | "authorization_pending" => Self::AuthorizationPending,
+            | "slow_down" => Self::SlowDown,
+            | "expired_token" => Self::ExpiredToken,
+            | _ => Self::Other(code),
+        }
+    }
+}
+
+impl fmt::Display for OAuthErrorCode {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Error {
+    pub fn is_transient(&self) -> bool {
+        match self {
+            | Error::Http(err) => {
+                err.is_timeout()
+                    || err.is_connect()
+                    || err.is_request()
+                    || err.status().is_some_and(|s| s.is_server_error())
+            }
+            | Error::Transport(_)
+            | Error::CircuitOpen
+            | Error::RateLimited { .. } => true,
+            | Error::Authentication { status, code, .. } => {
+                status.is_server_error()
+                    || matches!(
+                        code,
+                        OAuthErrorCode::ServerError
+                            | OAuthErrorCode::TemporarilyUnavailable
+                            | OAuthErrorCode::SlowDown
+                    )
+            }
+            | Error::Admin { status, .. }
+            | Error::UnexpectedResponse { status, .. } => {
+                status.is_server_error()
+            }
+            | _ => false,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn retry_after(&self) -> Option<Duration> {
+        match self {
+            | Error::RateLimited { retry_after, .. } => *retry_after,
+            | _ => None,
+        }
+    }
+
+    pub(crate) fn from_auth_response(resp: &crate::TransportResponse) -> Self {
+        #[derive(serde::Deserialize)]
+        struct ErrorDto {
+            error: OAuthErrorCode,
+            error_description: Option<String>,
+        }
+
+        let status = resp.status;
+        if crate::governor::is_rate_limited(status) {
+            return Error::RateLimited {
+                status,
+                retry_after: crate::governor::retry_after(&resp.headers),
+            };
+        }
+
+        match serde_json::from_slice::<ErrorDto>(&resp.body) {
+            | Ok(err) => Error::Authentication {
+                status,
+                code: err.error,
+                description: err.error_description,
+            },
+            | Err(_) => Error::unexpected_response(status, &resp.body),
+        }
+    }
+
+    pub(crate) fn unexpected_response(
+        status: reqwest::StatusCode,
+        body: &[u8],
+    ) -> Self {
+        let body = String::from_utf8_lossy(body);
+        let end = body
+            .char_indices()
+            .nth(BODY_SNIPPET_LEN)
+            .map_or(body.len(), |(idx, _)| idx);
+
+        Error::UnexpectedResponse {
+            status,
+            body_snippet: body[..end].to_owned(),
+        }
+    }
 }
@@ -0,0 +1,108 @@
+use crate::{
+    cache::TtlCache,
+    telemetry::{self, Redacted},
+    ClientGrant,
+    ReCloak,
+    Result,
+    TokenRequirements,
+    TokenResponse,
+};
+
+pub(crate) const ACCESS_TOKEN_TYPE: &str =
+    "urn:ietf:params:oauth:token-type:access_token";
+
+pub(crate) type ExchangeCache =
+    TtlCache<(arcstr::ArcStr, TokenRequirements), arcstr::ArcStr>;
+
+impl ReCloak {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self, actor_token))
+    )]
+    pub async fn impersonate(
+        &self,
+        user: &str,
+        actor_token: Option<&str>,
+    ) -> Result<TokenResponse> {
+        let actor = match actor_token {
+            | Some(token) => Some(self.validate_token(token).await?.claims),
+            | None => None,
+        };
+
+        let config = self.refresh_secret().await?;
+        let id = config.client.id.as_str();
+        let auth =
+            self.client_auth(id, &config.client.secret, "token_exchange")?;
+
+        let token = self
+            .login_client(ClientGrant::TokenExchange {
+                id,
+                auth,
+                subject_token: actor_token,
+                subject_token_type: actor_token.map(|_| ACCESS_TOKEN_TYPE),
+                requested_subject: Some(user),
+                requested_token_type: Some(ACCESS_TOKEN_TYPE),
+                audience: None,
+                scope: None,
+            })
+            .await?;
+
+        let logging = config.logging;
+        telemetry::trace_info!(
+            client_id = id,
+            requested_subject = user,
+            actor = %actor
+                .as_ref()
+                .map_or(Redacted::Missing, |claims| logging.subject(claims)),
+            actor_username = %actor
+                .as_ref()
+                .map_or(Redacted::Missing, |claims| logging.username(claims)),
+            "issued impersonation token"
+        );
+
+        Ok(token)
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self, subject_token))
+    )]
+    pub async fn exchange_token(
+        &self,
+        subject_token: &str,
+        requirements: &TokenRequirements,
+    ) -> Result<arcstr::ArcStr> {
+        let key = (arcstr::ArcStr::from(subject_token), requirements.clone());
+        if let Some(token) = self.exchanges.get(&key).await {
+            return Ok(token);
+        }
+
+        let config = self.refresh_secret().await?;
+        let id = config.client.id.as_str();
+        let auth =
+            self.client_auth(id, &config.client.secret, "token_exchange")?;
+        let scope = (!requirements.scopes.is_empty())
+            .then(|| requirements.scopes.join(" "));
+
+        let token = self
+            .login_client(ClientGrant::TokenExchange {
+                id,
+                auth,
+                subject_token: Some(subject_token),
+                subject_token_type: Some(ACCESS_TOKEN_TYPE),
+                requested_subject: None,
+                requested_token_type: Some(ACCESS_TOKEN_TYPE),
+                audience: requirements.audience.as_deref(),
+                scope: scope.as_deref(),
+            })
+            .await?;
+
+        if let Ok(ttl) = (token.expires_at() - self.clock.now()).to_std() {
+            self.exchanges
+                .insert(key, token.access_token.clone(), ttl)
+                .await;
+        }
+
+        Ok(token.access_token)
+    }
+}
@@ -0,0 +1,382 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use arc_swap::ArcSwap;
+use reqwest::{
+    header::{HeaderMap, RETRY_AFTER},
+    RequestBuilder,
+    StatusCode,
+};
+use tokio::sync::Semaphore;
+
+use crate::{
+    builder::RequestDecorator,
+    config::GovernorConfig,
+    telemetry,
+    Error,
+    HttpTransport,
+    Result,
+    TransportRequest,
+    TransportResponse,
+};
+
+#[derive(Debug)]
+pub(crate) struct Governor {
+    transport: Arc<dyn HttpTransport>,
+    headers: HeaderMap,
+    decorator: Option<RequestDecorator>,
+    permits: ArcSwap<Permits>,
+    breaker: Mutex<Breaker>,
+}
+
+#[derive(Debug)]
+struct Permits {
+    limit: usize,
+    semaphore: Arc<Semaphore>,
+}
+
+#[derive(Debug)]
+struct Breaker {
+    failures: u32,
+    open_until: Option<Instant>,
+    probing: bool,
+    backoff: Option<(Instant, StatusCode)>,
+    threshold: u32,
+    cooldown: Duration,
+}
+
+// held by the single request admitted while the circuit is half-open
+struct Probe<'a>(Option<&'a Mutex<Breaker>>);
+
+impl Governor {
+    pub(crate) fn new(
+        config: &GovernorConfig,
+        transport: Arc<dyn HttpTransport>,
+        headers: HeaderMap,
+        decorator: Option<RequestDecorator>,
+    ) -> Result<Self> {
+        config.check()?;
+
+        Ok(Self {
+            transport,
+            headers,
+            decorator,
+            permits: ArcSwap::from_pointee(Permits::new(
+                config.max_concurrent_requests,
+            )),
+            breaker: Mutex::new(Breaker {
+                failures: 0,
+                open_until: None,
+                probing: false,
+                backoff: None,
+                threshold: config.failure_threshold,
+                cooldown: config.cooldown,
+            }),
+        })
+    }
+
+    pub(crate) fn update(&self, config: &GovernorConfig) {
+        // requests already holding a permit finish against the previous limit
+        if self.permits.load().limit != config.max_concurrent_requests {
+            self.permits
+                .store(Arc::new(Permits::new(config.max_concurrent_requests)));
+        }
+
+        let mut breaker = self.breaker.lock().unwrap();
+        breaker.threshold = config.failure_threshold;
+        breaker.cooldown = config.cooldown;
+    }
+
+    #[inline]
+    pub(crate) async fn send(
+        &self,
+        req: RequestBuilder,
+    ) -> Result<TransportResponse> {
+        self.send_via(req, &*self.transport).await
+    }
+
+    pub(crate) async fn send_via(
+        &self,
+        req: RequestBuilder,
+        transport: &dyn HttpTransport,
+    ) -> Result<TransportResponse> {
+        let _probe = self.check()?;
+
+        let mut req = TransportRequest::from_reqwest(req.build()?)?;
+
+        for (name, value) in &self.headers {
+            req.headers.insert(name, value.clone());
+        }
+
+        if let Some(ref decorator) = self.decorator {
+            decorator.apply(&mut req);
+        }
+
+        let semaphore = self.permits.load().semaphore.clone();
+        let _permit = semaphore
+            .acquire_owned()
+            .await
+            .map_err(|err| Error::Transport(err.into()))?;
+        let result = transport.execute(req).await;
+
+        match result {
+            | Ok(ref resp) if is_rate_limited(resp.status) => {
+                if let Some(delay) = retry_after(&resp.headers) {
+                    self.record_backoff(resp.status, delay);
+                }
+
+                if resp.status.is_server_error() {
+                    self.record_failure();
+                }
+            }
+            | Ok(ref resp) if resp.status.is_server_error() => {
+                self.record_failure()
+            }
+            | Ok(_) => self.record_success(),
+            | Err(Error::Http(ref err))
+                if err.is_connect() || err.is_timeout() =>
+            {
+                self.record_failure()
+            }
+            | Err(Error::Transport(_)) => self.record_failure(),
+            | Err(_) => {}
+        }
+
+        result
+    }
+
+    fn check(&self) -> Result<Probe<'_>> {
+        let mut breaker = self.breaker.lock().unwrap();
+
+        if let Some((until, status)) = breaker.backoff {
+            let now = Instant::now();
+            if until > now {
+                return Err(Error::RateLimited {
+                    status,
+                    retry_after: Some(until - now),
+                });
+            }
+
+            breaker.backoff = None;
+        }
+
+        match breaker.open_until {
+            | Some(until) if until > Instant::now() => Err(Error::CircuitOpen),
+            | Some(_) if breaker.probing => Err(Error::CircuitOpen),
+            | Some(_) => {
+                // half-open: let a single trial request through, a failure
+                // re-opens the circuit since the failure count is retained.
+                breaker.probing = true;
+                Ok(Probe(Some(&self.breaker)))
+            }
+            | None => Ok(Probe(None)),
+        }
+    }
+
+    fn record_failure(&self) {
+        let mut breaker = self.breaker.lock().unwrap();
+
+        breaker.failures = breaker.failures.saturating_add(1);
+
+        if breaker.failures >= breaker.threshold {
+            telemetry::trace_warn!(
+                failures = breaker.failures,
+                cooldown = ?breaker.cooldown,
+                "keycloak circuit breaker opened",
+            );
+
+            breaker.open_until = Some(Instant::now() + breaker.cooldown);
+        }
+    }
+
+    #[inline]
+    fn record_success(&self) {
+        let mut breaker = self.breaker.lock().unwrap();
+        breaker.failures = 0;
+        breaker.open_until = None;
+    }
+
+    fn record_backoff(&self, status: StatusCode, delay: Duration) {
+        telemetry::trace_warn!(%status, retry_after = ?delay, "keycloak requested backoff");
+
+        self.breaker.lock().unwrap().backoff =
+            Some((Instant::now() + delay, status));
+    }
+}
+
+impl Permits {
+    #[inline]
+    fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            semaphore: Arc::new(Semaphore::new(limit)),
+        }
+    }
+}
+
+impl Drop for Probe<'_> {
+    fn drop(&mut self) {
+        if let Some(breaker) = self.0 {
+            breaker.lock().unwrap().probing = false;
+        }
+    }
+}
+
+#[inline]
+pub(crate) fn is_rate_limited(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::SERVICE_UNAVAILABLE
+}
+
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+
+    (at.with_timezone(&chrono::Utc) - chrono::Utc::now())
+        .to_std()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use reqwest::{header::HeaderMap, StatusCode};
+    use tokio::sync::Notify;
+
+    use super::Governor;
+    use crate::{
+        config::GovernorConfig,
+        testing::{self, MockTransport},
+        Error,
+        HttpTransport,
+        TransportFuture,
+        TransportRequest,
+        TransportResponse,
+    };
+
+    const URL: &str = "https://sso.internal:8443/health";
+
+    #[derive(Debug, Default)]
+    struct GatedTransport {
+        gate: Notify,
+    }
+
+    impl HttpTransport for Arc<GatedTransport> {
+        fn execute(&self, _: TransportRequest) -> TransportFuture<'_> {
+            Box::pin(async move {
+                self.gate.notified().await;
+
+                Ok(TransportResponse::new(StatusCode::OK, ""))
+            })
+        }
+    }
+
+    fn config(threshold: u32, cooldown: Duration) -> GovernorConfig {
+        GovernorConfig {
+            failure_threshold: threshold,
+            cooldown,
+            ..Default::default()
+        }
+    }
+
+    fn governor(
+        config: &GovernorConfig,
+        transport: impl HttpTransport,
+    ) -> Governor {
+        Governor::new(config, Arc::new(transport), HeaderMap::new(), None)
+            .unwrap()
+    }
+
+    async fn send(governor: &Governor) -> crate::Result<TransportResponse> {
+        governor.send(reqwest::Client::new().get(URL)).await
+    }
+
+    fn failing() -> Arc<MockTransport> {
+        let transport = Arc::new(MockTransport::new());
+        transport.respond(
+            "/health",
+            TransportResponse::new(StatusCode::INTERNAL_SERVER_ERROR, ""),
+        );
+
+        transport
+    }
+
+    #[tokio::test]
+    async fn rejects_zero_concurrency() {
+        let mut config = testing::config();
+        config.governor.max_concurrent_requests = 0;
+
+        let err = testing::builder(config).0.build().await.unwrap_err();
+        assert!(matches!(err, Error::Config(_)));
+
+        let kc = testing::builder(testing::config()).0.build().await.unwrap();
+        let mut config = testing::config();
+        config.governor.max_concurrent_requests = 0;
+        assert!(matches!(
+            kc.reconfigure(config).await,
+            Err(Error::Config(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn opens_after_threshold() {
+        let governor = governor(&config(2, Duration::from_secs(60)), failing());
+
+        assert!(send(&governor).await.is_ok());
+        assert!(send(&governor).await.is_ok());
+        assert!(matches!(send(&governor).await, Err(Error::CircuitOpen)));
+    }
+
+    #[tokio::test]
+    async fn half_open_admits_a_single_probe() {
+        let transport = Arc::new(GatedTransport::default());
+        let governor =
+            Arc::new(governor(&config(1, Duration::ZERO), transport.clone()));
+        governor.record_failure();
+
+        let probe = tokio::spawn({
+            let governor = governor.clone();
+            async move { send(&governor).await }
+        });
+        tokio::task::yield_now().await;
+
+        assert!(matches!(send(&governor).await, Err(Error::CircuitOpen)));
+
+        transport.gate.notify_one();
+        assert!(probe.await.unwrap().is_ok());
+
+        transport.gate.notify_one();
+        assert!(send(&governor).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn failed_probe_reopens() {
+        let governor = governor(&config(1, Duration::ZERO), failing());
+        governor.record_failure();
+        governor.update(&config(1, Duration::from_secs(60)));
+
+        assert!(send(&governor).await.is_ok());
+        assert!(matches!(send(&governor).await, Err(Error::CircuitOpen)));
+    }
+
+    #[tokio::test]
+    async fn update_applies_new_limits() {
+        let governor = governor(&config(5, Duration::from_secs(60)), failing());
+        governor.update(&GovernorConfig {
+            max_concurrent_requests: 1,
+            ..config(1, Duration::from_secs(60))
+        });
+
+        assert_eq!(governor.permits.load().limit, 1);
+        assert!(send(&governor).await.is_ok());
+        assert!(matches!(send(&governor).await, Err(Error::CircuitOpen)));
+    }
+}
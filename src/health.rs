@@ -0,0 +1,209 @@
+use std::{collections::HashMap, str::Chars};
+
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use crate::{Error, ReCloak, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthProbe {
+    Overall,
+    Ready,
+    Live,
+    Started,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum HealthStatus {
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HealthReport {
+    pub status: HealthStatus,
+
+    #[serde(default)]
+    pub checks: Vec<HealthCheck>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HealthCheck {
+    pub name: String,
+    pub status: HealthStatus,
+
+    #[serde(default)]
+    pub data: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    pub samples: Vec<MetricSample>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricSample {
+    pub name: String,
+    pub labels: HashMap<String, String>,
+    pub value: f64,
+}
+
+impl HealthProbe {
+    #[inline]
+    const fn path_segment(self) -> Option<&'static str> {
+        match self {
+            | HealthProbe::Overall => None,
+            | HealthProbe::Ready => Some("ready"),
+            | HealthProbe::Live => Some("live"),
+            | HealthProbe::Started => Some("started"),
+        }
+    }
+}
+
+impl HealthReport {
+    #[inline]
+    pub fn is_up(&self) -> bool {
+        self.status == HealthStatus::Up
+    }
+
+    #[inline]
+    pub fn check(&self, name: impl AsRef<str>) -> Option<&HealthCheck> {
+        self.checks.iter().find(|check| check.name == name.as_ref())
+    }
+}
+
+impl Metrics {
+    pub fn parse(text: &str) -> Self {
+        let samples = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(MetricSample::parse)
+            .collect();
+
+        Self { samples }
+    }
+
+    #[inline]
+    pub fn samples<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = &'a MetricSample> + 'a {
+        self.samples
+            .iter()
+            .filter(move |sample| sample.name == name)
+    }
+
+    pub fn find(
+        &self,
+        name: &str,
+        labels: &[(&str, &str)],
+    ) -> Option<&MetricSample> {
+        self.samples.iter().find(|sample| {
+            sample.name == name
+                && labels.iter().all(|(key, value)| {
+                    sample.labels.get(*key).is_some_and(|v| v == value)
+                })
+        })
+    }
+
+    #[inline]
+    pub fn total(&self, name: &str) -> f64 {
+        self.samples(name).map(|sample| sample.value).sum()
+    }
+}
+
+impl MetricSample {
+    fn parse(line: &str) -> Option<Self> {
+        let name_end = line
+            .find(|c: char| c == '{' || c.is_whitespace())
+            .unwrap_or(line.len());
+        let (name, mut rest) = line.split_at(name_end);
+
+        let mut labels = HashMap::new();
+        if let Some(inner) = rest.strip_prefix('{') {
+            let mut chars = inner.chars();
+            labels = parse_labels(&mut chars)?;
+            rest = chars.as_str();
+        }
+
+        let value = rest.split_whitespace().next()?.parse().ok()?;
+
+        Some(Self {
+            name: name.to_owned(),
+            labels,
+            value,
+        })
+    }
+}
+
+fn parse_labels(chars: &mut Chars<'_>) -> Option<HashMap<String, String>> {
+    let mut labels = HashMap::new();
+
+    loop {
+        let mut key = String::new();
+        loop {
+            match chars.next()? {
+                | '}' if key.trim().is_empty() => return Some(labels),
+                | '=' => break,
+                | ',' | ' ' => {}
+                | c => key.push(c),
+            }
+        }
+
+        if chars.next()? != '"' {
+            return None;
+        }
+
+        let mut value = String::new();
+        loop {
+            match chars.next()? {
+                | '\\' => match chars.next()? {
+                    | 'n' => value.push('\n'),
+                    | c => value.push(c),
+                },
+                | '"' => break,
+                | c => value.push(c),
+            }
+        }
+
+        labels.insert(key, value);
+    }
+}
+
+impl ReCloak {
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn health(&self, probe: HealthProbe) -> Result<HealthReport> {
+        self.config.load().require_keycloak("the health endpoint")?;
+
+        let mut url = self.urls.load().health.clone();
+        if let Some(segment) = probe.path_segment() {
+            url.path_segments_mut().unwrap().push(segment);
+        }
+
+        let resp = self.governor.send(self.client.get(url)).await?;
+
+        resp.json()
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn metrics(&self) -> Result<Option<Metrics>> {
+        self.config
+            .load()
+            .require_keycloak("the metrics endpoint")?;
+
+        let url = self.urls.load().metrics.clone();
+        let resp = self.governor.send(self.client.get(url)).await?;
+
+        if resp.status == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        if !resp.status.is_success() {
+            return Err(Error::unexpected_response(resp.status, &resp.body));
+        }
+
+        Ok(Some(Metrics::parse(&String::from_utf8_lossy(&resp.body))))
+    }
+}
@@ -0,0 +1,357 @@
+use std::{fmt, time::Duration};
+
+use arcstr::ArcStr;
+use jsonwebtoken::{
+    self as jwt,
+    errors::{Error as JwtError, ErrorKind as JwtErrorKind},
+    Algorithm,
+    DecodingKey,
+};
+
+use crate::{Claims, Error, JwtEncoder, ReCloak, Result};
+
+pub(crate) const HOP_TOKEN_TYPE: &str = "Hop";
+
+// carries the source token's jti so revoking it also revokes its hop tokens.
+const SOURCE_JTI_CLAIM: &str = "source_jti";
+
+const DEFAULT_LIFETIME: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HopClaim {
+    Username,
+    Email,
+    Name,
+    RealmRoles,
+    ClientRoles,
+    Scope,
+    Acr,
+    Organizations,
+    Extra(ArcStr),
+}
+
+#[derive(Clone)]
+pub struct HopTokens {
+    encoder: Option<JwtEncoder>,
+    key: DecodingKey,
+    algorithm: Algorithm,
+    issuer: ArcStr,
+    audience: ArcStr,
+    lifetime: Duration,
+    claims: Vec<HopClaim>,
+}
+
+impl HopTokens {
+    pub fn new(
+        issuer: impl Into<ArcStr>,
+        encoder: JwtEncoder,
+        key: DecodingKey,
+    ) -> Self {
+        let algorithm = encoder.header.alg;
+
+        Self {
+            encoder: Some(encoder),
+            ..Self::verifier(issuer, algorithm, key)
+        }
+    }
+
+    #[inline]
+    pub fn from_secret(issuer: impl Into<ArcStr>, secret: &[u8]) -> Self {
+        Self::new(
+            issuer,
+            JwtEncoder::from_secret(secret),
+            DecodingKey::from_secret(secret),
+        )
+    }
+
+    pub fn verifier(
+        issuer: impl Into<ArcStr>,
+        algorithm: Algorithm,
+        key: DecodingKey,
+    ) -> Self {
+        let issuer = issuer.into();
+
+        Self {
+            encoder: None,
+            key,
+            algorithm,
+            audience: issuer.clone(),
+            issuer,
+            lifetime: DEFAULT_LIFETIME,
+            claims: vec![
+                HopClaim::Username,
+                HopClaim::RealmRoles,
+                HopClaim::ClientRoles,
+                HopClaim::Scope,
+            ],
+        }
+    }
+
+    #[inline]
+    pub fn audience(mut self, audience: impl Into<ArcStr>) -> Self {
+        self.audience = audience.into();
+        self
+    }
+
+    #[inline]
+    pub const fn lifetime(mut self, lifetime: Duration) -> Self {
+        self.lifetime = lifetime;
+        self
+    }
+
+    #[inline]
+    pub fn claims(
+        mut self,
+        claims: impl IntoIterator<Item = HopClaim>,
+    ) -> Self {
+        self.claims = claims.into_iter().collect();
+        self
+    }
+
+    #[inline]
+    pub fn issuer(&self) -> &str {
+        &self.issuer
+    }
+
+    fn mint(
+        &self,
+        claims: &Claims,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<String> {
+        let encoder = self.encoder.as_ref().ok_or_else(|| {
+            Error::Config(
+                "hop tokens are configured for verification only".into(),
+            )
+        })?;
+
+        let expires_at = chrono::Duration::from_std(self.lifetime)
+            .ok()
+            .and_then(|lifetime| now.checked_add_signed(lifetime))
+            .map_or(claims.expires_at, |exp| exp.min(claims.expires_at));
+        let mut hop = Claims::new(
+            self.issuer.clone(),
+            claims.subject.clone(),
+            self.audience.clone(),
+            now,
+            expires_at,
+        );
+        hop.token_type = Some(HOP_TOKEN_TYPE.to_owned());
+        hop.authorized_party.clone_from(&claims.authorized_party);
+        if let Some(ref jti) = claims.id {
+            hop.extra
+                .insert(SOURCE_JTI_CLAIM.to_owned(), jti.as_str().into());
+        }
+
+        for claim in &self.claims {
+            match claim {
+                | HopClaim::Username => {
+                    hop.username.clone_from(&claims.username)
+                }
+                | HopClaim::Email => {
+                    hop.email.clone_from(&claims.email);
+                    hop.email_verified = claims.email_verified;
+                }
+                | HopClaim::Name => hop.name.clone_from(&claims.name),
+                | HopClaim::RealmRoles => hop.realm.clone_from(&claims.realm),
+                | HopClaim::ClientRoles => {
+                    hop.resource.clone_from(&claims.resource)
+                }
+                | HopClaim::Scope => copy_extra(&mut hop, claims, "scope"),
+                | HopClaim::Acr => {
+                    hop.auth_class_reference
+                        .clone_from(&claims.auth_class_reference);
+                }
+                | HopClaim::Organizations => {
+                    hop.organizations.clone_from(&claims.organizations)
+                }
+                | HopClaim::Extra(name) => copy_extra(&mut hop, claims, name),
+            }
+        }
+
+        encoder.encode(&hop)
+    }
+
+    fn verify(
+        &self,
+        token: &str,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Claims> {
+        let mut vld = jwt::Validation::new(self.algorithm);
+        vld.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+        vld.set_issuer(&[&self.issuer]);
+        vld.set_audience(&[&self.audience]);
+        // expiry is checked against the client's clock instead.
+        vld.validate_exp = false;
+
+        let claims = jwt::decode::<Claims>(token, &self.key, &vld)?.claims;
+        if claims.expires_at <= now {
+            return Err(JwtError::from(JwtErrorKind::ExpiredSignature).into());
+        }
+
+        if claims.token_type.as_deref() != Some(HOP_TOKEN_TYPE) {
+            return Err(JwtError::from(JwtErrorKind::InvalidToken).into());
+        }
+
+        Ok(claims)
+    }
+}
+
+impl fmt::Debug for HopTokens {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HopTokens")
+            .field("encoder", &self.encoder)
+            .field("key", &"[redacted]")
+            .field("algorithm", &self.algorithm)
+            .field("issuer", &self.issuer)
+            .field("audience", &self.audience)
+            .field("lifetime", &self.lifetime)
+            .field("claims", &self.claims)
+            .finish()
+    }
+}
+
+impl ReCloak {
+    #[inline]
+    pub fn mint_hop_token(&self, claims: &Claims) -> Result<String> {
+        self.hop_tokens()?.mint(claims, self.clock.now())
+    }
+
+    pub fn verify_hop_token(&self, token: &str) -> Result<Claims> {
+        let hop = self.hop_tokens()?;
+        self.decoder.current().check_limits(token)?;
+
+        hop.verify(token, self.clock.now())
+    }
+
+    pub async fn validate_hop_token(&self, token: &str) -> Result<Claims> {
+        let claims = self.verify_hop_token(token)?;
+        self.check_revoked(&claims).await?;
+
+        if let Some(source) =
+            claims.claim(SOURCE_JTI_CLAIM).and_then(|jti| jti.as_str())
+        {
+            self.check_revoked_jti(source).await?;
+        }
+
+        Ok(claims)
+    }
+
+    #[inline]
+    pub fn is_hop_token(&self, token: &str) -> bool {
+        self.hop_tokens.as_ref().is_some_and(|hop| {
+            self.decoder.current().check_limits(token).is_ok()
+                && crate::jwt::unverified_issuer(token).as_deref()
+                    == Some(hop.issuer())
+        })
+    }
+
+    #[inline]
+    fn hop_tokens(&self) -> Result<&HopTokens> {
+        self.hop_tokens.as_deref().ok_or_else(|| {
+            Error::Config("no hop token issuer is configured".to_owned())
+        })
+    }
+}
+
+#[inline]
+fn copy_extra(hop: &mut Claims, claims: &Claims, name: &str) {
+    if let Some(value) = claims.claim(name) {
+        hop.extra.insert(name.to_owned(), value.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{jti::MemoryJtiStore, testing};
+
+    const HOP_ISSUER: &str = "https://mesh.internal";
+
+    async fn recloak(hop: HopTokens) -> Arc<ReCloak> {
+        let (builder, _) = testing::builder(testing::config());
+
+        builder
+            .hop_tokens(hop)
+            .jti_store(MemoryJtiStore::default())
+            .build()
+            .await
+            .unwrap()
+    }
+
+    async fn source_claims(kc: &ReCloak) -> Claims {
+        let token = testing::sign(&testing::claims(chrono::Utc::now()));
+
+        kc.validate_token(&token).await.unwrap().claims
+    }
+
+    #[tokio::test]
+    async fn round_trips_minted_token() {
+        let kc = recloak(HopTokens::from_secret(HOP_ISSUER, b"hop")).await;
+        let claims = source_claims(&kc).await;
+
+        let token = kc.mint_hop_token(&claims).unwrap();
+        assert!(kc.is_hop_token(&token));
+
+        let hop = kc.validate_hop_token(&token).await.unwrap();
+        assert_eq!(hop.subject, claims.subject);
+        assert_eq!(hop.username, claims.username);
+        assert_eq!(hop.token_type.as_deref(), Some(HOP_TOKEN_TYPE));
+    }
+
+    #[tokio::test]
+    async fn rejects_oversized_token() {
+        let hop = HopTokens::from_secret(HOP_ISSUER, b"hop")
+            .claims([HopClaim::Extra("blob".into())]);
+        let kc = recloak(hop).await;
+        let mut claims = source_claims(&kc).await;
+        let blob = "x".repeat(32 * 1024);
+        claims.extra.insert("blob".into(), blob.into());
+
+        let token = kc.mint_hop_token(&claims).unwrap();
+
+        assert!(!kc.is_hop_token(&token));
+        assert!(matches!(
+            kc.verify_hop_token(&token),
+            Err(Error::LimitExceeded(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_token_from_another_key() {
+        let kc = recloak(HopTokens::from_secret(HOP_ISSUER, b"hop")).await;
+        let other =
+            recloak(HopTokens::from_secret(HOP_ISSUER, b"not-hop")).await;
+        let claims = source_claims(&kc).await;
+
+        let token = other.mint_hop_token(&claims).unwrap();
+
+        assert!(kc.verify_hop_token(&token).is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_access_token_as_hop_token() {
+        let secret = testing::SECRET.as_bytes();
+        let kc = recloak(HopTokens::from_secret(HOP_ISSUER, secret)).await;
+        let mut claims = testing::claims(chrono::Utc::now());
+        claims["iss"] = HOP_ISSUER.into();
+        claims["aud"] = HOP_ISSUER.into();
+
+        assert!(kc.verify_hop_token(&testing::sign(&claims)).is_err());
+    }
+
+    #[tokio::test]
+    async fn revoking_source_token_revokes_hop_token() {
+        let kc = recloak(HopTokens::from_secret(HOP_ISSUER, b"hop")).await;
+        let claims = source_claims(&kc).await;
+        let token = kc.mint_hop_token(&claims).unwrap();
+
+        kc.revoke_claims(&claims).await.unwrap();
+
+        assert!(matches!(
+            kc.validate_hop_token(&token).await,
+            Err(Error::RevokedToken)
+        ));
+    }
+}
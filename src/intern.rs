@@ -0,0 +1,137 @@
+use std::{cell::RefCell, collections::HashSet, fmt};
+
+use arcstr::ArcStr;
+use serde::{de, ser::SerializeSeq, Deserializer, Serializer};
+use serde_with::{de::DeserializeAsWrap, DeserializeAs, SerializeAs};
+use smallvec::SmallVec;
+
+const MAX_INTERNED: usize = 4096;
+
+thread_local! {
+    static INTERNED: RefCell<HashSet<ArcStr>> = RefCell::new(HashSet::new());
+}
+
+pub(crate) struct Interned;
+
+pub(crate) struct InternedOneOrMany;
+
+pub(crate) fn intern(value: &str) -> ArcStr {
+    INTERNED.with_borrow_mut(|interned| {
+        if let Some(value) = interned.get(value) {
+            return value.clone();
+        }
+
+        let value = ArcStr::from(value);
+        if interned.len() < MAX_INTERNED {
+            interned.insert(value.clone());
+        }
+
+        value
+    })
+}
+
+impl<'de> DeserializeAs<'de, ArcStr> for Interned {
+    fn deserialize_as<D>(deserializer: D) -> Result<ArcStr, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl de::Visitor<'_> for Visitor {
+            type Value = ArcStr;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a string")
+            }
+
+            #[inline]
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<ArcStr, E> {
+                Ok(intern(value))
+            }
+        }
+
+        deserializer.deserialize_str(Visitor)
+    }
+}
+
+impl SerializeAs<ArcStr> for Interned {
+    #[inline]
+    fn serialize_as<S>(
+        source: &ArcStr,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(source)
+    }
+}
+
+impl<'de, const N: usize> DeserializeAs<'de, SmallVec<[ArcStr; N]>>
+    for InternedOneOrMany
+{
+    fn deserialize_as<D>(
+        deserializer: D,
+    ) -> Result<SmallVec<[ArcStr; N]>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct Visitor<const N: usize>;
+
+        impl<'de, const N: usize> de::Visitor<'de> for Visitor<N> {
+            type Value = SmallVec<[ArcStr; N]>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a string or a sequence of strings")
+            }
+
+            #[inline]
+            fn visit_str<E: de::Error>(
+                self,
+                value: &str,
+            ) -> Result<Self::Value, E> {
+                let mut values = SmallVec::new();
+                values.push(intern(value));
+
+                Ok(values)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::SeqAccess<'de>,
+            {
+                let mut values = SmallVec::new();
+                while let Some(value) =
+                    seq.next_element::<DeserializeAsWrap<ArcStr, Interned>>()?
+                {
+                    values.push(value.into_inner());
+                }
+
+                Ok(values)
+            }
+        }
+
+        deserializer.deserialize_any(Visitor::<N>)
+    }
+}
+
+impl<const N: usize> SerializeAs<SmallVec<[ArcStr; N]>> for InternedOneOrMany {
+    fn serialize_as<S>(
+        source: &SmallVec<[ArcStr; N]>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if let [value] = source.as_slice() {
+            return serializer.serialize_str(value);
+        }
+
+        let mut seq = serializer.serialize_seq(Some(source.len()))?;
+        for value in source {
+            seq.serialize_element(value.as_str())?;
+        }
+
+        seq.end()
+    }
+}
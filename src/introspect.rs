@@ -0,0 +1,272 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use serde_with::TimestampSeconds;
+
+use crate::{cache::TtlCache, Claims, Error, ReCloak, Result, TokenData};
+
+// keyed by the token's SHA-256 digest so cached entries never hold live
+// credentials.
+pub(crate) type IntrospectionCache = TtlCache<CacheKey, Arc<Introspection>>;
+
+type CacheKey = [u8; ring::digest::SHA256_OUTPUT_LEN];
+
+#[serde_with::serde_as]
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Introspection {
+    #[serde(rename = "active")]
+    pub active: bool,
+
+    #[serde(rename = "sub")]
+    pub subject: Option<String>,
+
+    #[serde(rename = "exp", default)]
+    #[serde_as(as = "Option<TimestampSeconds<i64>>")]
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+
+    #[serde(rename = "scope")]
+    pub scope: Option<String>,
+
+    #[serde(rename = "client_id")]
+    pub client_id: Option<String>,
+
+    #[serde(rename = "username")]
+    pub username: Option<String>,
+
+    #[serde(rename = "token_type")]
+    pub token_type: Option<String>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl Introspection {
+    pub fn to_claims(&self) -> Result<Claims> {
+        serde_json::from_value(self.claims_map().into()).map_err(|err| {
+            Error::Jwt(
+                jsonwebtoken::errors::ErrorKind::Json(Arc::new(err)).into(),
+            )
+        })
+    }
+
+    pub(crate) fn claims_map(
+        &self,
+    ) -> serde_json::Map<String, serde_json::Value> {
+        let mut claims = self
+            .extra
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect::<serde_json::Map<_, _>>();
+
+        let known = [
+            ("sub", self.subject.as_deref()),
+            ("scope", self.scope.as_deref()),
+            ("azp", self.client_id.as_deref()),
+            ("preferred_username", self.username.as_deref()),
+            ("typ", self.token_type.as_deref()),
+        ];
+
+        for (name, value) in known {
+            if let Some(value) = value {
+                claims.entry(name).or_insert_with(|| value.into());
+            }
+        }
+
+        if let Some(exp) = self.expires_at {
+            claims.insert("exp".into(), exp.timestamp().into());
+        }
+
+        claims
+    }
+
+    fn cache_ttl(
+        &self,
+        config: &crate::config::IntrospectionConfig,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Duration {
+        if !self.active {
+            return config.negative_ttl;
+        }
+
+        let remaining = self
+            .expires_at
+            .and_then(|exp| (exp - now).to_std().ok())
+            .unwrap_or(config.cache_ttl);
+
+        remaining.min(config.cache_ttl)
+    }
+}
+
+impl ReCloak {
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, token)))]
+    pub async fn introspect(&self, token: &str) -> Result<Arc<Introspection>> {
+        let key = cache_key(token);
+        if let Some(result) = self.introspections.get(&key).await {
+            return Ok(result);
+        }
+
+        let result = Arc::new(self.introspect_uncached(token).await?);
+        let ttl = result
+            .cache_ttl(&self.config.load().introspection, self.clock.now());
+
+        self.introspections.insert(key, result.clone(), ttl).await;
+
+        Ok(result)
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, token)))]
+    pub async fn introspect_claims(&self, token: &str) -> Result<TokenData> {
+        let result = self.introspect(token).await?;
+        if !result.active {
+            return Err(Error::InactiveToken);
+        }
+
+        Ok(TokenData {
+            header: jsonwebtoken::Header::default(),
+            claims: result.to_claims()?,
+        })
+    }
+
+    async fn introspect_uncached(&self, token: &str) -> Result<Introspection> {
+        #[derive(serde::Serialize)]
+        struct IntrospectionForm<'a> {
+            token: &'a str,
+            client_id: &'a str,
+
+            #[serde(flatten)]
+            auth: crate::ClientAuth<'a>,
+        }
+
+        let url = self
+            .urls
+            .load()
+            .introspect
+            .clone()
+            .ok_or(crate::Error::Unsupported("token introspection"))?;
+        let config = self.config.load_full();
+        let form = IntrospectionForm {
+            token,
+            client_id: &config.client.id,
+            auth: self.client_auth(
+                &config.client.id,
+                &config.client.secret,
+                "token introspection",
+            )?,
+        };
+
+        let resp = self
+            .governor
+            .send(self.client.post(url).form(&form))
+            .await?;
+
+        if resp.status.is_success() {
+            resp.json()
+        } else {
+            Err(crate::Error::from_auth_response(&resp))
+        }
+    }
+}
+
+fn cache_key(token: &str) -> CacheKey {
+    let digest = ring::digest::digest(&ring::digest::SHA256, token.as_bytes());
+
+    let mut key = CacheKey::default();
+    key.copy_from_slice(digest.as_ref());
+
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{testing, Error};
+
+    const INTROSPECT_PATH: &str =
+        "/realms/test/protocol/openid-connect/introspect";
+
+    async fn introspected(
+        overrides: serde_json::Value,
+        configure: impl FnOnce(&mut crate::Config),
+    ) -> crate::Result<crate::TokenData> {
+        let mut config = testing::config();
+        config.introspection.opaque_tokens = true;
+        configure(&mut config);
+
+        let (builder, transport) = testing::builder(config);
+        let kc = builder.build().await.unwrap();
+
+        let mut result = testing::claims(chrono::Utc::now());
+        result["active"] = true.into();
+        for (name, value) in overrides.as_object().unwrap() {
+            result[name] = value.clone();
+        }
+        transport.route(INTROSPECT_PATH, result);
+
+        kc.validate_token("opaque-token").await
+    }
+
+    #[tokio::test]
+    async fn accepts_token_issued_to_this_client() {
+        let data = introspected(json!({}), |_| {}).await.unwrap();
+
+        assert_eq!(data.claims.username.as_deref(), Some("alice"));
+    }
+
+    #[tokio::test]
+    async fn caches_by_token_digest() {
+        let mut config = testing::config();
+        config.introspection.opaque_tokens = true;
+
+        let (builder, transport) = testing::builder(config);
+        let kc = builder.build().await.unwrap();
+        transport.route(INTROSPECT_PATH, json!({ "active": true }));
+
+        kc.introspect("opaque-token").await.unwrap();
+        kc.introspect("opaque-token").await.unwrap();
+        kc.introspect("other-token").await.unwrap();
+
+        let calls = transport
+            .requests()
+            .into_iter()
+            .filter(|req| req.url.path() == INTROSPECT_PATH)
+            .count();
+        assert_eq!(calls, 2);
+
+        let key = super::cache_key("opaque-token");
+        assert!(kc.introspections.get(&key).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn rejects_inactive_token() {
+        let err = introspected(json!({ "active": false }), |_| {}).await;
+
+        assert!(matches!(err, Err(Error::InactiveToken)));
+    }
+
+    #[tokio::test]
+    async fn rejects_wrong_audience() {
+        let err = introspected(json!({ "aud": "other-app" }), |_| {}).await;
+
+        assert!(matches!(err, Err(Error::Jwt(ref err))
+            if *err.kind() == jsonwebtoken::errors::ErrorKind::InvalidAudience));
+    }
+
+    #[tokio::test]
+    async fn rejects_wrong_issuer() {
+        let issuer = json!({ "iss": "https://evil.example.com/realms/test" });
+        let err = introspected(issuer, |_| {}).await;
+
+        assert!(matches!(err, Err(Error::Jwt(ref err))
+            if *err.kind() == jsonwebtoken::errors::ErrorKind::InvalidIssuer));
+    }
+
+    #[tokio::test]
+    async fn rejects_unauthorized_party() {
+        let err = introspected(json!({ "azp": "other-app" }), |config| {
+            config.token.allowed_parties = Some(vec!["app".to_owned()]);
+        })
+        .await;
+
+        assert!(matches!(err, Err(Error::UnauthorizedParty(_))));
+    }
+}
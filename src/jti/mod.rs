@@ -0,0 +1,354 @@
+#[cfg(feature = "redis")]
+mod redis;
+
+use std::{fmt, future::Future, pin::Pin, sync::Arc, time::Duration};
+
+#[cfg(feature = "redis")]
+pub use self::redis::RedisJtiStore;
+use crate::{
+    cache::TtlCache,
+    telemetry::{self, TOKEN_TARGET},
+    Claims,
+    Error,
+    Result,
+    TokenData,
+};
+
+const DEFAULT_CAPACITY: usize = 100_000;
+const MIN_TTL: Duration = Duration::from_secs(1);
+
+pub type JtiFuture<'a, T> =
+    Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+pub trait JtiStore: fmt::Debug + Send + Sync + 'static {
+    fn is_revoked<'a>(&'a self, jti: &'a str) -> JtiFuture<'a, bool>;
+
+    fn revoke<'a>(&'a self, jti: &'a str, ttl: Duration) -> JtiFuture<'a, ()>;
+
+    fn consume<'a>(
+        &'a self,
+        jti: &'a str,
+        ttl: Duration,
+    ) -> JtiFuture<'a, bool>;
+
+    #[inline]
+    fn check(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct MemoryJtiStore {
+    revoked: TtlCache<Box<str>, ()>,
+    consumed: TtlCache<Box<str>, ()>,
+    capacity: usize,
+}
+
+impl MemoryJtiStore {
+    #[inline]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            revoked: TtlCache::new(capacity),
+            consumed: TtlCache::new(capacity),
+            capacity,
+        }
+    }
+}
+
+impl Default for MemoryJtiStore {
+    #[inline]
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl JtiStore for MemoryJtiStore {
+    fn check(&self) -> Result<()> {
+        if self.capacity == 0 {
+            return Err(Error::Config(
+                "memory jti store capacity must be greater than zero".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn is_revoked<'a>(&'a self, jti: &'a str) -> JtiFuture<'a, bool> {
+        Box::pin(async move { Ok(self.revoked.get(jti).await.is_some()) })
+    }
+
+    fn revoke<'a>(&'a self, jti: &'a str, ttl: Duration) -> JtiFuture<'a, ()> {
+        Box::pin(async move {
+            self.revoked
+                .try_insert(jti.into(), (), ttl)
+                .await
+                .map_err(|_| store_full("revoked"))
+        })
+    }
+
+    fn consume<'a>(
+        &'a self,
+        jti: &'a str,
+        ttl: Duration,
+    ) -> JtiFuture<'a, bool> {
+        Box::pin(async move {
+            self.consumed
+                .insert_new(jti.into(), (), ttl)
+                .await
+                .map_err(|_| store_full("consumed"))
+        })
+    }
+}
+
+impl<S> JtiStore for Arc<S>
+where
+    S: JtiStore + ?Sized,
+{
+    #[inline]
+    fn is_revoked<'a>(&'a self, jti: &'a str) -> JtiFuture<'a, bool> {
+        (**self).is_revoked(jti)
+    }
+
+    #[inline]
+    fn revoke<'a>(&'a self, jti: &'a str, ttl: Duration) -> JtiFuture<'a, ()> {
+        (**self).revoke(jti, ttl)
+    }
+
+    #[inline]
+    fn consume<'a>(
+        &'a self,
+        jti: &'a str,
+        ttl: Duration,
+    ) -> JtiFuture<'a, bool> {
+        (**self).consume(jti, ttl)
+    }
+
+    #[inline]
+    fn check(&self) -> Result<()> {
+        (**self).check()
+    }
+}
+
+impl crate::ReCloak {
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn revoke_jti(
+        &self,
+        jti: &str,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<()> {
+        self.jti_store()?
+            .revoke(jti, self.ttl_until(expires_at))
+            .await?;
+
+        telemetry::trace_info!(target: TOKEN_TARGET, "token revoked by jti");
+
+        Ok(())
+    }
+
+    #[inline]
+    pub async fn revoke_claims(&self, claims: &Claims) -> Result<()> {
+        self.revoke_jti(claim_id(claims)?, claims.expires_at).await
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, token)))]
+    pub async fn consume_token(&self, token: &str) -> Result<TokenData> {
+        let data = self.validate_token(token).await?;
+        self.consume_claims(&data.claims).await?;
+
+        Ok(data)
+    }
+
+    pub(crate) async fn consume_claims(&self, claims: &Claims) -> Result<()> {
+        let ttl = self.ttl_until(claims.expires_at);
+
+        if !self.jti_store()?.consume(claim_id(claims)?, ttl).await? {
+            let logging = self.logging();
+            telemetry::trace_warn!(
+                target: TOKEN_TARGET,
+                jti = ?claims.id,
+                subject = %logging.subject(claims),
+                username = %logging.username(claims),
+                "rejected replayed token"
+            );
+
+            return Err(Error::ReplayedToken);
+        }
+
+        Ok(())
+    }
+
+    pub(crate) async fn check_revoked(&self, claims: &Claims) -> Result<()> {
+        if self.jti_store.is_none() {
+            return Ok(());
+        }
+
+        self.check_revoked_jti(claim_id(claims)?).await
+    }
+
+    pub(crate) async fn check_revoked_jti(&self, jti: &str) -> Result<()> {
+        let Some(ref store) = self.jti_store else {
+            return Ok(());
+        };
+
+        if store.is_revoked(jti).await? {
+            return Err(Error::RevokedToken);
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub(crate) fn jti_store(&self) -> Result<&dyn JtiStore> {
+        self.jti_store.as_deref().ok_or_else(|| {
+            Error::Config("no jti store is configured".to_owned())
+        })
+    }
+
+    // entries must outlive the leeway the decoder still accepts after `exp`.
+    #[inline]
+    pub(crate) fn ttl_until(
+        &self,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Duration {
+        let leeway = chrono::Duration::seconds(
+            crate::jwt::VALIDATION_LEEWAY.as_secs() as i64,
+        );
+
+        (expires_at + leeway - self.clock.now())
+            .to_std()
+            .unwrap_or_default()
+            .max(MIN_TTL)
+    }
+}
+
+#[inline]
+fn store_full(set: &str) -> Error {
+    Error::JtiStore(format!("{set} jti set is full"))
+}
+
+#[inline]
+fn claim_id(claims: &Claims) -> Result<&str> {
+    claims
+        .id
+        .as_deref()
+        .ok_or_else(|| crate::jwt::missing_claim("jti"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        testing::{self, FixedClock},
+        Clock,
+    };
+
+    async fn recloak(clock: &Arc<FixedClock>) -> Arc<crate::ReCloak> {
+        let (builder, _) = testing::builder(testing::config());
+
+        builder
+            .clock(clock.clone())
+            .jti_store(MemoryJtiStore::default())
+            .build()
+            .await
+            .unwrap()
+    }
+
+    fn token_expiring_in(
+        clock: &Arc<FixedClock>,
+        secs: i64,
+    ) -> (String, chrono::DateTime<chrono::Utc>) {
+        let now = clock.now();
+        let expires_at = now + chrono::Duration::seconds(secs);
+        let mut claims = testing::claims(now);
+        claims["exp"] = expires_at.timestamp().into();
+
+        (testing::sign(&claims), expires_at)
+    }
+
+    #[tokio::test]
+    async fn ttl_covers_validation_leeway() {
+        let clock = FixedClock::new();
+        let kc = recloak(&clock).await;
+        let (_, expires_at) = token_expiring_in(&clock, 5);
+
+        assert_eq!(
+            kc.ttl_until(expires_at),
+            Duration::from_secs(5) + crate::jwt::VALIDATION_LEEWAY
+        );
+
+        clock.advance(chrono::Duration::seconds(35));
+        assert_eq!(kc.ttl_until(expires_at), Duration::from_secs(30));
+
+        clock.advance(chrono::Duration::minutes(5));
+        assert_eq!(kc.ttl_until(expires_at), MIN_TTL);
+    }
+
+    #[tokio::test]
+    async fn rejects_replay_inside_leeway_window() {
+        let clock = FixedClock::new();
+        let kc = recloak(&clock).await;
+        let (token, expires_at) = token_expiring_in(&clock, 2);
+
+        // past `exp`, but still accepted by the decoder's leeway.
+        clock.advance(chrono::Duration::seconds(20));
+        kc.consume_token(&token).await.unwrap();
+
+        assert!(kc.ttl_until(expires_at) > Duration::from_secs(1));
+        assert!(matches!(
+            kc.consume_token(&token).await,
+            Err(Error::ReplayedToken)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_revoked_token_inside_leeway_window() {
+        let clock = FixedClock::new();
+        let kc = recloak(&clock).await;
+        let (token, _) = token_expiring_in(&clock, 2);
+
+        let claims = kc.validate_token(&token).await.unwrap().claims;
+        clock.advance(chrono::Duration::seconds(20));
+        kc.revoke_claims(&claims).await.unwrap();
+
+        assert!(matches!(
+            kc.validate_token(&token).await,
+            Err(Error::RevokedToken)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_claims_without_jti() {
+        let clock = FixedClock::new();
+        let kc = recloak(&clock).await;
+        let (token, _) = token_expiring_in(&clock, 60);
+        let mut claims = kc.validate_token(&token).await.unwrap().claims;
+        claims.id = None;
+
+        assert!(kc.consume_claims(&claims).await.is_err());
+        assert!(kc.check_revoked(&claims).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn memory_store_fails_closed_when_full() {
+        let store = MemoryJtiStore::new(1);
+        let ttl = Duration::from_secs(60);
+
+        assert!(store.consume("a", ttl).await.unwrap());
+        assert!(!store.consume("a", ttl).await.unwrap());
+        assert!(matches!(
+            store.consume("b", ttl).await,
+            Err(Error::JtiStore(_))
+        ));
+
+        store.revoke("a", ttl).await.unwrap();
+        assert!(store.revoke("b", ttl).await.is_err());
+        assert!(store.is_revoked("a").await.unwrap());
+    }
+
+    #[test]
+    fn memory_store_rejects_zero_capacity() {
+        assert!(MemoryJtiStore::new(0).check().is_err());
+    }
+}
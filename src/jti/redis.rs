@@ -0,0 +1,107 @@
+use std::time::Duration;
+
+use redis::{aio::ConnectionManager, AsyncCommands, SetExpiry, SetOptions};
+
+use super::{JtiFuture, JtiStore};
+use crate::{Error, Result};
+
+const DEFAULT_PREFIX: &str = "kc-rs:jti";
+
+#[derive(Clone)]
+pub struct RedisJtiStore {
+    conn: ConnectionManager,
+    prefix: String,
+}
+
+impl RedisJtiStore {
+    #[inline]
+    pub fn new(conn: ConnectionManager) -> Self {
+        Self {
+            conn,
+            prefix: DEFAULT_PREFIX.to_owned(),
+        }
+    }
+
+    pub async fn connect(url: &str) -> Result<Self> {
+        let client = redis::Client::open(url).map_err(store_error)?;
+        let conn = ConnectionManager::new(client).await.map_err(store_error)?;
+
+        Ok(Self::new(conn))
+    }
+
+    #[inline]
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    #[inline]
+    fn key(&self, kind: &str, jti: &str) -> String {
+        format!("{}:{kind}:{jti}", self.prefix)
+    }
+
+    async fn set(
+        &self,
+        key: String,
+        ttl: Duration,
+        only_new: bool,
+    ) -> Result<bool> {
+        let mut options = SetOptions::default().with_expiration(SetExpiry::PX(
+            ttl.as_millis().try_into().unwrap_or(u64::MAX),
+        ));
+        if only_new {
+            options = options.conditional_set(redis::ExistenceCheck::NX);
+        }
+
+        let reply: Option<String> = self
+            .conn
+            .clone()
+            .set_options(key, 1, options)
+            .await
+            .map_err(store_error)?;
+
+        Ok(reply.is_some())
+    }
+}
+
+impl JtiStore for RedisJtiStore {
+    fn is_revoked<'a>(&'a self, jti: &'a str) -> JtiFuture<'a, bool> {
+        Box::pin(async move {
+            self.conn
+                .clone()
+                .exists(self.key("revoked", jti))
+                .await
+                .map_err(store_error)
+        })
+    }
+
+    fn revoke<'a>(&'a self, jti: &'a str, ttl: Duration) -> JtiFuture<'a, ()> {
+        Box::pin(async move {
+            self.set(self.key("revoked", jti), ttl, false).await?;
+            Ok(())
+        })
+    }
+
+    fn consume<'a>(
+        &'a self,
+        jti: &'a str,
+        ttl: Duration,
+    ) -> JtiFuture<'a, bool> {
+        Box::pin(
+            async move { self.set(self.key("used", jti), ttl, true).await },
+        )
+    }
+}
+
+impl std::fmt::Debug for RedisJtiStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedisJtiStore")
+            .field("prefix", &self.prefix)
+            .finish_non_exhaustive()
+    }
+}
+
+#[inline]
+fn store_error(err: redis::RedisError) -> Error {
+    Error::JtiStore(err.to_string())
+}
@@ -0,0 +1,243 @@
+use std::{fmt, path::Path};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use openssl::{
+    encrypt::Decrypter,
+    hash::MessageDigest,
+    pkey::{PKey, Private},
+    rsa::Padding,
+    symm::Cipher,
+};
+
+use crate::{Error, Result};
+
+const JWE_SEGMENTS: usize = 5;
+
+pub(crate) struct Decryptor(PKey<Private>);
+
+#[derive(serde::Deserialize)]
+struct JweHeader {
+    alg: String,
+    enc: String,
+}
+
+impl Decryptor {
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let pem = std::fs::read(path).map_err(|err| {
+            Error::Config(format!(
+                "failed to read decryption key `{}`: {err}",
+                path.display()
+            ))
+        })?;
+
+        PKey::private_key_from_pem(&pem).map(Self).map_err(|err| {
+            Error::Config(format!("invalid decryption key: {err}"))
+        })
+    }
+
+    pub(crate) fn decrypt(&self, token: &str) -> Result<String> {
+        let segments = token.split('.').collect::<Vec<_>>();
+        let [header, key, iv, ciphertext, tag] = segments[..] else {
+            return Err(decryption("malformed jwe"));
+        };
+
+        let parsed = serde_json::from_slice::<JweHeader>(&decode(header)?)
+            .map_err(|_| decryption("malformed jwe header"))?;
+
+        let digest = match parsed.alg.as_str() {
+            | "RSA-OAEP" => MessageDigest::sha1(),
+            | "RSA-OAEP-256" => MessageDigest::sha256(),
+            | alg => {
+                return Err(decryption(format!("unsupported alg `{alg}`")))
+            }
+        };
+
+        let cipher = match parsed.enc.as_str() {
+            | "A128GCM" => Cipher::aes_128_gcm(),
+            | "A192GCM" => Cipher::aes_192_gcm(),
+            | "A256GCM" => Cipher::aes_256_gcm(),
+            | enc => {
+                return Err(decryption(format!("unsupported enc `{enc}`")))
+            }
+        };
+
+        let cek = self
+            .unwrap_key(&decode(key)?, digest)
+            .map_err(|_| decryption("failed to unwrap content key"))?;
+        if cek.len() != cipher.key_len() {
+            return Err(decryption("content key has the wrong length"));
+        }
+
+        let plaintext = openssl::symm::decrypt_aead(
+            cipher,
+            &cek,
+            Some(&decode(iv)?),
+            header.as_bytes(),
+            &decode(ciphertext)?,
+            &decode(tag)?,
+        )
+        .map_err(|_| decryption("failed to decrypt jwe payload"))?;
+
+        String::from_utf8(plaintext)
+            .map_err(|_| decryption("jwe payload is not valid utf-8"))
+    }
+
+    fn unwrap_key(
+        &self,
+        wrapped: &[u8],
+        digest: MessageDigest,
+    ) -> std::result::Result<Vec<u8>, openssl::error::ErrorStack> {
+        let mut decrypter = Decrypter::new(&self.0)?;
+        decrypter.set_rsa_padding(Padding::PKCS1_OAEP)?;
+        decrypter.set_rsa_oaep_md(digest)?;
+        decrypter.set_rsa_mgf1_md(digest)?;
+
+        let mut cek = vec![0; decrypter.decrypt_len(wrapped)?];
+        let len = decrypter.decrypt(wrapped, &mut cek)?;
+        cek.truncate(len);
+
+        Ok(cek)
+    }
+}
+
+impl fmt::Debug for Decryptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Decryptor")
+    }
+}
+
+#[inline]
+pub(crate) fn is_encrypted(token: &str) -> bool {
+    token.split('.').count() == JWE_SEGMENTS
+}
+
+#[inline]
+fn decode(segment: &str) -> Result<Vec<u8>> {
+    URL_SAFE_NO_PAD
+        .decode(segment)
+        .map_err(|_| decryption("invalid base64 in jwe"))
+}
+
+#[inline]
+fn decryption(message: impl Into<String>) -> Error {
+    Error::Decryption(message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+    use openssl::{
+        encrypt::Encrypter,
+        hash::MessageDigest,
+        pkey::{PKey, Private},
+        rand::rand_bytes,
+        rsa::{Padding, Rsa},
+        symm::Cipher,
+    };
+
+    use super::Decryptor;
+    use crate::{testing, Error};
+
+    fn key() -> PKey<Private> {
+        PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap()
+    }
+
+    fn encrypt(key: &PKey<Private>, alg: &str, plaintext: &str) -> String {
+        let header = serde_json::json!({ "alg": alg, "enc": "A256GCM" });
+        let header = URL_SAFE_NO_PAD.encode(header.to_string());
+
+        let mut cek = [0; 32];
+        let mut iv = [0; 12];
+        rand_bytes(&mut cek).unwrap();
+        rand_bytes(&mut iv).unwrap();
+
+        let digest = match alg {
+            | "RSA-OAEP" => MessageDigest::sha1(),
+            | _ => MessageDigest::sha256(),
+        };
+
+        let mut encrypter = Encrypter::new(key).unwrap();
+        encrypter.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
+        encrypter.set_rsa_oaep_md(digest).unwrap();
+        encrypter.set_rsa_mgf1_md(digest).unwrap();
+        let mut wrapped = vec![0; encrypter.encrypt_len(&cek).unwrap()];
+        let len = encrypter.encrypt(&cek, &mut wrapped).unwrap();
+        wrapped.truncate(len);
+
+        let mut tag = [0; 16];
+        let ciphertext = openssl::symm::encrypt_aead(
+            Cipher::aes_256_gcm(),
+            &cek,
+            Some(&iv),
+            header.as_bytes(),
+            plaintext.as_bytes(),
+            &mut tag,
+        )
+        .unwrap();
+
+        [
+            header,
+            URL_SAFE_NO_PAD.encode(wrapped),
+            URL_SAFE_NO_PAD.encode(iv),
+            URL_SAFE_NO_PAD.encode(ciphertext),
+            URL_SAFE_NO_PAD.encode(tag),
+        ]
+        .join(".")
+    }
+
+    #[test]
+    fn decrypts_rsa_oaep_256() {
+        let key = key();
+        let token = encrypt(&key, "RSA-OAEP-256", "nested.jws.token");
+
+        let plaintext = Decryptor(key).decrypt(&token).unwrap();
+        assert_eq!(plaintext, "nested.jws.token");
+    }
+
+    #[test]
+    fn rejects_tampered_and_unsupported_tokens() {
+        let key = key();
+
+        let mut segments = encrypt(&key, "RSA-OAEP-256", "payload")
+            .split('.')
+            .map(ToOwned::to_owned)
+            .collect::<Vec<_>>();
+        segments[4] = URL_SAFE_NO_PAD.encode([0; 16]);
+        let tampered = segments.join(".");
+
+        let unsupported = encrypt(&key, "RSA1_5", "payload");
+
+        let decryptor = Decryptor(key);
+        for token in [tampered, unsupported, "a.b.c.d.e".to_owned()] {
+            let err = decryptor.decrypt(&token).unwrap_err();
+            assert!(matches!(err, Error::Decryption(_)), "{err:?}");
+        }
+    }
+
+    #[test]
+    fn decoder_unwraps_nested_tokens() {
+        let key = key();
+        let path = std::env::temp_dir()
+            .join(format!("kc-rs-jwe-{}.pem", uuid::Uuid::new_v4()));
+        std::fs::write(&path, key.private_key_to_pem_pkcs8().unwrap()).unwrap();
+
+        let mut config = testing::config();
+        config.token.decryption_key = Some(path.clone());
+        let decoder = crate::JwtDecoder::new(
+            jsonwebtoken::jwk::JwkSet { keys: Vec::new() },
+            &config,
+        );
+        std::fs::remove_file(path).unwrap();
+
+        let jws = testing::sign(&testing::claims(chrono::Utc::now()));
+        let data = decoder.decode(&encrypt(&key, "RSA-OAEP", &jws)).unwrap();
+        assert_eq!(data.claims.username.as_deref(), Some("alice"));
+
+        let plain = crate::JwtDecoder::new(
+            jsonwebtoken::jwk::JwkSet { keys: Vec::new() },
+            &testing::config(),
+        );
+        let err = plain.decode(&encrypt(&key, "RSA-OAEP", &jws)).unwrap_err();
+        assert!(matches!(err, Error::Decryption(_)));
+    }
+}
@@ -0,0 +1,578 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use arc_swap::ArcSwap;
+use jsonwebtoken::jwk::JwkSet;
+use reqwest::{
+    header::{HeaderMap, HeaderValue, CACHE_CONTROL, ETAG, IF_NONE_MATCH},
+    StatusCode,
+};
+use tokio::sync::RwLock;
+
+use crate::{
+    governor::Governor,
+    telemetry::{self, TOKEN_TARGET},
+    ClaimsRef,
+    Clock,
+    Config,
+    DecodeReport,
+    Error,
+    JwtDecoder,
+    ReCloak,
+    Result,
+    TokenData,
+};
+
+#[derive(Debug, Clone)]
+pub struct SharedDecoder(Arc<DecoderState>);
+
+#[derive(Debug)]
+struct DecoderState {
+    config: ArcSwap<Config>,
+    decoder: ArcSwap<JwtDecoder>,
+    jwks: RwLock<CachedJwks>,
+    federated: RwLock<Vec<CachedJwks>>,
+    clock: Arc<dyn Clock>,
+
+    // bumped whenever the decoder is replaced, so memoized claims can tell
+    // they were decoded against keys that are no longer trusted.
+    generation: AtomicU64,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct CachedJwks {
+    pub(crate) jwks: JwkSet,
+    etag: Option<HeaderValue>,
+    fresh_until: Option<Instant>,
+}
+
+#[derive(Debug)]
+enum CertsResponse {
+    Modified(CachedJwks),
+    NotModified { fresh_until: Option<Instant> },
+}
+
+impl SharedDecoder {
+    pub(crate) fn new(
+        jwks: CachedJwks,
+        federated: Vec<CachedJwks>,
+        config: Arc<Config>,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        let decoder =
+            build_decoder(&jwks.jwks, &federated, &config, clock.clone());
+
+        Self(Arc::new(DecoderState {
+            config: ArcSwap::new(config),
+            decoder: ArcSwap::from_pointee(decoder),
+            jwks: RwLock::new(jwks),
+            federated: RwLock::new(federated),
+            clock,
+            generation: AtomicU64::new(0),
+        }))
+    }
+
+    #[inline]
+    pub fn decode(&self, token: &str) -> Result<TokenData> {
+        self.0.decoder.load().decode(token)
+    }
+
+    #[inline]
+    pub fn decode_with_report(
+        &self,
+        token: &str,
+    ) -> Result<(TokenData, DecodeReport)> {
+        self.0.decoder.load().decode_with_report(token)
+    }
+
+    #[inline]
+    pub fn decode_borrowed<'b>(
+        &self,
+        token: &str,
+        buf: &'b mut Vec<u8>,
+    ) -> Result<ClaimsRef<'b>> {
+        self.0.decoder.load().decode_borrowed(token, buf)
+    }
+
+    pub async fn decode_offloaded(&self, token: &str) -> Result<TokenData> {
+        let decoder = self.current();
+        if !decoder.should_offload(token)
+            || tokio::runtime::Handle::try_current().is_err()
+        {
+            return decoder.decode(token);
+        }
+
+        let token = token.to_owned();
+        tokio::task::spawn_blocking(move || decoder.decode(&token)).await?
+    }
+
+    #[inline]
+    pub fn current(&self) -> Arc<JwtDecoder> {
+        self.0.decoder.load_full()
+    }
+
+    #[cfg(feature = "middleware")]
+    #[inline]
+    pub(crate) fn generation(&self) -> u64 {
+        self.0.generation.load(Ordering::Acquire)
+    }
+
+    fn store(&self, decoder: JwtDecoder) {
+        self.0.decoder.store(Arc::new(decoder));
+        self.0.generation.fetch_add(1, Ordering::AcqRel);
+    }
+
+    pub(crate) async fn reset(
+        &self,
+        jwks: CachedJwks,
+        federated: Vec<CachedJwks>,
+        config: Arc<Config>,
+    ) {
+        let decoder = build_decoder(
+            &jwks.jwks,
+            &federated,
+            &config,
+            self.0.clock.clone(),
+        );
+
+        let mut cached = self.0.jwks.write().await;
+        let mut cached_federated = self.0.federated.write().await;
+        self.0.config.store(config);
+        self.store(decoder);
+        *cached = jwks;
+        *cached_federated = federated;
+    }
+
+    fn swap_keys(&self, jwks: &JwkSet, federated: &[CachedJwks]) {
+        let decoder = build_decoder(
+            jwks,
+            federated,
+            &self.0.config.load(),
+            self.0.clock.clone(),
+        );
+
+        self.store(decoder);
+    }
+}
+
+impl CachedJwks {
+    #[inline]
+    fn is_fresh(&self) -> bool {
+        self.fresh_until.is_some_and(|until| until > Instant::now())
+    }
+}
+
+impl ReCloak {
+    #[inline]
+    pub async fn jwks(&self) -> Result<JwkSet> {
+        self.fetch_jwks(false).await.map(|(jwks, _)| jwks)
+    }
+
+    #[inline]
+    pub fn decoder(&self) -> SharedDecoder {
+        self.decoder.clone()
+    }
+
+    #[cfg(feature = "axum")]
+    pub(crate) async fn published_jwks(
+        &self,
+    ) -> Result<(JwkSet, Option<Duration>)> {
+        let (jwks, _) = self.fetch_jwks(false).await?;
+        let fresh_for = self
+            .decoder
+            .0
+            .jwks
+            .read()
+            .await
+            .fresh_until
+            .map(|until| until.saturating_duration_since(Instant::now()));
+
+        Ok((jwks, fresh_for))
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn reload_jwks(&self) -> Result<bool> {
+        self.decoder.0.jwks.write().await.fresh_until = None;
+
+        self.fetch_jwks(true).await.map(|(_, changed)| changed)
+    }
+
+    // explicit reloads surface fetch errors, implicit refreshes fall back to
+    // the last known good set.
+    pub(crate) async fn fetch_jwks(
+        &self,
+        explicit: bool,
+    ) -> Result<(JwkSet, bool)> {
+        let state = &self.decoder.0;
+        let cached = state.jwks.read().await.clone();
+        let federated_changed = self.refresh_federated().await;
+
+        if cached.is_fresh() {
+            if federated_changed {
+                self.swap_decoder_keys(&cached.jwks).await;
+            }
+
+            return Ok((cached.jwks, false));
+        }
+
+        let url = self.urls.load().jwks.clone();
+        let tls = self.jwks_client.load_full();
+        let resp = get_certs(
+            &self.client,
+            &self.governor,
+            url,
+            Some(&cached),
+            tls.as_deref(),
+        )
+        .await;
+
+        match resp {
+            | Ok(CertsResponse::Modified(fetched)) => {
+                let changed = fetched.jwks != cached.jwks;
+                let jwks = fetched.jwks.clone();
+
+                if changed {
+                    telemetry::trace_info!(target: TOKEN_TARGET, "keycloak certs changed, swapping decoder");
+                }
+
+                if changed || federated_changed {
+                    self.swap_decoder_keys(&jwks).await;
+                }
+
+                *state.jwks.write().await = fetched;
+
+                Ok((jwks, changed))
+            }
+            | Ok(CertsResponse::NotModified { fresh_until }) => {
+                telemetry::trace_debug!(target: TOKEN_TARGET, "keycloak certs not modified");
+
+                state.jwks.write().await.fresh_until = fresh_until;
+                if federated_changed {
+                    self.swap_decoder_keys(&cached.jwks).await;
+                }
+
+                Ok((cached.jwks, false))
+            }
+            | Err(err) => {
+                if federated_changed {
+                    self.swap_decoder_keys(&cached.jwks).await;
+                }
+
+                if explicit {
+                    return Err(err);
+                }
+
+                telemetry::trace_warn!(
+                    target: TOKEN_TARGET,
+                    error = %err,
+                    "failed to fetch keycloak certs, using last known good set",
+                );
+
+                Ok((cached.jwks, false))
+            }
+        }
+    }
+
+    async fn refresh_federated(&self) -> bool {
+        let config = self.decoder.0.config.load_full();
+        let mut federated = self.decoder.0.federated.write().await;
+        let mut changed = false;
+
+        for (trusted, cached) in config
+            .token
+            .trusted_issuers
+            .iter()
+            .zip(federated.iter_mut())
+        {
+            if cached.is_fresh() {
+                continue;
+            }
+
+            let url = trusted.jwks_url.clone();
+            match get_certs(
+                &self.client,
+                &self.governor,
+                url,
+                Some(cached),
+                None,
+            )
+            .await
+            {
+                | Ok(CertsResponse::Modified(fetched)) => {
+                    if fetched.jwks != cached.jwks {
+                        telemetry::trace_info!(target: TOKEN_TARGET, issuer = %trusted.issuer, "federated issuer certs changed");
+                        changed = true;
+                    }
+
+                    *cached = fetched;
+                }
+                | Ok(CertsResponse::NotModified { fresh_until }) => {
+                    cached.fresh_until = fresh_until;
+                }
+                | Err(err) => {
+                    telemetry::trace_warn!(target: TOKEN_TARGET, issuer = %trusted.issuer, error = %err, "failed to fetch federated issuer certs, using last known good set");
+                }
+            }
+        }
+
+        changed
+    }
+
+    async fn swap_decoder_keys(&self, jwks: &JwkSet) {
+        let federated = self.decoder.0.federated.read().await;
+
+        self.decoder.swap_keys(jwks, &federated);
+    }
+}
+
+pub(crate) async fn fetch_initial(
+    client: &reqwest::Client,
+    governor: &Governor,
+    url: url::Url,
+    tls: Option<&reqwest::Client>,
+) -> Result<CachedJwks> {
+    match get_certs(client, governor, url, None, tls).await? {
+        | CertsResponse::Modified(jwks) => Ok(jwks),
+        | CertsResponse::NotModified { .. } => Err(Error::UnexpectedResponse {
+            status: StatusCode::NOT_MODIFIED,
+            body_snippet: String::new(),
+        }),
+    }
+}
+
+pub(crate) async fn fetch_federated(
+    client: &reqwest::Client,
+    governor: &Governor,
+    config: &Config,
+) -> Result<Vec<CachedJwks>> {
+    let mut federated = Vec::with_capacity(config.token.trusted_issuers.len());
+    for trusted in &config.token.trusted_issuers {
+        federated.push(
+            fetch_initial(client, governor, trusted.jwks_url.clone(), None)
+                .await?,
+        );
+    }
+
+    Ok(federated)
+}
+
+fn build_decoder(
+    jwks: &JwkSet,
+    federated: &[CachedJwks],
+    config: &Config,
+    clock: Arc<dyn Clock>,
+) -> JwtDecoder {
+    config
+        .token
+        .trusted_issuers
+        .iter()
+        .zip(federated)
+        .fold(
+            JwtDecoder::new(jwks.clone(), config),
+            |decoder, (trusted, cached)| {
+                decoder.with_issuer(trusted, cached.jwks.clone(), config)
+            },
+        )
+        .with_clock(clock)
+}
+
+pub(crate) fn tls_client(config: &Config) -> Result<Option<reqwest::Client>> {
+    let Some(ref tls) = config.token.jwks_tls else {
+        return Ok(None);
+    };
+
+    if config.urls()?.jwks.scheme() != "https" {
+        return Err(Error::Config(
+            "jwks_tls requires an https jwks url".to_owned(),
+        ));
+    }
+
+    let identity = reqwest::Identity::from_pkcs8_pem(
+        &read_pem(&tls.certificate)?,
+        &read_pem(&tls.private_key)?,
+    )?;
+    let mut builder = reqwest::ClientBuilder::new()
+        .user_agent(&config.http.user_agent)
+        .https_only(true)
+        .identity(identity);
+
+    if let Some(ref ca) = tls.ca_certificate {
+        builder = builder.tls_built_in_root_certs(false).add_root_certificate(
+            reqwest::Certificate::from_pem(&read_pem(ca)?)?,
+        );
+    }
+
+    builder.build().map(Some).map_err(From::from)
+}
+
+fn read_pem(path: &std::path::Path) -> Result<Vec<u8>> {
+    std::fs::read(path).map_err(|err| {
+        Error::Config(format!("failed to read `{}`: {err}", path.display()))
+    })
+}
+
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(skip(client, governor, cached, tls))
+)]
+async fn get_certs(
+    client: &reqwest::Client,
+    governor: &Governor,
+    url: url::Url,
+    cached: Option<&CachedJwks>,
+    tls: Option<&reqwest::Client>,
+) -> Result<CertsResponse> {
+    telemetry::trace_debug!(target: TOKEN_TARGET, %url, mtls = tls.is_some(), "fetching keycloak certs");
+
+    let mut req = tls.unwrap_or(client).get(url);
+    if let Some(etag) = cached.and_then(|c| c.etag.as_ref()) {
+        req = req.header(IF_NONE_MATCH, etag);
+    }
+
+    let resp = match tls {
+        | Some(tls) => governor.send_via(req, tls).await?,
+        | None => governor.send(req).await?,
+    };
+    let fresh_until = max_age(&resp.headers).map(|age| Instant::now() + age);
+
+    if resp.status == StatusCode::NOT_MODIFIED {
+        return Ok(CertsResponse::NotModified { fresh_until });
+    }
+
+    let etag = resp.headers.get(ETAG).cloned();
+    let jwks = resp.error_for_status()?.json()?;
+
+    Ok(CertsResponse::Modified(CachedJwks {
+        jwks,
+        etag,
+        fresh_until,
+    }))
+}
+
+fn max_age(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(CACHE_CONTROL)?.to_str().ok()?;
+
+    let mut max_age = None;
+    for directive in value.split(',').map(str::trim) {
+        match directive.split_once('=') {
+            | Some(("max-age", secs)) => {
+                max_age = secs.trim_matches('"').parse().ok();
+            }
+            | None if directive == "no-cache" || directive == "no-store" => {
+                return None;
+            }
+            | _ => {}
+        }
+    }
+
+    max_age.map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::{
+        header::{HeaderValue, CACHE_CONTROL, ETAG, IF_NONE_MATCH},
+        StatusCode,
+    };
+    use serde_json::json;
+
+    use crate::{testing, Error, TransportResponse};
+
+    const CERTS_PATH: &str = "/realms/test/protocol/openid-connect/certs";
+
+    fn certs(
+        etag: &'static str,
+        cache_control: &'static str,
+    ) -> TransportResponse {
+        let mut resp = TransportResponse::new(
+            StatusCode::OK,
+            json!({ "keys": [] }).to_string(),
+        );
+        resp.headers.insert(ETAG, HeaderValue::from_static(etag));
+        resp.headers
+            .insert(CACHE_CONTROL, HeaderValue::from_static(cache_control));
+
+        resp
+    }
+
+    #[tokio::test]
+    async fn rejects_not_modified_initial_fetch() {
+        let (builder, transport) = testing::builder(testing::config());
+        transport.respond(
+            CERTS_PATH,
+            TransportResponse::new(StatusCode::NOT_MODIFIED, ""),
+        );
+
+        let err = builder.build().await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::UnexpectedResponse {
+                status: StatusCode::NOT_MODIFIED,
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn revalidates_with_etag() {
+        let (builder, transport) = testing::builder(testing::config());
+        transport.respond(CERTS_PATH, certs("\"v1\"", "no-cache"));
+        let kc = builder.build().await.unwrap();
+
+        transport.respond(
+            CERTS_PATH,
+            TransportResponse::new(StatusCode::NOT_MODIFIED, ""),
+        );
+        assert!(!kc.reload_jwks().await.unwrap());
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].headers.get(IF_NONE_MATCH).is_none());
+        assert_eq!(requests[1].headers[IF_NONE_MATCH], "\"v1\"");
+    }
+
+    #[tokio::test]
+    async fn honors_max_age() {
+        let (builder, transport) = testing::builder(testing::config());
+        transport.respond(CERTS_PATH, certs("\"v1\"", "public, max-age=300"));
+        let kc = builder.build().await.unwrap();
+
+        kc.jwks().await.unwrap();
+        assert_eq!(transport.requests().len(), 1);
+
+        transport.respond(CERTS_PATH, certs("\"v1\"", "no-store"));
+        kc.reload_jwks().await.unwrap();
+        kc.jwks().await.unwrap();
+        assert_eq!(transport.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn explicit_reload_reports_fetch_errors() {
+        let (builder, transport) = testing::builder(testing::config());
+        transport.respond(CERTS_PATH, certs("\"v1\"", "no-cache"));
+        let kc = builder.build().await.unwrap();
+
+        transport.respond(
+            CERTS_PATH,
+            TransportResponse::new(StatusCode::SERVICE_UNAVAILABLE, ""),
+        );
+
+        let err = kc.reload_jwks().await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::UnexpectedResponse {
+                status: StatusCode::SERVICE_UNAVAILABLE,
+                ..
+            }
+        ));
+
+        // implicit refreshes keep serving the last known good set.
+        assert!(kc.jwks().await.unwrap().keys.is_empty());
+        assert_eq!(transport.requests().len(), 3);
+    }
+}
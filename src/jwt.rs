@@ -1,28 +1,68 @@
-use std::{fmt, str::FromStr};
+use std::{
+    fmt,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use jsonwebtoken::{
     self as jwt,
     errors::{Error as JwtError, ErrorKind as JwtErrorKind},
     Algorithm,
 };
 
-use crate::{Config, Result};
+use crate::{
+    config::{KeyPinning, OffloadConfig, TokenLimits, TrustedIssuer},
+    telemetry::{self, TOKEN_TARGET},
+    Clock,
+    Config,
+    Result,
+    SystemClock,
+};
+
+pub(crate) const BEARER_TOKEN_TYPE: &str = "Bearer";
+
+const HMAC_ALGORITHMS: &[Algorithm] =
+    &[Algorithm::HS256, Algorithm::HS384, Algorithm::HS512];
+
+// leeway applied to `exp` and `nbf` for realm and federated issuers.
+pub(crate) const VALIDATION_LEEWAY: Duration = Duration::from_secs(60);
 
-const REQUIRED_CLAIMS: &[&str] = &[
-    "iss",
-    "sub",
-    "aud",
-    "exp",
-    "iat",
-    "jti",
-    "preferred_username",
-    "realm_access",
-    "resource_access",
-];
+const REQUIRED_CLAIMS: &[&str] = &["iss", "sub", "aud", "exp", "iat", "jti"];
+
+const ACTION_REQUIRED_CLAIMS: &[&str] = &["iss", "sub", "aud", "exp"];
+
+const COMPACT_JWS_SEGMENTS: usize = 3;
+const COMPACT_JWE_SEGMENTS: usize = 5;
+
+const USERNAME_CLAIM: &str = "preferred_username";
+const REALM_ROLES_CLAIM: &str = "realm_access";
+const CLIENT_ROLES_CLAIM: &str = "resource_access";
 
 #[derive(Debug, Clone)]
 pub struct JwtDecoder {
     keys: Vec<Jwk>,
+    secret: Option<Jwk>,
+    require_bearer: bool,
+    allowed_parties: Option<Vec<String>>,
+    limits: TokenLimits,
+    require_username: bool,
+    require_roles: bool,
+    require_resource_access: bool,
+    uuid_subject: bool,
+    offload: Option<OffloadConfig>,
+    federated: Vec<FederatedIssuer>,
+    #[cfg(feature = "jwe")]
+    decryptor: Option<Arc<crate::jwe::Decryptor>>,
+    clock: Arc<dyn Clock>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeReport {
+    pub key_id: Option<String>,
+    pub algorithm: Algorithm,
+    pub elapsed: Duration,
 }
 
 #[derive(Clone)]
@@ -32,16 +72,193 @@ struct Jwk {
     vld: jwt::Validation,
 }
 
+#[derive(Debug, Clone)]
+struct FederatedIssuer {
+    issuer: String,
+    keys: Vec<Jwk>,
+    require_username: bool,
+    require_roles: bool,
+    require_resource_access: bool,
+    allowed_parties: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct IssuerRules<'a> {
+    require_bearer: bool,
+    require_username: bool,
+    require_roles: bool,
+    require_resource_access: bool,
+    uuid_subject: bool,
+    allowed_parties: Option<&'a [String]>,
+}
+
+impl IssuerRules<'_> {
+    fn enforce(
+        &self,
+        has_username: bool,
+        has_roles: bool,
+        has_client_roles: bool,
+        subject: &str,
+        token_type: Option<&str>,
+        authorized_party: Option<&str>,
+    ) -> crate::Result<()> {
+        // keycloak subjects are always user or service account ids, which
+        // `Claims::subject_id` relies on.
+        if self.uuid_subject && uuid::Uuid::try_parse(subject).is_err() {
+            return Err(JwtError::from(JwtErrorKind::InvalidSubject).into());
+        }
+
+        if self.require_username && !has_username {
+            return Err(missing_claim(USERNAME_CLAIM));
+        }
+
+        if self.require_roles && !has_roles {
+            return Err(missing_claim(REALM_ROLES_CLAIM));
+        }
+
+        if self.require_resource_access && !has_client_roles {
+            return Err(missing_claim(CLIENT_ROLES_CLAIM));
+        }
+
+        if self.require_bearer && token_type != Some(BEARER_TOKEN_TYPE) {
+            return Err(crate::Error::InvalidTokenType(
+                token_type.map(ToOwned::to_owned),
+            ));
+        }
+
+        if let Some(parties) = self.allowed_parties {
+            if !authorized_party
+                .is_some_and(|azp| parties.iter().any(|p| p == azp))
+            {
+                return Err(crate::Error::UnauthorizedParty(
+                    authorized_party.map(ToOwned::to_owned),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl JwtDecoder {
     #[inline]
     pub fn new(jwks: jwt::jwk::JwkSet, config: &Config) -> Self {
+        #[cfg(feature = "x5c")]
+        let trust = config
+            .token
+            .trusted_ca
+            .as_deref()
+            .map(crate::x5c::TrustStore::load);
+
+        let pinning = config.token.key_pinning.as_ref();
         let keys = jwks
             .keys
             .into_iter()
-            .filter_map(|jwk| Jwk::new(jwk, config).ok())
+            .filter(is_signing_key)
+            .filter(|jwk| is_pinned(jwk, pinning))
+            .filter_map(|jwk| {
+                let kid = jwk.common.key_id.clone();
+
+                #[cfg(feature = "x5c")]
+                if let Some(ref trust) = trust {
+                    let verified = match trust {
+                        | Ok(store) => store.verify(&jwk),
+                        | Err(err) => Err(crate::Error::Certificate(
+                            format!("trusted ca unavailable: {err}"),
+                        )),
+                    };
+
+                    if let Err(err) = verified {
+                        telemetry::trace_warn!(target: TOKEN_TARGET, ?kid, error = %err, "rejecting key with untrusted certificate");
+                        return None;
+                    }
+                }
+
+                Jwk::new(jwk, config)
+                    .inspect_err(|err| {
+                        telemetry::trace_warn!(target: TOKEN_TARGET, ?kid, error = %err, "skipping unusable key");
+                    })
+                    .ok()
+            })
             .collect();
 
-        Self { keys }
+        let secret = config.token.hs_secret.as_ref().and_then(|secret| {
+            Jwk::from_secret(secret.expose(), config)
+                .inspect_err(|err| {
+                    telemetry::trace_warn!(target: TOKEN_TARGET, error = %err, "skipping unusable shared secret");
+                })
+                .ok()
+        });
+
+        #[cfg(feature = "jwe")]
+        let decryptor = config.token.decryption_key.as_deref().and_then(|path| {
+            crate::jwe::Decryptor::load(path)
+                .inspect_err(|err| {
+                    telemetry::trace_error!(target: TOKEN_TARGET, error = %err, "failed to load decryption key");
+                })
+                .ok()
+                .map(Arc::new)
+        });
+
+        Self {
+            keys,
+            secret,
+            require_bearer: config.token.require_bearer,
+            allowed_parties: config.token.allowed_parties.clone(),
+            limits: config.token.limits,
+            require_username: config.token.require_username,
+            require_roles: config.token.require_roles
+                && config.provider == crate::config::Provider::Keycloak,
+            require_resource_access: config.token.require_resource_access
+                && config.provider == crate::config::Provider::Keycloak,
+            uuid_subject: config.provider == crate::config::Provider::Keycloak,
+            offload: config.token.offload.clone(),
+            federated: Vec::new(),
+            #[cfg(feature = "jwe")]
+            decryptor,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    #[inline]
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn with_issuer(
+        mut self,
+        trusted: &TrustedIssuer,
+        jwks: jwt::jwk::JwkSet,
+        config: &Config,
+    ) -> Self {
+        let keys = jwks
+            .keys
+            .into_iter()
+            .filter(is_signing_key)
+            .filter(|jwk| is_pinned(jwk, trusted.key_pinning.as_ref()))
+            .filter_map(|jwk| {
+                let kid = jwk.common.key_id.clone();
+
+                Jwk::with_validation(jwk, |alg| {
+                    federated_validation(alg, trusted, config)
+                })
+                .inspect_err(|err| {
+                    telemetry::trace_warn!(target: TOKEN_TARGET, issuer = %trusted.issuer, ?kid, error = %err, "skipping unusable federated key");
+                })
+                .ok()
+            })
+            .collect();
+
+        self.federated.push(FederatedIssuer {
+            issuer: trusted.issuer.clone(),
+            keys,
+            require_username: trusted.require_username,
+            require_roles: trusted.require_roles,
+            require_resource_access: trusted.require_resource_access,
+            allowed_parties: trusted.allowed_parties.clone(),
+        });
+        self
     }
 
     #[inline]
@@ -49,54 +266,546 @@ impl JwtDecoder {
         &self,
         token: &str,
     ) -> crate::Result<jwt::TokenData<crate::Claims>> {
-        self.get_key_for(token.as_ref())?.decode(token)
+        self.decode_inner(token, None::<fn(&mut jwt::Validation)>)
+            .map(|(data, _)| data)
+    }
+
+    pub fn decode_with_report(
+        &self,
+        token: &str,
+    ) -> crate::Result<(jwt::TokenData<crate::Claims>, DecodeReport)> {
+        let started = Instant::now();
+        let (data, key) =
+            self.decode_inner(token, None::<fn(&mut jwt::Validation)>)?;
+
+        let report = DecodeReport {
+            key_id: key.kid.clone(),
+            algorithm: data.header.alg,
+            elapsed: started.elapsed(),
+        };
+
+        Ok((data, report))
+    }
+
+    #[inline]
+    pub fn decode_with<F>(
+        &self,
+        token: &str,
+        configure: F,
+    ) -> crate::Result<jwt::TokenData<crate::Claims>>
+    where
+        F: FnOnce(&mut jwt::Validation),
+    {
+        self.decode_inner(token, Some(configure))
+            .map(|(data, _)| data)
+    }
+
+    fn decode_inner<F>(
+        &self,
+        token: &str,
+        configure: Option<F>,
+    ) -> crate::Result<(jwt::TokenData<crate::Claims>, &Jwk)>
+    where
+        F: FnOnce(&mut jwt::Validation),
+    {
+        self.check_limits(token)?;
+
+        #[cfg(feature = "jwe")]
+        let decrypted = self.decrypt(token)?;
+        #[cfg(feature = "jwe")]
+        let token = decrypted.as_deref().unwrap_or(token);
+
+        let (key, rules) = self.select_key(token.as_ref())?;
+
+        let custom = configure.map(|configure| {
+            let mut vld = key.vld.clone();
+            vld.validate_exp = true;
+            configure(&mut vld);
+
+            let validate_exp = std::mem::replace(&mut vld.validate_exp, false);
+            (vld, validate_exp)
+        });
+        let (vld, validate_exp) = match custom {
+            | Some((ref vld, validate_exp)) => (vld, validate_exp),
+            | None => (&key.vld, true),
+        };
+
+        let data = key.decode(token, vld)?;
+
+        let leeway = chrono::Duration::seconds(vld.leeway as i64);
+        if validate_exp && data.claims.expires_at + leeway < self.clock.now() {
+            return Err(JwtError::from(JwtErrorKind::ExpiredSignature).into());
+        }
+
+        rules.enforce(
+            data.claims.username.is_some(),
+            data.claims.realm.is_present(),
+            !data.claims.resource.is_empty(),
+            &data.claims.subject,
+            data.claims.token_type.as_deref(),
+            data.claims.authorized_party.as_deref(),
+        )?;
+
+        Ok((data, key))
+    }
+
+    pub fn decode_borrowed<'b>(
+        &self,
+        token: &str,
+        buf: &'b mut Vec<u8>,
+    ) -> crate::Result<crate::ClaimsRef<'b>> {
+        self.check_limits(token)?;
+
+        #[cfg(feature = "jwe")]
+        let decrypted = self.decrypt(token)?;
+        #[cfg(feature = "jwe")]
+        let token = decrypted.as_deref().unwrap_or(token);
+
+        let (key, rules) = self.select_key(token.as_ref())?;
+        key.verify(token)?;
+
+        let payload = token.split('.').nth(1).unwrap_or_default();
+        buf.clear();
+        URL_SAFE_NO_PAD
+            .decode_vec(payload, buf)
+            .map_err(|_| JwtError::from(JwtErrorKind::InvalidToken))?;
+
+        let claims: crate::ClaimsRef<'b> = serde_json::from_slice(buf)
+            .map_err(|err| JwtError::from(JwtErrorKind::Json(Arc::new(err))))?;
+
+        let leeway = chrono::Duration::seconds(key.vld.leeway as i64);
+        if claims.expires_at + leeway < self.clock.now() {
+            return Err(JwtError::from(JwtErrorKind::ExpiredSignature).into());
+        }
+
+        rules.enforce(
+            claims.username.is_some(),
+            claims.realm.is_some(),
+            !claims.resource.is_empty(),
+            &claims.subject,
+            claims.token_type.as_deref(),
+            claims.authorized_party.as_deref(),
+        )?;
+
+        Ok(claims)
+    }
+
+    pub fn decode_action(
+        &self,
+        token: &str,
+    ) -> crate::Result<crate::ActionToken> {
+        self.check_limits(token)?;
+
+        let key = self.get_key_for(token)?;
+        let mut vld = key.vld.clone();
+        vld.set_required_spec_claims(ACTION_REQUIRED_CLAIMS);
+        // action tokens are addressed to the realm itself.
+        vld.aud.clone_from(&vld.iss);
+
+        let action =
+            jwt::decode::<crate::ActionToken>(token, &key.key, &vld)?.claims;
+
+        let leeway = chrono::Duration::seconds(vld.leeway as i64);
+        if action.expires_at + leeway < self.clock.now() {
+            return Err(JwtError::from(JwtErrorKind::ExpiredSignature).into());
+        }
+
+        if action.kind.is_oidc_token() {
+            return Err(crate::Error::InvalidTokenType(Some(
+                action.kind.to_string(),
+            )));
+        }
+
+        Ok(action)
+    }
+
+    pub fn decode_batch(
+        &self,
+        tokens: &[&str],
+    ) -> Vec<crate::Result<jwt::TokenData<crate::Claims>>> {
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+
+            tokens.par_iter().map(|token| self.decode(token)).collect()
+        }
+
+        #[cfg(not(feature = "rayon"))]
+        tokens.iter().map(|token| self.decode(token)).collect()
+    }
+
+    #[cfg(feature = "jwe")]
+    fn decrypt(&self, token: &str) -> crate::Result<Option<String>> {
+        if !crate::jwe::is_encrypted(token) {
+            return Ok(None);
+        }
+
+        match self.decryptor {
+            | Some(ref decryptor) => decryptor.decrypt(token).map(Some),
+            | None => Err(crate::Error::Decryption(
+                "received an encrypted token without a decryption key".into(),
+            )),
+        }
+    }
+
+    pub fn should_offload(&self, token: &str) -> bool {
+        let Some(ref offload) = self.offload else {
+            return false;
+        };
+
+        if token.len() < offload.min_token_size {
+            return false;
+        }
+
+        #[cfg(feature = "jwe")]
+        if crate::jwe::is_encrypted(token) {
+            return true;
+        }
+
+        let Ok(header) = jwt::decode_header(token) else {
+            return false;
+        };
+
+        match offload.algorithms {
+            | Some(ref algorithms) => algorithms.contains(&header.alg),
+            | None => !HMAC_ALGORITHMS.contains(&header.alg),
+        }
+    }
+
+    pub(crate) fn check_limits(&self, token: &str) -> crate::Result<()> {
+        if token.len() > self.limits.max_token_size {
+            return Err(crate::Error::LimitExceeded("token size"));
+        }
+
+        let header = token.split('.').next().unwrap_or_default();
+        if header.len() > self.limits.max_header_size {
+            return Err(crate::Error::LimitExceeded("header size"));
+        }
+
+        Ok(())
+    }
+
+    fn select_key(
+        &self,
+        token: &str,
+    ) -> crate::Result<(&Jwk, IssuerRules<'_>)> {
+        let federated = match self.federated.as_slice() {
+            | [] => None,
+            | federated => unverified_issuer(token).and_then(|issuer| {
+                federated.iter().find(|fed| fed.issuer == issuer)
+            }),
+        };
+
+        let Some(federated) = federated else {
+            let rules = IssuerRules {
+                require_bearer: self.require_bearer,
+                require_username: self.require_username,
+                require_roles: self.require_roles,
+                require_resource_access: self.require_resource_access,
+                uuid_subject: self.uuid_subject,
+                allowed_parties: self.allowed_parties.as_deref(),
+            };
+
+            return Ok((self.get_key_for(token)?, rules));
+        };
+
+        let header = self.checked_header(token)?;
+        if HMAC_ALGORITHMS.contains(&header.alg) {
+            return Err(JwtError::from(JwtErrorKind::InvalidAlgorithm).into());
+        }
+
+        let rules = IssuerRules {
+            require_bearer: false,
+            require_username: federated.require_username,
+            require_roles: federated.require_roles,
+            require_resource_access: federated.require_resource_access,
+            uuid_subject: false,
+            allowed_parties: federated.allowed_parties.as_deref(),
+        };
+
+        Ok((find_key(&federated.keys, header.kid.as_deref())?, rules))
     }
 
     fn get_key_for(&self, token: &str) -> crate::Result<&Jwk> {
+        let header = self.checked_header(token)?;
+
+        if HMAC_ALGORITHMS.contains(&header.alg) {
+            return self.secret.as_ref().ok_or_else(|| {
+                JwtError::from(JwtErrorKind::InvalidAlgorithm).into()
+            });
+        }
+
+        find_key(&self.keys, header.kid.as_deref())
+    }
+
+    fn checked_header(&self, token: &str) -> crate::Result<jwt::Header> {
         let header = jwt::decode_header(token)?;
 
-        let key = if self.keys.len() == 1 {
-            &self.keys[0]
-        } else {
-            self.keys
-                .iter()
-                .find(|key| key.kid == header.kid)
-                .ok_or_else(|| JwtError::from(JwtErrorKind::InvalidToken))?
-        };
+        if header
+            .kid
+            .as_ref()
+            .is_some_and(|kid| kid.len() > self.limits.max_kid_length)
+        {
+            return Err(crate::Error::LimitExceeded("kid length"));
+        }
+
+        Ok(header)
+    }
+}
+
+fn find_key<'a>(keys: &'a [Jwk], kid: Option<&str>) -> crate::Result<&'a Jwk> {
+    match keys {
+        | [key] => Ok(key),
+        | keys => keys
+            .iter()
+            .find(|key| key.kid.as_deref() == kid)
+            .ok_or_else(|| JwtError::from(JwtErrorKind::InvalidToken).into()),
+    }
+}
+
+#[inline]
+pub(crate) fn missing_claim(name: &str) -> crate::Error {
+    JwtError::from(JwtErrorKind::MissingRequiredClaim(name.to_owned())).into()
+}
+
+fn is_signing_key(jwk: &jwt::jwk::Jwk) -> bool {
+    matches!(
+        jwk.common.public_key_use,
+        None | Some(jwt::jwk::PublicKeyUse::Signature)
+    )
+}
+
+fn is_pinned(jwk: &jwt::jwk::Jwk, pinning: Option<&KeyPinning>) -> bool {
+    let Some(pinning) = pinning else {
+        return true;
+    };
+
+    let kid = jwk.common.key_id.as_deref();
+    let pinned = kid
+        .is_some_and(|kid| pinning.key_ids.iter().any(|k| k == kid))
+        || (!pinning.thumbprints.is_empty()
+            && thumbprint(jwk).is_some_and(|thumbprint| {
+                pinning.thumbprints.contains(&thumbprint)
+            }));
 
-        Ok(key)
+    if !pinned {
+        telemetry::trace_warn!(target: TOKEN_TARGET, ?kid, "rejecting key that does not match any pin");
     }
+
+    pinned
+}
+
+// RFC 7638 thumbprint over the required members in lexicographic order.
+pub(crate) fn thumbprint(jwk: &jwt::jwk::Jwk) -> Option<String> {
+    use jwt::jwk::AlgorithmParameters::*;
+
+    let canonical = match jwk.algorithm {
+        | RSA(ref rsa) => serde_json::json!({
+            "e": rsa.e,
+            "kty": "RSA",
+            "n": rsa.n,
+        }),
+        | EllipticCurve(ref ec) => serde_json::json!({
+            "crv": ec.curve,
+            "kty": "EC",
+            "x": ec.x,
+            "y": ec.y,
+        }),
+        | OctetKeyPair(ref okp) => serde_json::json!({
+            "crv": okp.curve,
+            "kty": "OKP",
+            "x": okp.x,
+        }),
+        | OctetKey(_) => return None,
+    };
+
+    let digest = ring::digest::digest(
+        &ring::digest::SHA256,
+        canonical.to_string().as_bytes(),
+    );
+
+    Some(URL_SAFE_NO_PAD.encode(digest))
+}
+
+#[inline]
+pub(crate) fn is_structured(token: &str) -> bool {
+    matches!(
+        token.split('.').count(),
+        COMPACT_JWS_SEGMENTS | COMPACT_JWE_SEGMENTS
+    )
+}
+
+pub(crate) fn unverified_token_type(token: &str) -> Option<String> {
+    #[derive(serde::Deserialize)]
+    struct TypeDto {
+        typ: Option<String>,
+    }
+
+    unverified_claims::<TypeDto>(token)?.typ
+}
+
+pub(crate) fn unverified_issuer(token: &str) -> Option<String> {
+    #[derive(serde::Deserialize)]
+    struct IssuerDto {
+        iss: Option<String>,
+    }
+
+    unverified_claims::<IssuerDto>(token)?.iss
+}
+
+fn unverified_claims<T: serde::de::DeserializeOwned>(token: &str) -> Option<T> {
+    let mut vld = jwt::Validation::default();
+    vld.insecure_disable_signature_validation();
+    vld.validate_exp = false;
+    vld.validate_aud = false;
+    vld.required_spec_claims.clear();
+
+    jwt::decode::<T>(token, &jwt::DecodingKey::from_secret(&[]), &vld)
+        .ok()
+        .map(|data| data.claims)
 }
 
 impl Jwk {
     #[inline]
     fn new(jwk: jwt::jwk::Jwk, config: &Config) -> Result<Self> {
-        let alg_name = jwk.common.key_algorithm.unwrap().to_string();
+        Self::with_validation(jwk, |alg| validation(&[alg], config))
+    }
+
+    fn with_validation(
+        jwk: jwt::jwk::Jwk,
+        validation: impl FnOnce(Algorithm) -> Result<jwt::Validation>,
+    ) -> Result<Self> {
+        let alg_name = jwk
+            .common
+            .key_algorithm
+            .ok_or_else(|| JwtError::from(JwtErrorKind::MissingAlgorithm))?
+            .to_string();
 
         let alg = Algorithm::from_str(alg_name.as_str())?;
         let key = jwt::DecodingKey::from_jwk(&jwk)?;
         let kid = jwk.common.key_id;
+        let vld = validation(alg)?;
 
-        let mut vld = jwt::Validation::new(alg);
-        vld.set_required_spec_claims(REQUIRED_CLAIMS);
+        Ok(Self { kid, key, vld })
+    }
 
-        match config.token.issuer.as_deref() {
-            | Some(issuer) => vld.set_issuer(issuer),
-            | None => vld.set_issuer(&[config.urls()?.issuer.as_str()]),
-        }
+    fn from_secret(secret: &str, config: &Config) -> Result<Self> {
+        let key = jwt::DecodingKey::from_secret(secret.as_bytes());
+        let vld = validation(HMAC_ALGORITHMS, config)?;
 
-        match config.token.audience.as_deref() {
-            | Some(audience) => vld.set_audience(audience),
-            | None => vld.set_issuer(&[&config.client.id]),
-        }
+        Ok(Self {
+            kid: None,
+            key,
+            vld,
+        })
+    }
 
-        Ok(Self { kid, key, vld })
+    #[inline]
+    fn decode(
+        &self,
+        token: &str,
+        vld: &jwt::Validation,
+    ) -> crate::Result<crate::TokenData> {
+        jwt::decode(token, &self.key, vld).map_err(From::from)
     }
 
     #[inline]
-    fn decode(&self, token: &str) -> crate::Result<crate::TokenData> {
-        jwt::decode(token, &self.key, &self.vld).map_err(From::from)
+    fn verify(&self, token: &str) -> crate::Result<()> {
+        jwt::decode::<serde::de::IgnoredAny>(token, &self.key, &self.vld)
+            .map(drop)
+            .map_err(From::from)
+    }
+}
+
+fn validation(algs: &[Algorithm], config: &Config) -> Result<jwt::Validation> {
+    let mut vld = jwt::Validation::new(algs[0]);
+    vld.algorithms = algs.to_vec();
+    vld.set_required_spec_claims(REQUIRED_CLAIMS);
+    vld.leeway = VALIDATION_LEEWAY.as_secs();
+    // expiry is checked against the decoder's clock instead.
+    vld.validate_exp = false;
+
+    match config.token.issuer.as_deref() {
+        | Some(issuer) => vld.set_issuer(issuer),
+        | None => vld.set_issuer(&[config.urls()?.issuer.as_str()]),
+    }
+
+    match config.token.audience.as_deref() {
+        | Some(audience) => vld.set_audience(audience),
+        | None => vld.set_audience(&[&config.client.id]),
+    }
+
+    Ok(vld)
+}
+
+// introspected claims skip `jsonwebtoken`, so the same issuer, audience and
+// authorized party rules are applied to them by hand.
+pub(crate) fn check_introspected(
+    claims: &crate::Claims,
+    config: &Config,
+) -> Result<()> {
+    let vld = validation(&[Algorithm::RS256], config)?;
+
+    if vld
+        .iss
+        .as_ref()
+        .is_some_and(|iss| !iss.contains(claims.issuer.as_str()))
+    {
+        return Err(JwtError::from(JwtErrorKind::InvalidIssuer).into());
+    }
+
+    if vld.aud.as_ref().is_some_and(|aud| {
+        !claims.audience.iter().any(|a| aud.contains(a.as_str()))
+    }) {
+        return Err(JwtError::from(JwtErrorKind::InvalidAudience).into());
+    }
+
+    let rules = IssuerRules {
+        require_bearer: false,
+        require_username: false,
+        require_roles: false,
+        require_resource_access: false,
+        uuid_subject: config.provider == crate::config::Provider::Keycloak,
+        allowed_parties: config.token.allowed_parties.as_deref(),
+    };
+
+    rules.enforce(
+        false,
+        false,
+        false,
+        &claims.subject,
+        None,
+        claims.authorized_party.as_deref(),
+    )
+}
+
+fn federated_validation(
+    alg: Algorithm,
+    trusted: &TrustedIssuer,
+    config: &Config,
+) -> Result<jwt::Validation> {
+    if trusted
+        .algorithms
+        .as_ref()
+        .is_some_and(|algs| !algs.contains(&alg))
+    {
+        return Err(JwtError::from(JwtErrorKind::InvalidAlgorithm).into());
+    }
+
+    let mut vld = jwt::Validation::new(alg);
+    vld.set_required_spec_claims(REQUIRED_CLAIMS);
+    vld.leeway = VALIDATION_LEEWAY.as_secs();
+    vld.validate_exp = false;
+    vld.set_issuer(&[&trusted.issuer]);
+
+    match (&trusted.audience, &config.token.audience) {
+        | (Some(audience), _) | (None, Some(audience)) => {
+            vld.set_audience(audience)
+        }
+        | (None, None) => vld.set_audience(&[&config.client.id]),
     }
+
+    Ok(vld)
 }
 
 impl fmt::Debug for Jwk {
@@ -108,3 +817,220 @@ impl fmt::Debug for Jwk {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+    use crate::{testing, Error};
+
+    fn decoder(config: &Config) -> JwtDecoder {
+        JwtDecoder::new(jwt::jwk::JwkSet { keys: Vec::new() }, config)
+    }
+
+    fn sign_with(header: &jwt::Header, claims: &Value) -> String {
+        let key = jwt::EncodingKey::from_secret(testing::SECRET.as_bytes());
+
+        jwt::encode(header, claims, &key).unwrap()
+    }
+
+    fn claims() -> Value {
+        testing::claims(chrono::Utc::now())
+    }
+
+    fn jwt_error(result: crate::Result<crate::TokenData>) -> JwtErrorKind {
+        match result {
+            | Err(Error::Jwt(err)) => err.into_kind(),
+            | other => panic!("expected a jwt error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn accepts_valid_token() {
+        let decoder = decoder(&testing::config());
+        let token = testing::sign(&claims());
+
+        let data = decoder.decode(&token).unwrap();
+        assert_eq!(data.claims.issuer, testing::ISSUER);
+        assert_eq!(data.claims.audience.as_slice(), [testing::AUDIENCE]);
+    }
+
+    #[test]
+    fn rejects_oversized_token() {
+        let mut config = testing::config();
+        config.token.limits.max_token_size = 256;
+        let mut claims = claims();
+        claims["blob"] = "x".repeat(512).into();
+
+        let result = decoder(&config).decode(&testing::sign(&claims));
+
+        assert!(matches!(result, Err(Error::LimitExceeded("token size"))));
+    }
+
+    #[test]
+    fn rejects_oversized_header() {
+        let mut config = testing::config();
+        config.token.limits.max_header_size = 64;
+        let header = jwt::Header {
+            cty: Some("x".repeat(128)),
+            ..Default::default()
+        };
+
+        let result = decoder(&config).decode(&sign_with(&header, &claims()));
+
+        assert!(matches!(result, Err(Error::LimitExceeded("header size"))));
+    }
+
+    #[test]
+    fn rejects_long_key_id() {
+        let mut config = testing::config();
+        config.token.limits.max_kid_length = 8;
+        let header = jwt::Header {
+            kid: Some("a-very-long-key-id".to_owned()),
+            ..Default::default()
+        };
+
+        let result = decoder(&config).decode(&sign_with(&header, &claims()));
+
+        assert!(matches!(result, Err(Error::LimitExceeded("kid length"))));
+    }
+
+    #[test]
+    fn rejects_garbage_without_panicking() {
+        let decoder = decoder(&testing::config());
+
+        for token in ["", ".", "a.b.c", "a.b.c.d.e", &"a".repeat(64 * 1024)] {
+            assert!(decoder.decode(token).is_err(), "{token:.16}");
+        }
+    }
+
+    #[test]
+    fn defaults_audience_to_client_id() {
+        let decoder = decoder(&testing::config());
+        let mut claims = claims();
+        claims["aud"] = "other-app".into();
+
+        let result = decoder.decode(&testing::sign(&claims));
+
+        assert_eq!(jwt_error(result), JwtErrorKind::InvalidAudience);
+    }
+
+    #[test]
+    fn rejects_client_id_as_issuer() {
+        let decoder = decoder(&testing::config());
+        let mut claims = claims();
+        claims["iss"] = testing::AUDIENCE.into();
+
+        let result = decoder.decode(&testing::sign(&claims));
+
+        assert_eq!(jwt_error(result), JwtErrorKind::InvalidIssuer);
+    }
+
+    #[test]
+    fn applies_leeway_to_expiry() {
+        let decoder = decoder(&testing::config());
+        let now = chrono::Utc::now();
+        let leeway =
+            chrono::Duration::seconds(VALIDATION_LEEWAY.as_secs() as i64);
+        let mut claims = claims();
+
+        claims["exp"] = (now - leeway / 2).timestamp().into();
+        assert!(decoder.decode(&testing::sign(&claims)).is_ok());
+
+        claims["exp"] = (now - leeway * 2).timestamp().into();
+        let result = decoder.decode(&testing::sign(&claims));
+        assert_eq!(jwt_error(result), JwtErrorKind::ExpiredSignature);
+    }
+
+    #[test]
+    fn accepts_tokens_without_keycloak_claims_by_default() {
+        let decoder = decoder(&testing::config());
+        let mut claims = claims();
+        let claims = claims.as_object_mut().unwrap();
+        claims.remove(USERNAME_CLAIM);
+        claims.remove(REALM_ROLES_CLAIM);
+
+        let token = testing::sign(&claims.clone().into());
+        assert!(decoder.decode(&token).is_ok());
+
+        let mut buf = Vec::new();
+        assert!(decoder.decode_borrowed(&token, &mut buf).is_ok());
+    }
+
+    #[test]
+    fn enforces_required_claims_when_enabled() {
+        let mut config = testing::config();
+        config.token.require_username = true;
+        config.token.require_roles = true;
+        config.token.require_resource_access = true;
+        let decoder = decoder(&config);
+
+        let mut claims = claims();
+        claims[CLIENT_ROLES_CLAIM] = serde_json::json!({
+            "app": { "roles": ["reader"] },
+        });
+        assert!(decoder.decode(&testing::sign(&claims)).is_ok());
+
+        for name in [USERNAME_CLAIM, REALM_ROLES_CLAIM, CLIENT_ROLES_CLAIM] {
+            let mut claims = claims.clone();
+            claims.as_object_mut().unwrap().remove(name);
+
+            let result = decoder.decode(&testing::sign(&claims));
+            assert_eq!(
+                jwt_error(result),
+                JwtErrorKind::MissingRequiredClaim(name.to_owned())
+            );
+        }
+
+        claims[CLIENT_ROLES_CLAIM] = serde_json::json!({});
+        let mut buf = Vec::new();
+        let token = testing::sign(&claims);
+        let missing =
+            JwtErrorKind::MissingRequiredClaim(CLIENT_ROLES_CLAIM.into());
+        assert!(matches!(decoder.decode_borrowed(&token, &mut buf),
+            Err(Error::Jwt(ref err)) if *err.kind() == missing));
+    }
+
+    #[test]
+    fn skips_keycloak_claims_for_generic_providers() {
+        let mut config = testing::config();
+        config.provider = crate::config::Provider::Generic;
+        config.token.issuer = Some(vec![testing::ISSUER.to_owned()]);
+        config.token.require_roles = true;
+        config.token.require_resource_access = true;
+
+        let mut claims = claims();
+        claims.as_object_mut().unwrap().remove(REALM_ROLES_CLAIM);
+
+        assert!(decoder(&config).decode(&testing::sign(&claims)).is_ok());
+    }
+
+    #[test]
+    fn requires_uuid_subjects_for_keycloak() {
+        let mut claims = claims();
+        claims["sub"] = "auth0|5f7c8ec7c33c6c004bbafe82".into();
+        let token = testing::sign(&claims);
+
+        let result = decoder(&testing::config()).decode(&token);
+        assert_eq!(jwt_error(result), JwtErrorKind::InvalidSubject);
+
+        let mut config = testing::config();
+        config.provider = crate::config::Provider::Generic;
+        config.token.issuer = Some(vec![testing::ISSUER.to_owned()]);
+
+        let data = decoder(&config).decode(&token).unwrap();
+        assert_eq!(data.claims.subject_id(), None);
+        assert!(data.claims.is_subject("auth0|5f7c8ec7c33c6c004bbafe82"));
+    }
+
+    #[test]
+    fn exposes_keycloak_subject_ids() {
+        let token = testing::sign(&claims());
+        let data = decoder(&testing::config()).decode(&token).unwrap();
+
+        let id = data.claims.subject_id().unwrap();
+        assert!(data.claims.is_subject_id(id));
+        assert!(data.claims.is_subject(id.to_string()));
+    }
+}
@@ -0,0 +1,77 @@
+use std::{error::Error, sync::Arc};
+
+use rdkafka::{
+    client::OAuthToken,
+    consumer::ConsumerContext,
+    producer::ProducerContext,
+    ClientContext,
+    Message,
+};
+use tokio::runtime::Handle;
+
+use crate::{telemetry, OAuthBearerProvider};
+
+#[derive(Debug, Clone)]
+pub struct OAuthBearerContext {
+    provider: Arc<dyn OAuthBearerProvider>,
+    runtime: Handle,
+}
+
+impl OAuthBearerContext {
+    #[inline]
+    pub fn new(provider: impl OAuthBearerProvider) -> Self {
+        Self::with_runtime(provider, Handle::current())
+    }
+
+    #[inline]
+    pub fn with_runtime(
+        provider: impl OAuthBearerProvider,
+        runtime: Handle,
+    ) -> Self {
+        Self {
+            provider: Arc::new(provider),
+            runtime,
+        }
+    }
+}
+
+impl ClientContext for OAuthBearerContext {
+    const ENABLE_REFRESH_OAUTH_TOKEN: bool = true;
+
+    fn generate_oauth_token(
+        &self,
+        _oauthbearer_config: Option<&str>,
+    ) -> Result<OAuthToken, Box<dyn Error>> {
+        let token = std::thread::scope(|scope| {
+            scope
+                .spawn(|| self.runtime.block_on(self.provider.bearer_token()))
+                .join()
+        })
+        .map_err(|_| "oauth token provider panicked")?
+        .inspect_err(|err| {
+            telemetry::trace_error!(error = %err, "failed to refresh kafka oauth token");
+        })?;
+
+        Ok(OAuthToken {
+            token: token.token.to_string(),
+            principal_name: token.principal,
+            lifetime_ms: token.expires_at.timestamp_millis(),
+        })
+    }
+}
+
+impl ConsumerContext for OAuthBearerContext {}
+
+impl ProducerContext for OAuthBearerContext {
+    type DeliveryOpaque = ();
+
+    fn delivery(
+        &self,
+        result: &rdkafka::producer::DeliveryResult<'_>,
+        _opaque: Self::DeliveryOpaque,
+    ) {
+        if let Err((err, message)) = result {
+            telemetry::trace_warn!(error = %err, topic = message.topic(), "failed to deliver kafka message");
+        }
+    }
+}
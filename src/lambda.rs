@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    telemetry::{self, MIDDLEWARE_TARGET},
+    Claims,
+    Error,
+    JwtDecoder,
+    Result,
+};
+
+const POLICY_VERSION: &str = "2012-10-17";
+const INVOKE_ACTION: &str = "execute-api:Invoke";
+const BEARER_SCHEME: &str = "Bearer";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorizerEvent {
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+
+    pub authorization_token: Option<String>,
+    pub method_arn: Option<String>,
+    pub route_arn: Option<String>,
+
+    #[serde(default)]
+    pub identity_source: Vec<String>,
+
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyResponse {
+    pub principal_id: String,
+    pub policy_document: PolicyDocument,
+    pub context: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PolicyDocument {
+    pub version: &'static str,
+    pub statement: Vec<PolicyStatement>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PolicyStatement {
+    pub action: &'static str,
+    pub effect: PolicyEffect,
+    pub resource: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum PolicyEffect {
+    Allow,
+    Deny,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimpleResponse {
+    pub is_authorized: bool,
+
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub context: HashMap<String, serde_json::Value>,
+}
+
+impl AuthorizerEvent {
+    pub fn token(&self) -> Option<&str> {
+        let value = self
+            .authorization_token
+            .as_deref()
+            .or_else(|| self.identity_source.first().map(String::as_str))
+            .or_else(|| {
+                self.headers
+                    .iter()
+                    .find(|(name, _)| {
+                        name.eq_ignore_ascii_case("authorization")
+                    })
+                    .map(|(_, value)| value.as_str())
+            })?;
+
+        let (scheme, token) = value.trim().split_once(' ')?;
+        scheme
+            .eq_ignore_ascii_case(BEARER_SCHEME)
+            .then(|| token.trim_start())
+            .filter(|token| !token.is_empty())
+    }
+
+    #[inline]
+    pub fn resource(&self) -> Option<&str> {
+        self.method_arn.as_deref().or(self.route_arn.as_deref())
+    }
+}
+
+pub fn policy_response(
+    decoder: &JwtDecoder,
+    event: &AuthorizerEvent,
+) -> Result<PolicyResponse> {
+    let claims = validate(decoder, event)?;
+    let resource = event.resource().ok_or_else(|| {
+        Error::Config("authorizer event has no method or route arn".into())
+    })?;
+
+    Ok(PolicyResponse {
+        principal_id: claims.subject.to_string(),
+        policy_document: PolicyDocument {
+            version: POLICY_VERSION,
+            statement: vec![PolicyStatement {
+                action: INVOKE_ACTION,
+                effect: PolicyEffect::Allow,
+                resource: resource.to_owned(),
+            }],
+        },
+        context: context(&claims),
+    })
+}
+
+pub fn simple_response(
+    decoder: &JwtDecoder,
+    event: &AuthorizerEvent,
+) -> SimpleResponse {
+    match validate(decoder, event) {
+        | Ok(claims) => SimpleResponse {
+            is_authorized: true,
+            context: context(&claims),
+        },
+        | Err(err) => {
+            telemetry::trace_warn!(target: MIDDLEWARE_TARGET, error = %err, "rejected authorizer request");
+
+            SimpleResponse {
+                is_authorized: false,
+                context: HashMap::new(),
+            }
+        }
+    }
+}
+
+fn validate(decoder: &JwtDecoder, event: &AuthorizerEvent) -> Result<Claims> {
+    let token = event.token().ok_or_else(|| {
+        Error::Jwt(jsonwebtoken::errors::ErrorKind::InvalidToken.into())
+    })?;
+
+    decoder.decode(token).map(|data| data.claims)
+}
+
+fn context(claims: &Claims) -> HashMap<String, serde_json::Value> {
+    let mut context = HashMap::new();
+    context.insert("sub".into(), claims.subject.as_str().into());
+    context.insert("realmRoles".into(), claims.realm.roles.join(",").into());
+
+    if let Some(ref username) = claims.username {
+        context.insert("username".into(), username.as_str().into());
+    }
+
+    if let Some(ref azp) = claims.authorized_party {
+        context.insert("clientId".into(), azp.as_str().into());
+    }
+
+    if let Some(ref email) = claims.email {
+        context.insert("email".into(), email.as_str().into());
+    }
+
+    if let Some(serde_json::Value::String(scope)) = claims.claim("scope") {
+        context.insert("scope".into(), scope.as_str().into());
+    }
+
+    context
+}
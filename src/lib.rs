@@ -1,104 +1,254 @@
+mod access;
+mod action;
+mod assertion;
+mod authorizer;
+#[cfg(feature = "authz")]
+mod authz;
+mod bearer;
+mod builder;
+mod cache;
+mod clock;
 mod config;
+mod credentials;
+#[cfg(feature = "dev")]
+mod dev;
+mod discovery;
+mod doctor;
+mod encoder;
+mod enrich;
 mod error;
+mod exchange;
+mod governor;
+mod health;
+mod hop;
+mod intern;
+mod introspect;
+#[cfg(feature = "jwe")]
+mod jwe;
+mod jwks;
 mod jwt;
+#[cfg(feature = "lambda")]
+pub mod lambda;
+mod principal;
+mod provider;
+mod registration;
+#[cfg(feature = "watch")]
+mod reload;
+mod rotation;
+mod telemetry;
+#[cfg(test)]
+mod testing;
 mod token;
-
+mod transport;
+mod validator;
+#[cfg(feature = "x5c")]
+mod x5c;
+
+pub mod admin;
+pub mod jti;
+#[cfg(feature = "rdkafka")]
+pub mod kafka;
 #[cfg(feature = "middleware")]
 pub mod middleware;
+pub mod policy;
+pub mod representations;
+#[cfg(feature = "reqwest-middleware")]
+pub mod reqwest_middleware;
+pub mod secret;
+#[cfg(feature = "warp")]
+pub mod warp;
 
 use std::{ops::Add, sync::Arc};
 
+use arc_swap::{ArcSwap, ArcSwapOption};
 use reqwest::ClientBuilder;
 use serde_with::DurationSeconds;
-use tokio::sync::RwLock;
+use tokio::sync::{watch, RwLock};
 
+#[cfg(feature = "authz")]
+pub use self::authz::{AuthorizationClaim, Permission};
+pub use self::{
+    access::ResourceAccess,
+    action::{ActionToken, ActionTokenKind},
+    admin::AdminClient,
+    assertion::ClientAuth,
+    authorizer::{Authorizer, Decision, DenyReason, Requirement},
+    bearer::{BearerFuture, OAuthBearerProvider, OAuthBearerToken},
+    builder::ReCloakBuilder,
+    clock::{Clock, SystemClock},
+    config::{
+        ClientCredentials,
+        ClientKey,
+        Config,
+        EnrichmentSource,
+        JwksTlsConfig,
+        KeyPinning,
+        LoggingConfig,
+        OffloadConfig,
+        Provider,
+        SecretString,
+        ServerEndpoints,
+        TrustedIssuer,
+        ValidationMode,
+    },
+    credentials::TokenRequirements,
+    doctor::{CheckOutcome, DoctorCheck, DoctorCheckKind, DoctorReport},
+    encoder::JwtEncoder,
+    error::{Error, OAuthErrorCode, Result},
+    health::{
+        HealthCheck,
+        HealthProbe,
+        HealthReport,
+        HealthStatus,
+        MetricSample,
+        Metrics,
+    },
+    hop::{HopClaim, HopTokens},
+    introspect::Introspection,
+    jwks::SharedDecoder,
+    jwt::{DecodeReport, JwtDecoder},
+    principal::{KnownRole, Principal, RoleSet},
+    provider::{
+        AccessTokenPassword,
+        CredentialFuture,
+        CredentialProvider,
+        PasswordCredentials,
+    },
+    registration::{ClientMetadata, ClientRegistration},
+    token::{Claims, ClaimsRef, Organization, RolesClaim, RolesRef, TokenData},
+    transport::{
+        HttpTransport,
+        TransportFuture,
+        TransportRequest,
+        TransportResponse,
+    },
+    validator::{ClaimsValidator, RequireEmailVerified},
+};
+#[cfg(feature = "dev")]
 pub use self::{
-    config::{Config, ServerEndpoints},
-    error::{Error, Result},
-    jwt::JwtDecoder,
-    token::{Claims, TokenData},
+    config::{DevConfig, DevUser},
+    dev::DevIssuer,
 };
 use crate::token::UserInfo;
 
+const OFFLINE_TOKEN_TYPE: &str = "Offline";
+
 #[derive(Debug)]
 pub struct ReCloak {
     client: reqwest::Client,
-    decoder: JwtDecoder,
-    config: Config,
-    urls: ServerEndpoints,
+    jwks_client: ArcSwapOption<reqwest::Client>,
+    decoder: jwks::SharedDecoder,
+    config: ArcSwap<Config>,
+    urls: ArcSwap<ServerEndpoints>,
     token: RwLock<Option<TokenResponse>>,
+    token_updates: watch::Sender<Option<arcstr::ArcStr>>,
+    tokens: credentials::TokenStore,
+    scoped_tokens: credentials::TokenStore<TokenRequirements>,
+    secret_source: Option<Arc<dyn secret::SecretSource>>,
+    validators: validator::ClaimsValidators,
+    jti_store: Option<Arc<dyn jti::JtiStore>>,
+    hop_tokens: Option<Arc<HopTokens>>,
+    roles: admin::RoleCache,
+    principals: ArcSwap<principal::RoleRegistry>,
+    introspections: introspect::IntrospectionCache,
+    enrichments: enrich::EnrichmentCache,
+    exchanges: exchange::ExchangeCache,
+    governor: governor::Governor,
+    clock: Arc<dyn Clock>,
+    #[cfg(feature = "dev")]
+    dev: Option<DevIssuer>,
 }
 
 impl ReCloak {
+    #[inline]
     pub async fn new(config: Config) -> Result<Arc<Self>> {
-        tracing::debug!(
-            agent = %config.http.user_agent,
-            auth_server_url = %config.http.auth_server_url,
-            realm = %config.client.realm,
-            client_id = %config.client.id,
-            "creating keycloak client",
-        );
-
-        let mut builder =
-            ClientBuilder::new().user_agent(&config.http.user_agent);
-        if config.http.allow_insecure {
-            builder = builder.danger_accept_invalid_certs(true);
-        }
+        Self::builder(config).build().await
+    }
 
-        let client = builder.build()?;
+    #[inline]
+    pub async fn with_transport(
+        config: Config,
+        transport: impl HttpTransport,
+    ) -> Result<Arc<Self>> {
+        Self::builder(config).transport(transport).build().await
+    }
 
-        let urls = config.urls()?;
-        let jwks = Self::get_certs(&client, urls.jwks.clone()).await?;
-        let decoder = JwtDecoder::new(jwks, &config);
-
-        Ok(Arc::new(Self {
-            config,
-            client,
-            decoder,
-            urls,
-            token: Default::default(),
-        }))
+    #[inline]
+    pub const fn builder(config: Config) -> ReCloakBuilder {
+        ReCloakBuilder::new(config)
     }
 
-    #[tracing::instrument(skip(self, creds))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, creds)))]
     pub async fn login_client(
         &self,
         creds: ClientGrant<'_>,
     ) -> Result<TokenResponse> {
-        #[derive(serde::Deserialize)]
-        struct ErrorDto {
-            error: String,
-            error_description: Option<String>,
+        let governor = self.config.load().governor.clone();
+        let mut attempt = 0;
+
+        loop {
+            let err = match self.login_client_once(&creds).await {
+                | Ok(token) => return Ok(token),
+                | Err(err) if err.is_transient() => err,
+                | Err(err) => return Err(err),
+            };
+
+            let delay = governor
+                .retry_backoff
+                .saturating_mul(1 << attempt.min(16))
+                .max(err.retry_after().unwrap_or_default());
+
+            if attempt >= governor.max_retries || delay > governor.cooldown {
+                return Err(err);
+            }
+
+            attempt += 1;
+            telemetry::trace_debug!(error = %err, attempt, ?delay, "retrying token request");
+            tokio::time::sleep(delay).await;
         }
+    }
 
+    async fn login_client_once(
+        &self,
+        creds: &ClientGrant<'_>,
+    ) -> Result<TokenResponse> {
         let resp = self
-            .client
-            .post(self.urls.token.clone())
-            .form(&creds)
-            .send()
+            .governor
+            .send(self.client.post(self.urls.load().token.clone()).form(creds))
             .await?;
 
-        if resp.status().is_success() {
-            resp.json::<TokenResponse>().await.map_err(From::from)
-        } else {
-            let err =
-                resp.json::<ErrorDto>().await.map_err(crate::Error::from)?;
+        if resp.status.is_success() {
+            let mut token = resp.json::<TokenResponse>()?;
+            token.issued_at = self.clock.now();
 
-            Err(crate::Error::Authentication {
-                code: err.error,
-                description: err.error_description,
-            })
+            Ok(token)
+        } else {
+            Err(Error::from_auth_response(&resp))
         }
     }
 
-    #[tracing::instrument(skip(self))]
+    #[inline]
     pub async fn authenticate(&self) -> Result<arcstr::ArcStr> {
+        self.authenticate_valid_for(std::time::Duration::ZERO).await
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn authenticate_valid_for(
+        &self,
+        min_validity: std::time::Duration,
+    ) -> Result<arcstr::ArcStr> {
         if let Some(token) = self.token.read().await.as_ref() {
-            if !token.is_access_expired() {
+            let now = self.clock.now();
+            let valid_until = chrono::Duration::from_std(min_validity)
+                .ok()
+                .and_then(|margin| now.checked_add_signed(margin))
+                .unwrap_or(chrono::DateTime::<chrono::Utc>::MAX_UTC);
+
+            if !token.is_access_expired(valid_until) {
                 return Ok(token.access_token.clone());
             }
 
-            if let Some(refresh_token) = token.valid_refresh_token() {
+            if let Some(refresh_token) = token.valid_refresh_token(now) {
                 let token_resp = self
                     .login_client(ClientGrant::RefreshToken { refresh_token })
                     .await?;
@@ -106,57 +256,78 @@ impl ReCloak {
                 let access_token = token_resp.access_token.clone();
 
                 *self.token.write().await = Some(token_resp);
+                self.token_updates.send_replace(Some(access_token.clone()));
 
                 return Ok(access_token);
             }
         }
 
-        let id = self.config.client.id.as_str();
-        let secret = match self.config.client.secret {
-            | config::ClientSecret::Basic(ref secret) => secret,
-        };
-        let scope = Some(self.config.client.scope.as_str());
+        let config = self.refresh_secret().await?;
+        let id = config.client.id.as_str();
+        let auth =
+            self.client_auth(id, &config.client.secret, "client_credentials")?;
+        let scope = config.client.requested_scope();
+        let scope = Some(scope.as_ref());
 
         let token_resp = self
-            .login_client(ClientGrant::ClientCredentials { id, secret, scope })
+            .login_client(ClientGrant::ClientCredentials { id, auth, scope })
             .await?;
         let access_token = token_resp.access_token.clone();
 
         *self.token.write().await = Some(token_resp);
+        self.token_updates.send_replace(Some(access_token.clone()));
 
         Ok(access_token)
     }
 
-    #[tracing::instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn invalidate_token(&self) {
+        *self.token.write().await = None;
+        self.scoped_tokens.write().await.clear();
+        self.token_updates.send_replace(None);
+    }
+
+    #[cfg(feature = "dev")]
+    #[inline]
+    pub const fn dev_issuer(&self) -> Option<&DevIssuer> {
+        self.dev.as_ref()
+    }
+
+    #[inline]
+    pub fn token_updates(&self) -> watch::Receiver<Option<arcstr::ArcStr>> {
+        self.token_updates.subscribe()
+    }
+
+    #[inline]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub async fn user_info(&self, token: &str) -> Result<UserInfo> {
-        #[derive(serde::Deserialize)]
-        struct ErrorDto {
-            error: String,
-            error_description: Option<String>,
-        }
+        self.user_info_as(token).await
+    }
 
+    pub(crate) async fn user_info_as<T>(&self, token: &str) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let url = self
+            .urls
+            .load()
+            .userinfo
+            .clone()
+            .ok_or(Error::Unsupported("the userinfo endpoint"))?;
         let resp = self
-            .client
-            .get(self.urls.userinfo.clone())
-            .bearer_auth(token)
-            .send()
+            .governor
+            .send(self.client.get(url).bearer_auth(token))
             .await?;
 
-        if resp.status().is_success() {
-            resp.json::<UserInfo>().await.map_err(From::from)
+        if resp.status.is_success() {
+            resp.json()
         } else {
-            let err =
-                resp.json::<ErrorDto>().await.map_err(crate::Error::from)?;
-
-            Err(crate::Error::Authentication {
-                code: err.error,
-                description: err.error_description,
-            })
+            Err(Error::from_auth_response(&resp))
         }
     }
 
     #[inline]
-    #[tracing::instrument(skip(self))]
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
     pub async fn client_info(&self) -> Result<UserInfo> {
         let token = self.authenticate().await?;
 
@@ -164,39 +335,177 @@ impl ReCloak {
     }
 
     #[inline]
-    #[tracing::instrument(skip(self))]
     pub fn decode_token(&self, token: &str) -> Result<TokenData> {
         self.decoder.decode(token)
     }
 
     #[inline]
-    pub async fn jwks(&self) -> Result<jsonwebtoken::jwk::JwkSet> {
-        Self::get_certs(&self.client, self.urls.jwks.clone()).await
+    pub fn decode_token_borrowed<'b>(
+        &self,
+        token: &str,
+        buf: &'b mut Vec<u8>,
+    ) -> Result<ClaimsRef<'b>> {
+        self.decoder.decode_borrowed(token, buf)
+    }
+
+    #[inline]
+    pub fn decode_token_with_report(
+        &self,
+        token: &str,
+    ) -> Result<(TokenData, DecodeReport)> {
+        self.decoder.decode_with_report(token)
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip(self, token))
+    )]
+    pub async fn validate_token(&self, token: &str) -> Result<TokenData> {
+        if self.config.load().introspection.opaque_tokens
+            && !jwt::is_structured(token)
+        {
+            let data = self.introspect_claims(token).await?;
+            jwt::check_introspected(&data.claims, &self.config.load())?;
+            self.validators.check(&data.claims, self.logging())?;
+            self.check_revoked(&data.claims).await?;
+
+            return Ok(data);
+        }
+
+        let data = self.decoder.decode_offloaded(token).await?;
+        self.revalidate_token(token, &data.claims).await?;
+
+        Ok(data)
+    }
+
+    // the checks that must not be skipped for already decoded claims.
+    pub(crate) async fn revalidate_token(
+        &self,
+        token: &str,
+        claims: &Claims,
+    ) -> Result<()> {
+        let config = self.config.load();
+        let introspect = config.token.validation
+            == ValidationMode::Introspection
+            || (config.introspection.opaque_tokens
+                && !jwt::is_structured(token));
+
+        if introspect && !self.introspect(token).await?.active {
+            return Err(Error::InactiveToken);
+        }
+
+        self.validators.check(claims, self.logging())?;
+        self.check_revoked(claims).await
+    }
+
+    #[inline]
+    pub fn clock(&self) -> &dyn Clock {
+        &*self.clock
     }
 
     #[inline]
-    pub const fn config(&self) -> &Config {
-        &self.config
+    pub fn config(&self) -> Arc<Config> {
+        self.config.load_full()
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, config)))]
+    pub async fn reconfigure(&self, config: Config) -> Result<()> {
+        builder::check_key_material(&config)?;
+        config.governor.check()?;
+
+        let config =
+            discovery::resolve(&self.client, &self.governor, config).await?;
+        let urls = config.urls()?;
+        let principals = principal::RoleRegistry::new(&config.principal)?;
+        let jwks_client = jwks::tls_client(&config)?;
+        let jwks = jwks::fetch_initial(
+            &self.client,
+            &self.governor,
+            urls.jwks.clone(),
+            jwks_client.as_ref(),
+        )
+        .await?;
+        let federated =
+            jwks::fetch_federated(&self.client, &self.governor, &config)
+                .await?;
+        let config = Arc::new(config);
+
+        let mut token = self.token.write().await;
+        let mut tokens = self.tokens.write().await;
+        let mut scoped_tokens = self.scoped_tokens.write().await;
+
+        self.config.store(config.clone());
+        self.urls.store(Arc::new(urls));
+        self.principals.store(Arc::new(principals));
+        self.jwks_client.store(jwks_client.map(Arc::new));
+        self.governor.update(&config.governor);
+        self.decoder.reset(jwks, federated, config).await;
+        *token = None;
+        self.token_updates.send_replace(None);
+        tokens.clear();
+        scoped_tokens.clear();
+
+        drop(scoped_tokens);
+        drop(tokens);
+        drop(token);
+
+        self.introspections.clear().await;
+        self.enrichments.clear().await;
+        self.exchanges.clear().await;
+        self.roles.clear().await;
+
+        telemetry::trace_info!("keycloak client reconfigured");
+
+        Ok(())
     }
 
-    #[tracing::instrument]
-    async fn get_certs(
-        client: &reqwest::Client,
-        url: url::Url,
-    ) -> Result<jsonwebtoken::jwk::JwkSet> {
-        tracing::debug!(%url, "fetching keycloak certs");
-
-        client
-            .get(url)
-            .send()
-            .await?
-            .json()
-            .await
-            .map_err(From::from)
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self, secret)))]
+    pub async fn update_secret(&self, secret: impl Into<String>) {
+        let mut config = Config::clone(&self.config.load());
+        config.client.secret = config::ClientSecret::Basic(secret.into());
+
+        let mut token = self.token.write().await;
+
+        self.config.store(Arc::new(config));
+        *token = None;
+        self.token_updates.send_replace(None);
+
+        drop(token);
+
+        self.scoped_tokens.write().await.clear();
+
+        telemetry::trace_info!("client secret updated");
+    }
+
+    fn http_client(config: &Config) -> Result<reqwest::Client> {
+        let mut builder =
+            ClientBuilder::new().user_agent(&config.http.user_agent);
+        if config.http.allow_insecure {
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+
+        if let Some(ref proxy_config) = config.http.proxy {
+            let mut proxy = reqwest::Proxy::all(proxy_config.url.clone())?
+                .no_proxy(reqwest::NoProxy::from_string(
+                    &proxy_config.no_proxy.join(","),
+                ));
+
+            if let Some(ref username) = proxy_config.username {
+                let password = proxy_config
+                    .password
+                    .as_ref()
+                    .map_or("", SecretString::expose);
+                proxy = proxy.basic_auth(username, password);
+            }
+
+            builder = builder.proxy(proxy);
+        }
+
+        builder.build().map_err(From::from)
     }
 }
 
-#[derive(Debug, Clone, Copy, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize)]
 #[serde(tag = "grant_type")]
 pub enum ClientGrant<'a> {
     #[serde(rename = "client_credentials")]
@@ -204,8 +513,8 @@ pub enum ClientGrant<'a> {
         #[serde(rename = "client_id")]
         id: &'a str,
 
-        #[serde(rename = "client_secret")]
-        secret: &'a str,
+        #[serde(flatten)]
+        auth: ClientAuth<'a>,
 
         #[serde(default = "openid")]
         scope: Option<&'a str>,
@@ -216,6 +525,33 @@ pub enum ClientGrant<'a> {
         #[serde(rename = "refresh_token")]
         refresh_token: &'a str,
     },
+
+    #[serde(rename = "urn:ietf:params:oauth:grant-type:token-exchange")]
+    TokenExchange {
+        #[serde(rename = "client_id")]
+        id: &'a str,
+
+        #[serde(flatten)]
+        auth: ClientAuth<'a>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        subject_token: Option<&'a str>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        subject_token_type: Option<&'a str>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        requested_subject: Option<&'a str>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        requested_token_type: Option<&'a str>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        audience: Option<&'a str>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        scope: Option<&'a str>,
+    },
 }
 
 #[derive(Debug, Clone, Copy, serde::Deserialize)]
@@ -242,22 +578,76 @@ pub struct TokenResponse {
     #[serde_as(as = "Option<DurationSeconds<i64>>")]
     pub refresh_expires_in: Option<chrono::Duration>,
 
-    #[serde(skip, default = "chrono::Local::now")]
-    issued_at: chrono::DateTime<chrono::Local>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+
+    #[serde(skip, default = "chrono::Utc::now")]
+    issued_at: chrono::DateTime<chrono::Utc>,
 }
 
 impl TokenResponse {
+    pub fn is_offline(&self) -> bool {
+        let offline_type = self
+            .refresh_token
+            .as_deref()
+            .and_then(jwt::unverified_token_type)
+            .is_some_and(|typ| typ == OFFLINE_TOKEN_TYPE);
+
+        offline_type
+            || self.scope.as_deref().is_some_and(|scope| {
+                scope
+                    .split_whitespace()
+                    .any(|scope| scope == config::OFFLINE_ACCESS_SCOPE)
+            })
+    }
+
+    #[inline]
+    pub const fn issued_at(&self) -> chrono::DateTime<chrono::Utc> {
+        self.issued_at
+    }
+
+    #[inline]
+    pub fn expires_at(&self) -> chrono::DateTime<chrono::Utc> {
+        self.issued_at + self.expires_in
+    }
+
+    #[inline]
+    pub fn refresh_expires_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.refresh_token.as_ref()?;
+
+        self.refresh_expires_in
+            .filter(|d| !d.is_zero())
+            .map(|d| self.issued_at + d)
+    }
+
     #[inline]
-    fn is_access_expired(&self) -> bool {
-        self.issued_at + self.expires_in < chrono::Local::now()
+    pub fn remaining(&self, clock: &dyn Clock) -> chrono::Duration {
+        self.remaining_at(clock.now())
     }
 
-    fn valid_refresh_token(&self) -> Option<&str> {
+    #[inline]
+    pub fn remaining_at(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> chrono::Duration {
+        (self.expires_at() - now).max(chrono::Duration::zero())
+    }
+
+    #[inline]
+    fn is_access_expired(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.expires_at() < now
+    }
+
+    fn valid_refresh_token(
+        &self,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Option<&str> {
         match (&self.refresh_token, &self.refresh_expires_in) {
             | (Some(rt), None) => Some(rt.as_str()),
-            | (Some(rt), Some(d))
-                if self.issued_at.add(*d) > chrono::Local::now() =>
-            {
+            | (Some(rt), Some(d)) if d.is_zero() && self.is_offline() => {
+                Some(rt.as_str())
+            }
+            | (Some(rt), Some(d)) if self.issued_at.add(*d) > now => {
                 Some(rt.as_str())
             }
             | _ => None,
@@ -0,0 +1,252 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{FromRef, FromRequestParts},
+    http::{
+        header::{
+            CACHE_CONTROL,
+            CONTENT_TYPE,
+            ETAG,
+            IF_NONE_MATCH,
+            RETRY_AFTER,
+            VARY,
+            WWW_AUTHENTICATE,
+        },
+        request::Parts,
+        HeaderMap,
+        HeaderValue,
+        StatusCode,
+    },
+    response::{IntoResponse, Response},
+    routing::MethodRouter,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::Serialize;
+
+use super::{
+    http::{RequestAuthorization, ServerAuthError},
+    websocket::HandshakeAuth,
+};
+use crate::{
+    telemetry::{self, MIDDLEWARE_TARGET},
+    Error,
+};
+
+const PROBLEM_JSON: &str = "application/problem+json";
+const JWKS_JSON: &str = "application/jwk-set+json";
+
+#[derive(Debug, Clone)]
+pub struct UpgradeGuard {
+    pub authorization: RequestAuthorization,
+    pub protocol: arcstr::ArcStr,
+}
+
+#[derive(Debug, Serialize)]
+struct Problem<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    title: &'a str,
+    status: u16,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+}
+
+impl<S> FromRequestParts<S> for UpgradeGuard
+where
+    S: Send + Sync,
+    HandshakeAuth: FromRef<S>,
+{
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        let auth = HandshakeAuth::from_ref(state);
+
+        let authorization = auth
+            .authorize_handshake(&parts.headers, &parts.uri)
+            .await
+            .map_err(|err| {
+                telemetry::trace_warn!(target: MIDDLEWARE_TARGET, error = %err, "rejected upgrade request");
+
+                (StatusCode::UNAUTHORIZED, err.to_string())
+            })?;
+
+        Ok(Self {
+            authorization,
+            protocol: auth.selected_protocol().into(),
+        })
+    }
+}
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        let status = status_code(&self);
+        if status.is_server_error() {
+            telemetry::trace_error!(target: MIDDLEWARE_TARGET, error = %self, "request failed");
+        }
+
+        let mut res =
+            problem(status, status.is_client_error().then(|| self.to_string()));
+
+        if let Error::RateLimited {
+            retry_after: Some(delay),
+            ..
+        } = self
+        {
+            res.headers_mut()
+                .insert(RETRY_AFTER, delay.as_secs().max(1).into());
+        }
+
+        res
+    }
+}
+
+impl IntoResponse for ServerAuthError {
+    fn into_response(self) -> Response {
+        let challenge = match self {
+            | ServerAuthError::MissingHeader => "Bearer",
+            | _ if self.is_forbidden() => "Bearer error=\"insufficient_scope\"",
+            | _ => "Bearer error=\"invalid_token\"",
+        };
+
+        let mut res = problem(self.status_code(), Some(self.to_string()));
+        res.headers_mut()
+            .insert(WWW_AUTHENTICATE, HeaderValue::from_static(challenge));
+        // the rejection depends on the credentials the request carried.
+        res.headers_mut()
+            .append(VARY, HeaderValue::from_static("authorization"));
+
+        res
+    }
+}
+
+fn status_code(err: &Error) -> StatusCode {
+    match err {
+        | Error::Jwt(_)
+        | Error::InactiveToken
+        | Error::RevokedToken
+        | Error::ReplayedToken
+        | Error::ClaimsRejected(_)
+        | Error::LimitExceeded(_)
+        | Error::InvalidTokenType(_)
+        | Error::UnauthorizedParty(_) => StatusCode::UNAUTHORIZED,
+        #[cfg(feature = "jwe")]
+        | Error::Decryption(_) => StatusCode::UNAUTHORIZED,
+        | Error::Admin { status, .. } if status.is_client_error() => *status,
+        | Error::CircuitOpen | Error::RateLimited { .. } => {
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+        | Error::Http(err) if err.is_timeout() => StatusCode::GATEWAY_TIMEOUT,
+        | Error::Http(_)
+        | Error::Transport(_)
+        | Error::Admin { .. }
+        | Error::Registration { .. }
+        | Error::Authentication { .. }
+        | Error::UnexpectedResponse { .. } => StatusCode::BAD_GATEWAY,
+        | _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+pub fn client_jwks_route<S>(kc: Arc<crate::ReCloak>) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    axum::routing::get(move || {
+        let jwks = kc.client_jwks().and_then(|jwks| {
+            serde_json::to_string(&jwks)
+                .map_err(|err| Error::Config(err.to_string()))
+        });
+
+        async move {
+            match jwks {
+                | Ok(body) => (
+                    [(CONTENT_TYPE, HeaderValue::from_static(JWKS_JSON))],
+                    body,
+                )
+                    .into_response(),
+                | Err(err) => {
+                    telemetry::trace_error!(target: MIDDLEWARE_TARGET, error = %err, "failed to export client jwks");
+
+                    problem(StatusCode::INTERNAL_SERVER_ERROR, None)
+                }
+            }
+        }
+    })
+}
+
+impl crate::ReCloak {
+    pub fn serve_jwks<S>(self: &Arc<Self>) -> MethodRouter<S>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        let kc = self.clone();
+
+        axum::routing::get(move |headers: HeaderMap| async move {
+            let (jwks, fresh_for) = match kc.published_jwks().await {
+                | Ok(published) => published,
+                | Err(err) => return err.into_response(),
+            };
+
+            let body = match serde_json::to_vec(&jwks) {
+                | Ok(body) => body,
+                | Err(err) => {
+                    return Error::Config(err.to_string()).into_response();
+                }
+            };
+
+            let digest = ring::digest::digest(&ring::digest::SHA256, &body);
+            let etag = format!("\"{}\"", URL_SAFE_NO_PAD.encode(digest));
+            let cache_control = match fresh_for {
+                | Some(fresh_for) if !fresh_for.is_zero() => {
+                    format!("public, max-age={}", fresh_for.as_secs())
+                }
+                | _ => "no-cache".to_owned(),
+            };
+
+            let cache_headers =
+                [(ETAG, etag.clone()), (CACHE_CONTROL, cache_control)];
+
+            if is_not_modified(&headers, &etag) {
+                return (StatusCode::NOT_MODIFIED, cache_headers)
+                    .into_response();
+            }
+
+            (
+                [(CONTENT_TYPE, HeaderValue::from_static(JWKS_JSON))],
+                cache_headers,
+                body,
+            )
+                .into_response()
+        })
+    }
+}
+
+fn is_not_modified(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|tag| tag.trim())
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+fn problem(status: StatusCode, detail: Option<String>) -> Response {
+    let body = serde_json::to_string(&Problem {
+        kind: "about:blank",
+        title: status.canonical_reason().unwrap_or_default(),
+        status: status.as_u16(),
+        detail,
+    })
+    .unwrap_or_default();
+
+    (
+        status,
+        [(CONTENT_TYPE, HeaderValue::from_static(PROBLEM_JSON))],
+        body,
+    )
+        .into_response()
+}
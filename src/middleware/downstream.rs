@@ -0,0 +1,320 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use http::{header::AUTHORIZATION, Request, Uri};
+use tower::{Layer, Service};
+
+use super::http::{RequestAuthorization, TokenHeader};
+use crate::{
+    telemetry::{self, MIDDLEWARE_TARGET},
+    TokenRequirements,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DownstreamStrategy {
+    Forward,
+    Exchange(TokenRequirements),
+    ServiceAccount(TokenRequirements),
+    Hop,
+    Anonymous,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DownstreamAuth {
+    targets: HashMap<String, DownstreamStrategy>,
+    fallback: Option<DownstreamStrategy>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DownstreamAuthLayer {
+    kc: Arc<crate::ReCloak>,
+    policy: Arc<DownstreamAuth>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DownstreamAuthService<S> {
+    kc: Arc<crate::ReCloak>,
+    policy: Arc<DownstreamAuth>,
+    inner: S,
+}
+
+impl DownstreamAuth {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn target(
+        mut self,
+        host: impl Into<String>,
+        strategy: DownstreamStrategy,
+    ) -> Self {
+        self.targets
+            .insert(host.into().to_ascii_lowercase(), strategy);
+        self
+    }
+
+    #[inline]
+    pub fn fallback(mut self, strategy: DownstreamStrategy) -> Self {
+        self.fallback = Some(strategy);
+        self
+    }
+
+    pub fn strategy_for(&self, uri: &Uri) -> Option<&DownstreamStrategy> {
+        let authority = uri.authority().map(|authority| {
+            (
+                authority.as_str().to_ascii_lowercase(),
+                authority.host().to_ascii_lowercase(),
+            )
+        });
+
+        authority
+            .and_then(|(authority, host)| {
+                self.targets
+                    .get(&authority)
+                    .or_else(|| self.targets.get(&host))
+            })
+            .or(self.fallback.as_ref())
+    }
+}
+
+impl DownstreamAuthLayer {
+    #[inline]
+    pub fn new(kc: Arc<crate::ReCloak>, policy: DownstreamAuth) -> Self {
+        Self {
+            kc,
+            policy: Arc::new(policy),
+        }
+    }
+}
+
+impl<S> Layer<S> for DownstreamAuthLayer {
+    type Service = DownstreamAuthService<S>;
+
+    #[inline]
+    fn layer(&self, inner: S) -> Self::Service {
+        DownstreamAuthService {
+            kc: self.kc.clone(),
+            policy: self.policy.clone(),
+            inner,
+        }
+    }
+}
+
+impl<S, B> Service<Request<B>> for DownstreamAuthService<S>
+where
+    S: Service<Request<B>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    B: Send + 'static,
+{
+    type Error = S::Error;
+    type Response = S::Response;
+
+    type Future = Pin<
+        Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>,
+    >;
+
+    #[inline]
+    fn poll_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<B>) -> Self::Future {
+        let kc = self.kc.clone();
+        let policy = self.policy.clone();
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        Box::pin(async move {
+            let Some(strategy) = policy.strategy_for(req.uri()).cloned() else {
+                return inner.call(req).await;
+            };
+            let user = req.extensions().get::<RequestAuthorization>().cloned();
+
+            let token = match (strategy, user) {
+                | (DownstreamStrategy::Anonymous, _) => {
+                    req.headers_mut().remove(AUTHORIZATION);
+
+                    return inner.call(req).await;
+                }
+                | (DownstreamStrategy::Forward, Some(user)) => {
+                    user.apply_to(req.headers_mut());
+
+                    return inner.call(req).await;
+                }
+                | (DownstreamStrategy::Exchange(requirements), Some(user)) => {
+                    let Some(token) = user.bearer_token() else {
+                        telemetry::trace_error!(
+                            target: MIDDLEWARE_TARGET,
+                            "request authorization carries no token to \
+                             exchange, proceeding without token"
+                        );
+
+                        return inner.call(req).await;
+                    };
+
+                    kc.exchange_token(token, &requirements).await
+                }
+                | (DownstreamStrategy::Hop, Some(user)) => {
+                    kc.mint_hop_token(user.claims()).map(Into::into)
+                }
+                | (DownstreamStrategy::ServiceAccount(requirements), _) => {
+                    kc.authenticate_for(&requirements).await
+                }
+                | (strategy, None) => {
+                    telemetry::trace_error!(
+                        target: MIDDLEWARE_TARGET,
+                        ?strategy,
+                        "no request authorization to propagate, proceeding \
+                         without token"
+                    );
+
+                    return inner.call(req).await;
+                }
+            };
+
+            match token {
+                | Ok(token) => match TokenHeader::DEFAULT.value(&token) {
+                    | Some(value) => {
+                        req.headers_mut().insert(AUTHORIZATION, value.into());
+                    }
+                    | None => {
+                        telemetry::trace_error!(
+                            target: MIDDLEWARE_TARGET,
+                            "token is not a valid header value, proceeding \
+                             without token"
+                        );
+                    }
+                },
+                | Err(err) => {
+                    telemetry::trace_error!(target: MIDDLEWARE_TARGET, error = %err, "failed to obtain downstream token, proceeding without token");
+                }
+            }
+
+            inner.call(req).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{header::AUTHORIZATION, Request, Response, Uri};
+    use serde_json::json;
+    use tower::{service_fn, Layer, ServiceExt};
+
+    use super::{DownstreamAuth, DownstreamAuthLayer, DownstreamStrategy};
+    use crate::{
+        middleware::http::{RequestAuthorization, ServerAuthServiceLayer},
+        testing,
+        TokenRequirements,
+    };
+
+    type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+    const TOKEN_PATH: &str = "/realms/test/protocol/openid-connect/token";
+
+    fn service_account() -> DownstreamStrategy {
+        DownstreamStrategy::ServiceAccount(TokenRequirements::default())
+    }
+
+    #[test]
+    fn picks_strategy_by_authority_then_host() {
+        let policy = DownstreamAuth::new()
+            .target("billing.internal:8443", DownstreamStrategy::Forward)
+            .target("Billing.internal", DownstreamStrategy::Anonymous)
+            .fallback(service_account());
+
+        let strategy = |uri: &str| {
+            policy.strategy_for(&uri.parse::<Uri>().unwrap()).cloned()
+        };
+
+        assert_eq!(
+            strategy("https://billing.internal:8443/invoices"),
+            Some(DownstreamStrategy::Forward)
+        );
+        assert_eq!(
+            strategy("https://billing.internal/invoices"),
+            Some(DownstreamStrategy::Anonymous)
+        );
+        assert_eq!(
+            strategy("https://other.internal/"),
+            Some(service_account())
+        );
+        assert_eq!(
+            DownstreamAuth::new().strategy_for(&Uri::from_static("/")),
+            None
+        );
+    }
+
+    // returns the authorization header the downstream service receives.
+    async fn outgoing(
+        kc: std::sync::Arc<crate::ReCloak>,
+        strategy: DownstreamStrategy,
+        user: Option<RequestAuthorization>,
+    ) -> Option<String> {
+        let endpoint = service_fn(|req: Request<()>| async move {
+            let header = req
+                .headers()
+                .get(AUTHORIZATION)
+                .map(|value| value.to_str().unwrap().to_owned());
+
+            Ok::<_, BoxError>(Response::new(header))
+        });
+        let policy = DownstreamAuth::new().fallback(strategy);
+        let service = DownstreamAuthLayer::new(kc, policy).layer(endpoint);
+
+        let mut req = Request::builder()
+            .uri("https://billing.internal/invoices")
+            .header(AUTHORIZATION, "Bearer stale")
+            .body(())
+            .unwrap();
+        if let Some(user) = user {
+            req.extensions_mut().insert(user);
+        }
+
+        service.oneshot(req).await.unwrap().into_body()
+    }
+
+    #[tokio::test]
+    async fn applies_the_selected_strategy() {
+        let (builder, transport) = testing::builder(testing::config());
+        let kc = builder.build().await.unwrap();
+        transport.route(
+            TOKEN_PATH,
+            json!({ "access_token": "service-token", "expires_in": 300 }),
+        );
+
+        let token = testing::sign(&testing::claims(chrono::Utc::now()));
+        let mut headers = http::HeaderMap::new();
+        headers
+            .insert(AUTHORIZATION, format!("Bearer {token}").parse().unwrap());
+        let user = ServerAuthServiceLayer::new::<BoxError>(kc.clone())
+            .extract_and_validate(&headers)
+            .await
+            .unwrap();
+
+        let forwarded =
+            outgoing(kc.clone(), DownstreamStrategy::Forward, Some(user)).await;
+        assert_eq!(forwarded, Some(format!("Bearer {token}")));
+
+        let anonymous =
+            outgoing(kc.clone(), DownstreamStrategy::Anonymous, None).await;
+        assert_eq!(anonymous, None);
+
+        let service = outgoing(kc.clone(), service_account(), None).await;
+        assert_eq!(service.as_deref(), Some("Bearer service-token"));
+
+        // without a user to forward, the request proceeds untouched.
+        let unchanged = outgoing(kc, DownstreamStrategy::Forward, None).await;
+        assert_eq!(unchanged.as_deref(), Some("Bearer stale"));
+    }
+}
@@ -0,0 +1,163 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use http::Request;
+use tokio::sync::watch;
+use tower::{Layer, Service};
+
+use super::http::TokenHeader;
+use crate::telemetry::{self, MIDDLEWARE_TARGET};
+
+#[derive(Debug, Clone)]
+pub struct StreamAuthLayer {
+    kc: Arc<crate::ReCloak>,
+    min_validity: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct StreamAuthService<S> {
+    kc: Arc<crate::ReCloak>,
+    inner: S,
+    min_validity: Duration,
+}
+
+#[derive(Debug)]
+pub struct TokenRotation(watch::Receiver<Option<arcstr::ArcStr>>);
+
+impl StreamAuthLayer {
+    #[inline]
+    pub const fn new(kc: Arc<crate::ReCloak>) -> Self {
+        Self {
+            kc,
+            min_validity: Duration::ZERO,
+        }
+    }
+
+    #[inline]
+    pub const fn min_validity(mut self, min_validity: Duration) -> Self {
+        self.min_validity = min_validity;
+        self
+    }
+}
+
+impl<S> Layer<S> for StreamAuthLayer {
+    type Service = StreamAuthService<S>;
+
+    #[inline]
+    fn layer(&self, inner: S) -> Self::Service {
+        StreamAuthService {
+            kc: self.kc.clone(),
+            inner,
+            min_validity: self.min_validity,
+        }
+    }
+}
+
+impl<S, B> Service<Request<B>> for StreamAuthService<S>
+where
+    S: Service<Request<B>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    B: Send + 'static,
+{
+    type Error = S::Error;
+    type Response = S::Response;
+
+    type Future = Pin<
+        Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>,
+    >;
+
+    #[inline]
+    fn poll_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<B>) -> Self::Future {
+        let kc = self.kc.clone();
+        let min_validity = self.min_validity;
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        Box::pin(async move {
+            match kc.authenticate_valid_for(min_validity).await {
+                | Ok(token) => match TokenHeader::DEFAULT.value(&token) {
+                    | Some(value) => {
+                        req.headers_mut()
+                            .insert(http::header::AUTHORIZATION, value.into());
+                    }
+                    | None => {
+                        telemetry::trace_error!(
+                            target: MIDDLEWARE_TARGET,
+                            "token is not a valid header value, proceeding \
+                             without token"
+                        );
+                    }
+                },
+                | Err(err) => {
+                    telemetry::trace_error!(target: MIDDLEWARE_TARGET, error = %err, "failed to authenticate stream, proceeding without token");
+                }
+            }
+
+            inner.call(req).await
+        })
+    }
+}
+
+impl TokenRotation {
+    #[inline]
+    pub fn new(kc: &crate::ReCloak) -> Self {
+        Self(kc.token_updates())
+    }
+
+    #[inline]
+    pub fn mark_seen(&mut self) {
+        self.0.mark_unchanged();
+    }
+
+    #[inline]
+    pub async fn rotated(&mut self) -> bool {
+        self.0.changed().await.is_ok()
+    }
+}
+
+pub async fn renewing_stream<T, F, Fut, H>(
+    kc: &crate::ReCloak,
+    mut connect: F,
+    mut on_message: H,
+) -> Result<(), tonic::Status>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<tonic::Streaming<T>, tonic::Status>>,
+    H: FnMut(T),
+{
+    let mut rotation = TokenRotation::new(kc);
+
+    loop {
+        let mut stream = connect().await?;
+        rotation.mark_seen();
+
+        loop {
+            tokio::select! {
+                message = stream.message() => match message? {
+                    | Some(message) => on_message(message),
+                    | None => return Ok(()),
+                },
+                rotated = rotation.rotated() => {
+                    if !rotated {
+                        return Ok(());
+                    }
+
+                    telemetry::trace_debug!(target: MIDDLEWARE_TARGET, "access token rotated, re-establishing stream");
+                    break;
+                }
+            }
+        }
+    }
+}
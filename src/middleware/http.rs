@@ -1,18 +1,35 @@
 use std::{
+    any::Any,
+    collections::HashMap,
     future::Future,
     marker::PhantomData,
+    net::IpAddr,
     pin::Pin,
-    sync::Arc,
+    sync::{Arc, Mutex},
     task::{Context, Poll},
 };
 
 use bytes::{BufMut, BytesMut};
-use http::{header::AUTHORIZATION, HeaderValue, Request};
+use http::{
+    header::{ACCESS_CONTROL_REQUEST_METHOD, AUTHORIZATION, VARY},
+    HeaderMap,
+    HeaderName,
+    HeaderValue,
+    Method,
+    Request,
+    Response,
+    Uri,
+};
 use tower::{Layer, Service};
 
-const BEARER_TOKEN_PREFIX: &str = "Bearer ";
+use super::proxy::{self, TrustedProxies};
+use crate::telemetry::{self, MIDDLEWARE_TARGET};
+pub use crate::TokenRequirements;
+
+const DEFAULT_SCHEME: arcstr::ArcStr = arcstr::literal!("Bearer");
 
 type BoxError = Box<dyn std::error::Error + Send + Sync>;
+type RejectionFn<B> = dyn Fn(ServerAuthError) -> Response<B> + Send + Sync;
 
 pub type ServerAuthService<S, E = BoxError> = AuthService<S, ServerMode, E>;
 pub type ClientAuthService<S, E = BoxError> = AuthService<S, ClientMode, E>;
@@ -23,12 +40,32 @@ pub type ClientAuthServiceLayer<E = BoxError> = AuthServiceLayer<ClientMode, E>;
 #[derive(Debug, Clone)]
 pub struct RequestAuthorization {
     claims: crate::Claims,
-    auth_header: HeaderValue,
+    auth_header: Option<ValidatedHeaderValue>,
+    decisions: Arc<DecisionCache>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ValidatedHeaderValue(HeaderValue);
+
+pub trait AuthorizationTarget: Sized {
+    fn with_authorization(self, value: &ValidatedHeaderValue) -> Self;
 }
 
 #[derive(Debug, Clone)]
 #[doc(hidden)]
-pub struct ServerMode;
+pub struct ServerMode {
+    requirement: Option<Arc<crate::Requirement>>,
+    policy: Option<Arc<dyn crate::policy::PolicyBackend>>,
+    validators: crate::validator::ClaimsValidators,
+    pub(super) proxies: Option<Arc<TrustedProxies>>,
+    hop_tokens: bool,
+    pub(super) strip_header: bool,
+    allow_preflight: bool,
+    memoize: bool,
+    single_use: bool,
+    pub(super) vary: bool,
+    pub(super) rejection: Option<RejectionHandler>,
+}
 
 #[derive(Debug, Clone)]
 #[doc(hidden)]
@@ -38,20 +75,59 @@ pub struct ClientMode;
 pub struct AuthService<S, M, E> {
     kc: Arc<crate::ReCloak>,
     inner: S,
-    _marker: PhantomData<(M, E)>,
+    header: TokenHeader,
+    mode: M,
+    _marker: PhantomData<E>,
 }
 
 #[derive(Debug, Clone)]
 pub struct AuthServiceLayer<M, E> {
-    kc: Arc<crate::ReCloak>,
-    _marker: PhantomData<(M, E)>,
+    pub(super) kc: Arc<crate::ReCloak>,
+    pub(super) header: TokenHeader,
+    pub(super) mode: M,
+    _marker: PhantomData<E>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct TokenHeader {
+    pub(crate) name: HeaderName,
+    scheme: Option<arcstr::ArcStr>,
 }
 
-#[derive(Debug, Clone, Copy)]
-enum ServerAuthError {
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionMemo(Arc<ClaimsMemo>);
+
+#[derive(Debug, Clone)]
+pub struct PerConnectionMemo<M> {
+    inner: M,
+}
+
+#[derive(Debug, Clone)]
+pub struct MemoizedConnection<S> {
+    inner: S,
+    memo: ConnectionMemo,
+}
+
+#[derive(Debug, Default)]
+pub(super) struct ClaimsMemo(Mutex<Option<MemoEntry>>);
+
+// the decoder generation the claims were decoded under.
+type MemoEntry = (HeaderValue, u64, crate::Claims);
+
+#[derive(Debug, Default)]
+struct DecisionCache(Mutex<HashMap<crate::Requirement, crate::Decision>>);
+
+#[derive(Clone)]
+pub(super) struct RejectionHandler(Arc<dyn Any + Send + Sync>);
+
+#[derive(Debug)]
+pub enum ServerAuthError {
     MissingHeader,
-    InvalidHeader,
-    InvalidToken,
+    InvalidHeader(http::header::ToStrError),
+    InvalidScheme,
+    InvalidToken(crate::Error),
+    PolicyFailed(crate::Error),
+    Denied(crate::DenyReason),
 }
 
 impl ServerAuthServiceLayer {
@@ -59,6 +135,20 @@ impl ServerAuthServiceLayer {
     pub const fn new<E>(kc: Arc<crate::ReCloak>) -> ServerAuthServiceLayer<E> {
         AuthServiceLayer {
             kc,
+            header: TokenHeader::DEFAULT,
+            mode: ServerMode {
+                requirement: None,
+                policy: None,
+                validators: crate::validator::ClaimsValidators::new(),
+                proxies: None,
+                hop_tokens: false,
+                strip_header: false,
+                allow_preflight: false,
+                memoize: false,
+                single_use: false,
+                vary: false,
+                rejection: None,
+            },
             _marker: PhantomData,
         }
     }
@@ -71,23 +161,166 @@ impl ServerAuthServiceLayer {
     }
 }
 
+impl<E> ServerAuthServiceLayer<E> {
+    pub fn require(mut self, requirement: crate::Requirement) -> Self {
+        let requirement = match self.mode.requirement.take() {
+            | Some(current) => Arc::unwrap_or_clone(current).and(requirement),
+            | None => requirement,
+        };
+
+        self.mode.requirement = Some(Arc::new(requirement));
+        self
+    }
+
+    #[inline]
+    pub fn policy(
+        mut self,
+        backend: impl crate::policy::PolicyBackend,
+    ) -> Self {
+        self.mode.policy = Some(Arc::new(backend));
+        self
+    }
+
+    #[inline]
+    pub fn validate_claims(
+        mut self,
+        validator: impl crate::ClaimsValidator,
+    ) -> Self {
+        self.mode.validators.push(validator);
+        self
+    }
+
+    #[inline]
+    pub fn require_acr(self, level: impl Into<arcstr::ArcStr>) -> Self {
+        self.require(crate::Requirement::acr(level))
+    }
+
+    #[inline]
+    pub fn require_organization(
+        self,
+        alias: impl Into<arcstr::ArcStr>,
+    ) -> Self {
+        self.require(crate::Requirement::organization(alias))
+    }
+
+    #[inline]
+    pub fn trust_proxies(mut self, proxies: TrustedProxies) -> Self {
+        self.mode.proxies = Some(Arc::new(proxies));
+        self
+    }
+
+    #[inline]
+    pub const fn accept_hop_tokens(mut self, accept: bool) -> Self {
+        self.mode.hop_tokens = accept;
+        self
+    }
+
+    #[inline]
+    pub const fn strip_header(mut self, strip: bool) -> Self {
+        self.mode.strip_header = strip;
+        self
+    }
+
+    #[inline]
+    pub const fn allow_preflight(mut self, allow: bool) -> Self {
+        self.mode.allow_preflight = allow;
+        self
+    }
+
+    #[inline]
+    pub const fn memoize_claims(mut self, memoize: bool) -> Self {
+        self.mode.memoize = memoize;
+        self
+    }
+
+    #[inline]
+    pub const fn single_use(mut self, single_use: bool) -> Self {
+        self.mode.single_use = single_use;
+        self
+    }
+
+    #[inline]
+    pub const fn vary_authorization(mut self, vary: bool) -> Self {
+        self.mode.vary = vary;
+        self
+    }
+
+    #[inline]
+    pub async fn extract_and_validate(
+        &self,
+        headers: &HeaderMap,
+    ) -> Result<RequestAuthorization, ServerAuthError> {
+        self.mode
+            .extract_and_validate(&self.kc, &self.header, headers, None, None)
+            .await
+    }
+
+    #[inline]
+    pub async fn authorize_request(
+        &self,
+        method: &Method,
+        uri: &Uri,
+        headers: &HeaderMap,
+    ) -> Result<RequestAuthorization, ServerAuthError> {
+        self.mode
+            .authorize(&self.kc, &self.header, method, uri, headers, None, None)
+            .await
+    }
+
+    #[inline]
+    pub fn on_rejection<B, F>(mut self, handler: F) -> Self
+    where
+        B: 'static,
+        F: Fn(ServerAuthError) -> Response<B> + Send + Sync + 'static,
+    {
+        self.mode.rejection = Some(RejectionHandler::new(handler));
+        self
+    }
+}
+
 impl<E> ClientAuthServiceLayer<E> {
     #[inline]
     pub const fn new(kc: Arc<crate::ReCloak>) -> Self {
         AuthServiceLayer {
             kc,
+            header: TokenHeader::DEFAULT,
+            mode: ClientMode,
             _marker: PhantomData,
         }
     }
 }
 
-impl<S, E, B> Service<Request<B>> for ServerAuthService<S, E>
+impl<M, E> AuthServiceLayer<M, E> {
+    #[inline]
+    pub fn header(mut self, name: HeaderName) -> Self {
+        self.header.name = name;
+        self
+    }
+
+    #[inline]
+    pub fn scheme(mut self, scheme: impl Into<arcstr::ArcStr>) -> Self {
+        self.header.scheme = Some(scheme.into());
+        self
+    }
+
+    #[inline]
+    pub fn without_scheme(mut self) -> Self {
+        self.header.scheme = None;
+        self
+    }
+}
+
+impl<S, E, B, ResBody> Service<Request<B>> for ServerAuthService<S, E>
 where
-    S: Service<Request<B>> + Clone + Send + 'static,
+    S: Service<Request<B>, Response = Response<ResBody>>
+        + Clone
+        + Send
+        + 'static,
     S::Error: From<E>,
     S::Future: Send + 'static,
     B: Send + 'static,
     E: From<ServerAuthError>,
+    ResBody: 'static,
 {
     type Error = S::Error;
     type Response = S::Response;
@@ -105,46 +338,68 @@ where
     }
 
     fn call(&mut self, mut req: Request<B>) -> Self::Future {
-        let Self { kc, inner, .. } = self.clone();
-        let mut inner = std::mem::replace(&mut self.inner, inner);
+        let kc = self.kc.clone();
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let header = self.header.clone();
+        let mode = self.mode.clone();
+        let memo = req
+            .extensions()
+            .get::<ConnectionMemo>()
+            .filter(|_| mode.memoize)
+            .cloned();
 
         Box::pin(async move {
-            if req.extensions().get::<crate::Claims>().is_some() {
+            if req.extensions().get::<crate::Claims>().is_some()
+                || mode.is_preflight(req.method(), req.headers())
+            {
                 return inner.call(req).await;
             }
 
-            let auth_header = req
-                .headers()
-                .get(AUTHORIZATION)
-                .ok_or(ServerAuthError::MissingHeader.into())?
-                .clone();
-
-            let header_str = auth_header
-                .to_str()
-                .map_err(|err| {
-                    tracing::error!(error = %err, "failed to parse authorization header");
-
-                    ServerAuthError::InvalidHeader.into()
-                })?;
+            let peer = mode
+                .proxies
+                .as_ref()
+                .and_then(|_| proxy::peer_addr(req.extensions()));
+            let auth = mode
+                .authorize(
+                    &kc,
+                    &header,
+                    req.method(),
+                    req.uri(),
+                    req.headers(),
+                    peer,
+                    memo.as_ref().map(|memo| &*memo.0),
+                )
+                .await;
 
-            let bearer = header_str
-                .strip_prefix(BEARER_TOKEN_PREFIX)
-                .ok_or(ServerAuthError::InvalidToken.into())?;
+            let auth = match auth {
+                | Ok(auth) => auth,
+                | Err(err) => {
+                    return match mode.rejection {
+                        | Some(ref handler) => handler.handle(err),
+                        | None => Err(err),
+                    }
+                    .map(|mut res| {
+                        mode.append_vary(&header, res.headers_mut());
+                        res
+                    })
+                    .map_err(|err| S::Error::from(E::from(err)));
+                }
+            };
 
-            let token = kc
-                .decode_token(bearer)
-                .map_err(|err| {
-                    tracing::error!(error = %err, "failed to parse authorization header");
+            if mode.strip_header {
+                req.headers_mut().remove(&header.name);
+            }
 
-                    ServerAuthError::InvalidToken.into()
-                })?;
+            if let Some(principal) = kc.request_principal(auth.claims()) {
+                req.extensions_mut().insert(principal);
+            }
+            req.extensions_mut().insert(auth);
 
-            req.extensions_mut().insert(RequestAuthorization {
-                claims: token.claims,
-                auth_header,
-            });
+            let mut res = inner.call(req).await?;
+            mode.append_vary(&header, res.headers_mut());
 
-            inner.call(req).await
+            Ok(res)
         })
     }
 }
@@ -168,37 +423,40 @@ where
         &mut self,
         cx: &mut Context<'_>,
     ) -> Poll<Result<(), Self::Error>> {
-        self.inner.poll_ready(cx).map_err(From::from)
+        self.inner.poll_ready(cx)
     }
 
     fn call(&mut self, mut req: Request<B>) -> Self::Future {
-        let Self { kc, inner, .. } = self.clone();
-        let mut inner = std::mem::replace(&mut self.inner, inner);
+        let kc = self.kc.clone();
+        let header = self.header.clone();
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
 
         Box::pin(async move {
             if req.extensions().get::<crate::Claims>().is_some() {
                 return inner.call(req).await;
             }
 
-            match kc.authenticate().await {
-                | Ok(token) => {
-                    let mut buf = BytesMut::with_capacity(
-                        BEARER_TOKEN_PREFIX.len() + token.as_bytes().len(),
-                    );
-
-                    buf.put(BEARER_TOKEN_PREFIX.as_bytes());
-                    buf.put_slice(token.as_bytes());
-
-                    // Safety: we know the buffer is valid utf-8, since we
-                    // the token always comes from a valid source.
-                    let value = unsafe {
-                        HeaderValue::from_maybe_shared_unchecked(buf.freeze())
-                    };
+            let token = match req.extensions().get::<TokenRequirements>() {
+                | Some(requirements) => kc.authenticate_for(requirements).await,
+                | None => kc.authenticate().await,
+            };
 
-                    req.headers_mut().insert(AUTHORIZATION, value);
-                }
+            match token {
+                | Ok(token) => match header.value(&token) {
+                    | Some(value) => {
+                        req.headers_mut().insert(header.name, value.into());
+                    }
+                    | None => {
+                        telemetry::trace_error!(
+                            target: MIDDLEWARE_TARGET,
+                            "token is not a valid header value, proceeding \
+                             without token"
+                        );
+                    }
+                },
                 | Err(err) => {
-                    tracing::error!(error = %err, "failed to authenticate, proceeding without token");
+                    telemetry::trace_error!(target: MIDDLEWARE_TARGET, error = %err, "failed to authenticate, proceeding without token");
 
                     return inner.call(req).await;
                 }
@@ -209,7 +467,10 @@ where
     }
 }
 
-impl<S, M, E> Layer<S> for AuthServiceLayer<M, E> {
+impl<S, M, E> Layer<S> for AuthServiceLayer<M, E>
+where
+    M: Clone,
+{
     type Service = AuthService<S, M, E>;
 
     #[inline]
@@ -217,6 +478,8 @@ impl<S, M, E> Layer<S> for AuthServiceLayer<M, E> {
         AuthService {
             kc: self.kc.clone(),
             inner,
+            header: self.header.clone(),
+            mode: self.mode.clone(),
             _marker: PhantomData,
         }
     }
@@ -225,17 +488,354 @@ impl<S, M, E> Layer<S> for AuthServiceLayer<M, E> {
 impl<S, M, E> Clone for AuthService<S, M, E>
 where
     S: Clone,
+    M: Clone,
 {
     #[inline]
     fn clone(&self) -> Self {
         Self {
             kc: self.kc.clone(),
             inner: self.inner.clone(),
+            header: self.header.clone(),
+            mode: self.mode.clone(),
             _marker: PhantomData,
         }
     }
 }
 
+impl<M> PerConnectionMemo<M> {
+    #[inline]
+    pub const fn new(make_service: M) -> Self {
+        Self {
+            inner: make_service,
+        }
+    }
+}
+
+impl<M, T> Service<T> for PerConnectionMemo<M>
+where
+    M: Service<T>,
+    M::Future: Send + 'static,
+{
+    type Error = M::Error;
+    type Response = MemoizedConnection<M::Response>;
+
+    type Future = Pin<
+        Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>,
+    >;
+
+    #[inline]
+    fn poll_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, target: T) -> Self::Future {
+        let connection = self.inner.call(target);
+
+        Box::pin(async move {
+            Ok(MemoizedConnection {
+                inner: connection.await?,
+                memo: ConnectionMemo::default(),
+            })
+        })
+    }
+}
+
+impl<S, B> Service<Request<B>> for MemoizedConnection<S>
+where
+    S: Service<Request<B>>,
+{
+    type Error = S::Error;
+    type Future = S::Future;
+    type Response = S::Response;
+
+    #[inline]
+    fn poll_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    #[inline]
+    fn call(&mut self, mut req: Request<B>) -> Self::Future {
+        req.extensions_mut().insert(self.memo.clone());
+        self.inner.call(req)
+    }
+}
+
+impl ServerMode {
+    #[inline]
+    pub(super) fn is_preflight(
+        &self,
+        method: &Method,
+        headers: &HeaderMap,
+    ) -> bool {
+        self.allow_preflight
+            && method == Method::OPTIONS
+            && headers.contains_key(ACCESS_CONTROL_REQUEST_METHOD)
+    }
+
+    #[inline]
+    pub(super) fn append_vary(
+        &self,
+        header: &TokenHeader,
+        headers: &mut HeaderMap,
+    ) {
+        if self.vary {
+            headers.append(VARY, header.name.clone().into());
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(super) async fn authorize(
+        &self,
+        kc: &crate::ReCloak,
+        header: &TokenHeader,
+        method: &Method,
+        uri: &Uri,
+        headers: &HeaderMap,
+        peer: Option<IpAddr>,
+        memo: Option<&ClaimsMemo>,
+    ) -> Result<RequestAuthorization, ServerAuthError> {
+        let auth = self
+            .extract_and_validate(kc, header, headers, peer, memo)
+            .await?;
+
+        if let Some(ref policy) = self.policy {
+            let request =
+                crate::policy::RequestDescriptor::from_http(method, uri);
+
+            policy
+                .evaluate(&auth.claims, &request)
+                .await
+                .map_err(|err| {
+                    telemetry::trace_error!(target: MIDDLEWARE_TARGET, error = %err, "failed to evaluate policy");
+
+                    ServerAuthError::PolicyFailed(err)
+                })?
+                .into_result()
+                .map_err(|reason| denied(kc, &auth.claims, reason))?;
+        }
+
+        Ok(auth)
+    }
+
+    async fn extract_and_validate(
+        &self,
+        kc: &crate::ReCloak,
+        header: &TokenHeader,
+        headers: &HeaderMap,
+        peer: Option<IpAddr>,
+        memo: Option<&ClaimsMemo>,
+    ) -> Result<RequestAuthorization, ServerAuthError> {
+        let proxied = self
+            .proxies
+            .as_ref()
+            .and_then(|proxies| proxies.identity(kc, peer, headers));
+        if let Some(identity) = proxied {
+            return self.check(kc, identity.claims, identity.auth_header).await;
+        }
+
+        let auth_header = headers
+            .get(&header.name)
+            .ok_or(ServerAuthError::MissingHeader)?
+            .clone();
+
+        let auth_header =
+            ValidatedHeaderValue::try_from(auth_header).map_err(|err| {
+                telemetry::trace_error!(target: MIDDLEWARE_TARGET, error = %err, "failed to parse authorization header");
+
+                ServerAuthError::InvalidHeader(err)
+            })?;
+
+        let bearer = header
+            .extract(auth_header.as_str())
+            .ok_or(ServerAuthError::InvalidScheme)?;
+
+        if self.hop_tokens && kc.is_hop_token(bearer) {
+            let claims = kc.validate_hop_token(bearer).await.map_err(|err| {
+                telemetry::trace_error!(target: MIDDLEWARE_TARGET, error = %err, "failed to verify hop token");
+
+                ServerAuthError::InvalidToken(err)
+            })?;
+
+            return self.check(kc, claims, Some(auth_header)).await;
+        }
+
+        let now = kc.clock.now();
+        let generation = kc.decoder.generation();
+        let claims = match memo
+            .and_then(|memo| memo.get(&auth_header, generation, now))
+        {
+            | Some(claims) => {
+                // only decoding is memoized, revocation is checked every time.
+                kc.revalidate_token(bearer, &claims).await.map_err(|err| {
+                    telemetry::trace_error!(target: MIDDLEWARE_TARGET, error = %err, "failed to revalidate memoized token");
+
+                    ServerAuthError::InvalidToken(err)
+                })?;
+
+                claims
+            }
+            | None => {
+                let token = kc.validate_token(bearer).await.map_err(|err| {
+                    telemetry::trace_error!(target: MIDDLEWARE_TARGET, error = %err, "failed to parse authorization header");
+
+                    ServerAuthError::InvalidToken(err)
+                })?;
+
+                let claims =
+                    kc.enrich_claims(bearer, token.claims).await.map_err(|err| {
+                        telemetry::trace_error!(target: MIDDLEWARE_TARGET, error = %err, "failed to enrich token claims");
+
+                        ServerAuthError::InvalidToken(err)
+                    })?;
+
+                if let Some(memo) = memo {
+                    memo.set(&auth_header, generation, &claims);
+                }
+
+                claims
+            }
+        };
+
+        self.check(kc, claims, Some(auth_header)).await
+    }
+
+    async fn check(
+        &self,
+        kc: &crate::ReCloak,
+        claims: crate::Claims,
+        auth_header: Option<ValidatedHeaderValue>,
+    ) -> Result<RequestAuthorization, ServerAuthError> {
+        self.validators
+            .check(&claims, kc.logging())
+            .map_err(ServerAuthError::InvalidToken)?;
+
+        if self.single_use {
+            kc.consume_claims(&claims)
+                .await
+                .map_err(ServerAuthError::InvalidToken)?;
+        }
+
+        let auth = RequestAuthorization {
+            claims,
+            auth_header,
+            decisions: Arc::default(),
+        };
+
+        if let Some(ref requirement) = self.requirement {
+            auth.authorize(requirement)
+                .into_result()
+                .map_err(|reason| denied(kc, &auth.claims, reason))?;
+        }
+
+        Ok(auth)
+    }
+}
+
+impl ClaimsMemo {
+    fn get(
+        &self,
+        header: &ValidatedHeaderValue,
+        generation: u64,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Option<crate::Claims> {
+        let memo = self.0.lock().ok()?;
+
+        memo.as_ref()
+            .filter(|(value, memo_generation, claims)| {
+                value == header.as_header_value()
+                    && *memo_generation == generation
+                    && claims.expires_at > now
+            })
+            .map(|(_, _, claims)| claims.clone())
+    }
+
+    fn set(
+        &self,
+        header: &ValidatedHeaderValue,
+        generation: u64,
+        claims: &crate::Claims,
+    ) {
+        if let Ok(mut memo) = self.0.lock() {
+            let value = header.as_header_value().clone();
+            *memo = Some((value, generation, claims.clone()));
+        }
+    }
+}
+
+impl DecisionCache {
+    fn get(&self, requirement: &crate::Requirement) -> Option<crate::Decision> {
+        self.0.lock().ok()?.get(requirement).cloned()
+    }
+
+    fn insert(
+        &self,
+        requirement: &crate::Requirement,
+        decision: &crate::Decision,
+    ) {
+        if let Ok(mut decisions) = self.0.lock() {
+            decisions.insert(requirement.clone(), decision.clone());
+        }
+    }
+
+    fn snapshot(&self) -> Vec<(crate::Requirement, crate::Decision)> {
+        self.0
+            .lock()
+            .map(|decisions| {
+                decisions
+                    .iter()
+                    .map(|(requirement, decision)| {
+                        (requirement.clone(), decision.clone())
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl TokenHeader {
+    pub(crate) const DEFAULT: Self = Self {
+        name: AUTHORIZATION,
+        scheme: Some(DEFAULT_SCHEME),
+    };
+
+    pub(crate) fn extract<'a>(&self, value: &'a str) -> Option<&'a str> {
+        let token = match self.scheme {
+            | Some(ref scheme) => {
+                let (prefix, token) = value.split_once(' ')?;
+                if !prefix.eq_ignore_ascii_case(scheme) {
+                    return None;
+                }
+
+                token.trim_start()
+            }
+            | None => value.trim(),
+        };
+
+        (!token.is_empty()).then_some(token)
+    }
+
+    pub(super) fn value(&self, token: &str) -> Option<ValidatedHeaderValue> {
+        let scheme = self.scheme.as_deref().unwrap_or_default();
+        let mut buf = BytesMut::with_capacity(scheme.len() + 1 + token.len());
+
+        if !scheme.is_empty() {
+            buf.put(scheme.as_bytes());
+            buf.put_u8(b' ');
+        }
+        buf.put_slice(token.as_bytes());
+
+        HeaderValue::from_maybe_shared(buf.freeze())
+            .ok()
+            .and_then(|value| ValidatedHeaderValue::try_from(value).ok())
+    }
+}
+
 impl std::fmt::Display for ServerAuthError {
     #[inline]
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -243,29 +843,527 @@ impl std::fmt::Display for ServerAuthError {
 
         match self {
             | MissingHeader => write!(f, "missing authorization header"),
-            | InvalidHeader => write!(f, "invalid authorization header"),
-            | InvalidToken => write!(f, "invalid token"),
+            | InvalidHeader(_) => write!(f, "invalid authorization header"),
+            | InvalidScheme => write!(f, "invalid authorization scheme"),
+            | InvalidToken(_) => write!(f, "invalid token"),
+            | PolicyFailed(_) => write!(f, "policy evaluation failed"),
+            | Denied(reason) => write!(f, "{reason}"),
+        }
+    }
+}
+
+impl std::error::Error for ServerAuthError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            | ServerAuthError::InvalidHeader(err) => Some(err),
+            | ServerAuthError::InvalidToken(err)
+            | ServerAuthError::PolicyFailed(err) => Some(err),
+            | _ => None,
         }
     }
 }
 
-impl std::error::Error for ServerAuthError {}
+impl ServerAuthError {
+    #[inline]
+    pub const fn is_forbidden(&self) -> bool {
+        match self {
+            | ServerAuthError::PolicyFailed(_) => true,
+            | ServerAuthError::Denied(reason) => !reason.is_authentication(),
+            | _ => false,
+        }
+    }
+
+    #[inline]
+    pub const fn status_code(&self) -> http::StatusCode {
+        if self.is_forbidden() {
+            http::StatusCode::FORBIDDEN
+        } else {
+            http::StatusCode::UNAUTHORIZED
+        }
+    }
+}
 
 impl From<ServerAuthError> for tonic::Status {
     #[inline]
     fn from(value: ServerAuthError) -> Self {
-        tonic::Status::unauthenticated(value.to_string())
+        if value.is_forbidden() {
+            tonic::Status::permission_denied(value.to_string())
+        } else {
+            tonic::Status::unauthenticated(value.to_string())
+        }
+    }
+}
+
+impl RejectionHandler {
+    #[inline]
+    fn new<B, F>(handler: F) -> Self
+    where
+        B: 'static,
+        F: Fn(ServerAuthError) -> Response<B> + Send + Sync + 'static,
+    {
+        Self(Arc::new(Arc::new(handler) as Arc<RejectionFn<B>>))
+    }
+
+    pub(super) fn handle<B>(
+        &self,
+        err: ServerAuthError,
+    ) -> Result<Response<B>, ServerAuthError>
+    where
+        B: 'static,
+    {
+        match self.0.downcast_ref::<Arc<RejectionFn<B>>>() {
+            | Some(handler) => Ok(handler(err)),
+            | None => {
+                telemetry::trace_warn!(
+                    target: MIDDLEWARE_TARGET,
+                    "rejection handler body type does not match the service \
+                     response"
+                );
+
+                Err(err)
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for RejectionHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RejectionHandler")
     }
 }
 
 impl RequestAuthorization {
+    #[inline]
+    pub(super) fn new(
+        claims: crate::Claims,
+        auth_header: ValidatedHeaderValue,
+    ) -> Self {
+        Self {
+            claims,
+            auth_header: Some(auth_header),
+            decisions: Arc::default(),
+        }
+    }
+
     #[inline]
     pub const fn claims(&self) -> &crate::Claims {
         &self.claims
     }
 
+    pub fn authorize(
+        &self,
+        requirement: &crate::Requirement,
+    ) -> crate::Decision {
+        if let Some(decision) = self.decisions.get(requirement) {
+            return decision;
+        }
+
+        let decision = crate::Authorizer.decide(&self.claims, requirement);
+        telemetry::trace_debug!(
+            target: MIDDLEWARE_TARGET,
+            ?requirement,
+            allowed = decision.is_allowed(),
+            "authorization decided"
+        );
+        self.decisions.insert(requirement, &decision);
+
+        decision
+    }
+
+    #[inline]
+    pub fn decisions(&self) -> Vec<(crate::Requirement, crate::Decision)> {
+        self.decisions.snapshot()
+    }
+
     #[inline]
-    pub fn authorization_header(&self) -> &str {
-        unsafe { std::str::from_utf8_unchecked(self.auth_header.as_bytes()) }
+    pub fn authorization_header(&self) -> Option<&str> {
+        self.auth_header.as_ref().map(ValidatedHeaderValue::as_str)
+    }
+
+    #[inline]
+    pub fn bearer_token(&self) -> Option<&str> {
+        let header = self.authorization_header()?;
+
+        header.split_whitespace().next_back().or(Some(header))
+    }
+
+    #[inline]
+    pub fn apply_to<T: AuthorizationTarget>(&self, target: T) -> T {
+        match self.auth_header {
+            | Some(ref value) => target.with_authorization(value),
+            | None => target,
+        }
+    }
+}
+
+impl AuthorizationTarget for reqwest::RequestBuilder {
+    #[inline]
+    fn with_authorization(self, value: &ValidatedHeaderValue) -> Self {
+        self.header(AUTHORIZATION, value.0.clone())
+    }
+}
+
+impl AuthorizationTarget for &mut HeaderMap {
+    #[inline]
+    fn with_authorization(self, value: &ValidatedHeaderValue) -> Self {
+        self.insert(AUTHORIZATION, value.0.clone());
+        self
+    }
+}
+
+impl AuthorizationTarget for &mut tonic::metadata::MetadataMap {
+    fn with_authorization(self, value: &ValidatedHeaderValue) -> Self {
+        match tonic::metadata::MetadataValue::try_from(value.as_str()) {
+            | Ok(value) => {
+                self.insert(AUTHORIZATION.as_str(), value);
+            }
+            | Err(err) => {
+                telemetry::trace_error!(target: MIDDLEWARE_TARGET, error = %err, "authorization header is not valid metadata, not forwarding");
+            }
+        }
+
+        self
+    }
+}
+
+impl<T> AuthorizationTarget for &mut tonic::Request<T> {
+    #[inline]
+    fn with_authorization(self, value: &ValidatedHeaderValue) -> Self {
+        self.metadata_mut().with_authorization(value);
+        self
+    }
+}
+
+impl ValidatedHeaderValue {
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        // checked to be visible ascii on construction
+        self.0.to_str().unwrap_or_default()
+    }
+
+    #[inline]
+    pub const fn as_header_value(&self) -> &HeaderValue {
+        &self.0
+    }
+}
+
+impl TryFrom<HeaderValue> for ValidatedHeaderValue {
+    type Error = http::header::ToStrError;
+
+    #[inline]
+    fn try_from(value: HeaderValue) -> Result<Self, Self::Error> {
+        value.to_str()?;
+
+        Ok(Self(value))
+    }
+}
+
+impl From<ValidatedHeaderValue> for HeaderValue {
+    #[inline]
+    fn from(value: ValidatedHeaderValue) -> Self {
+        value.0
+    }
+}
+
+fn denied(
+    kc: &crate::ReCloak,
+    claims: &crate::Claims,
+    reason: crate::DenyReason,
+) -> ServerAuthError {
+    let logging = kc.logging();
+    telemetry::trace_warn!(
+        target: MIDDLEWARE_TARGET,
+        subject = %logging.subject(claims),
+        username = %logging.username(claims),
+        %reason,
+        "request denied"
+    );
+
+    ServerAuthError::Denied(reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use tower::{service_fn, ServiceExt};
+
+    use super::*;
+    use crate::{jti::MemoryJtiStore, testing};
+
+    type Connection = MemoizedConnection<ServerAuthService<Endpoint>>;
+    type Endpoint = tower::util::ServiceFn<fn(Request<()>) -> Ready>;
+    type Ready = std::future::Ready<Result<Response<()>, BoxError>>;
+
+    async fn recloak() -> Arc<crate::ReCloak> {
+        let (builder, _) = testing::builder(testing::config());
+
+        builder
+            .jti_store(MemoryJtiStore::default())
+            .build()
+            .await
+            .unwrap()
+    }
+
+    fn endpoint(_: Request<()>) -> Ready {
+        std::future::ready(Ok(Response::new(())))
+    }
+
+    async fn connect(layer: &ServerAuthServiceLayer) -> Connection {
+        let make = service_fn(|()| {
+            let endpoint: Endpoint = service_fn(endpoint);
+
+            std::future::ready(Ok::<_, BoxError>(layer.layer(endpoint)))
+        });
+
+        PerConnectionMemo::new(make).oneshot(()).await.unwrap()
+    }
+
+    fn request(token: &str) -> Request<()> {
+        Request::builder()
+            .header(AUTHORIZATION, format!("Bearer {token}"))
+            .body(())
+            .unwrap()
+    }
+
+    // mirrors hyper, which clones the connection service for each request.
+    async fn send(
+        conn: &Connection,
+        req: Request<()>,
+    ) -> Result<Response<()>, BoxError> {
+        conn.clone().oneshot(req).await
+    }
+
+    fn memo_entry(conn: &Connection) -> Option<MemoEntry> {
+        let memo = &conn.memo.0;
+
+        memo.0.lock().unwrap().clone()
+    }
+
+    fn memoized(conn: &Connection) -> Option<crate::Claims> {
+        memo_entry(conn).map(|(_, _, claims)| claims)
+    }
+
+    #[tokio::test]
+    async fn memo_is_shared_by_requests_on_one_connection() {
+        let kc = recloak().await;
+        let layer = ServerAuthServiceLayer::new(kc).memoize_claims(true);
+        let token = testing::sign(&testing::claims(chrono::Utc::now()));
+
+        let conn = connect(&layer).await;
+        send(&conn, request(&token)).await.unwrap();
+        send(&conn, request(&token)).await.unwrap();
+
+        assert!(memoized(&conn).is_some());
+        assert!(memoized(&connect(&layer).await).is_none());
+    }
+
+    #[tokio::test]
+    async fn memo_hit_still_checks_revocation() {
+        let kc = recloak().await;
+        let layer =
+            ServerAuthServiceLayer::new(kc.clone()).memoize_claims(true);
+        let token = testing::sign(&testing::claims(chrono::Utc::now()));
+
+        let conn = connect(&layer).await;
+        send(&conn, request(&token)).await.unwrap();
+
+        kc.revoke_claims(&memoized(&conn).unwrap()).await.unwrap();
+
+        let err = send(&conn, request(&token)).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ServerAuthError>(),
+            Some(ServerAuthError::InvalidToken(crate::Error::RevokedToken))
+        ));
+    }
+
+    #[tokio::test]
+    async fn memo_is_dropped_when_keys_rotate() {
+        let (builder, transport) = testing::builder(testing::config());
+        let kc = builder.build().await.unwrap();
+        let layer =
+            ServerAuthServiceLayer::new(kc.clone()).memoize_claims(true);
+        let token = testing::sign(&testing::claims(chrono::Utc::now()));
+        let generation = |conn: &Connection| {
+            memo_entry(conn).map(|(_, generation, _)| generation)
+        };
+
+        let conn = connect(&layer).await;
+        send(&conn, request(&token)).await.unwrap();
+        assert_eq!(generation(&conn), Some(kc.decoder.generation()));
+
+        transport.route(
+            "/realms/test/protocol/openid-connect/certs",
+            serde_json::json!({
+                "keys": [{
+                    "kty": "OKP",
+                    "crv": "Ed25519",
+                    "kid": "rotated",
+                    "x": "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo",
+                }],
+            }),
+        );
+        assert!(kc.reload_jwks().await.unwrap());
+        assert_ne!(generation(&conn), Some(kc.decoder.generation()));
+
+        send(&conn, request(&token)).await.unwrap();
+        assert_eq!(generation(&conn), Some(kc.decoder.generation()));
+    }
+
+    #[test]
+    fn builds_only_valid_header_values() {
+        let bearer = TokenHeader::DEFAULT.value("abc").unwrap();
+        assert_eq!(bearer.as_str(), "Bearer abc");
+
+        let raw = TokenHeader {
+            name: AUTHORIZATION,
+            scheme: None,
+        };
+        assert_eq!(raw.value("abc").unwrap().as_str(), "abc");
+
+        assert!(TokenHeader::DEFAULT.value("abc\r\nx-injected: 1").is_none());
+        assert!(TokenHeader::DEFAULT.value("caf\u{e9}").is_none());
+
+        let opaque = HeaderValue::from_bytes(b"Bearer \xfe").unwrap();
+        assert!(ValidatedHeaderValue::try_from(opaque).is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_opaque_authorization_headers() {
+        let layer = ServerAuthServiceLayer::new(recloak().await);
+        let conn = connect(&layer).await;
+
+        let mut req = request("unused");
+        let opaque = HeaderValue::from_bytes(b"Bearer \xfe").unwrap();
+        req.headers_mut().insert(AUTHORIZATION, opaque);
+
+        let err = send(&conn, req).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ServerAuthError>(),
+            Some(ServerAuthError::InvalidHeader(_))
+        ));
+    }
+
+    const API_TOKEN: HeaderName = HeaderName::from_static("x-api-token");
+
+    // answers 200 if the endpoint still sees the token header, 204 otherwise.
+    async fn forwarded(
+        layer: &ServerAuthServiceLayer,
+        req: Request<()>,
+    ) -> Result<http::StatusCode, BoxError> {
+        let endpoint = service_fn(|req: Request<()>| async move {
+            let mut res = Response::new(());
+            if !req.headers().contains_key(API_TOKEN) {
+                *res.status_mut() = http::StatusCode::NO_CONTENT;
+            }
+
+            Ok::<_, BoxError>(res)
+        });
+
+        let res = layer.layer(endpoint).oneshot(req).await?;
+
+        Ok(res.status())
+    }
+
+    fn api_request(value: &str) -> Request<()> {
+        Request::builder()
+            .header(API_TOKEN, value)
+            .body(())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn reads_the_configured_header_and_scheme() {
+        let layer = ServerAuthServiceLayer::new(recloak().await)
+            .header(API_TOKEN)
+            .scheme("Token");
+        let token = testing::sign(&testing::claims(chrono::Utc::now()));
+
+        let status = forwarded(&layer, api_request(&format!("token {token}")))
+            .await
+            .unwrap();
+        assert_eq!(status, http::StatusCode::OK);
+
+        let err = forwarded(&layer, request(&token)).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ServerAuthError>(),
+            Some(ServerAuthError::MissingHeader)
+        ));
+
+        let err = forwarded(&layer, api_request(&format!("Bearer {token}")))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ServerAuthError>(),
+            Some(ServerAuthError::InvalidScheme)
+        ));
+    }
+
+    #[tokio::test]
+    async fn strips_the_token_header_without_a_scheme() {
+        let layer = ServerAuthServiceLayer::new(recloak().await)
+            .header(API_TOKEN)
+            .without_scheme()
+            .strip_header(true);
+        let token = testing::sign(&testing::claims(chrono::Utc::now()));
+
+        let status = forwarded(&layer, api_request(&token)).await.unwrap();
+        assert_eq!(status, http::StatusCode::NO_CONTENT);
+    }
+
+    #[derive(Clone)]
+    struct Busy;
+
+    impl Service<Request<()>> for Busy {
+        type Error = BoxError;
+        type Future = Ready;
+        type Response = Response<()>;
+
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            Poll::Pending
+        }
+
+        fn call(&mut self, req: Request<()>) -> Self::Future {
+            endpoint(req)
+        }
+    }
+
+    async fn is_ready<S>(svc: &mut S) -> bool
+    where
+        S: Service<Request<()>>,
+    {
+        std::future::poll_fn(|cx| Poll::Ready(svc.poll_ready(cx).is_ready()))
+            .await
+    }
+
+    #[tokio::test]
+    async fn propagates_inner_readiness() {
+        let kc = recloak().await;
+
+        let mut server =
+            ServerAuthServiceLayer::new::<BoxError>(kc.clone()).layer(Busy);
+        assert!(!is_ready(&mut server).await);
+
+        let mut client =
+            ClientAuthServiceLayer::<BoxError>::new(kc.clone()).layer(Busy);
+        assert!(!is_ready(&mut client).await);
+
+        let endpoint: Endpoint = service_fn(endpoint);
+        let mut server =
+            ServerAuthServiceLayer::new::<BoxError>(kc).layer(endpoint);
+        assert!(is_ready(&mut server).await);
+    }
+
+    #[tokio::test]
+    async fn memo_is_unused_unless_enabled() {
+        let kc = recloak().await;
+        let layer = ServerAuthServiceLayer::new(kc);
+        let token = testing::sign(&testing::claims(chrono::Utc::now()));
+
+        let conn = connect(&layer).await;
+        send(&conn, request(&token)).await.unwrap();
+
+        assert!(memoized(&conn).is_none());
     }
 }
@@ -1 +1,15 @@
+#![forbid(unsafe_code)]
+
+#[cfg(feature = "axum")]
+pub mod axum;
+pub mod downstream;
+pub mod grpc;
 pub mod http;
+#[cfg(feature = "poem")]
+pub mod poem;
+pub mod proxy;
+#[cfg(feature = "salvo")]
+pub mod salvo;
+pub mod tenant;
+pub mod webhook;
+pub mod websocket;
@@ -0,0 +1,118 @@
+use std::sync::Arc;
+
+use poem::{Endpoint, IntoResponse, Middleware, Request, Response};
+
+use super::http::{AuthServiceLayer, ServerMode, TokenHeader};
+
+#[derive(Debug)]
+pub struct PoemAuthEndpoint<Ep> {
+    kc: Arc<crate::ReCloak>,
+    inner: Ep,
+    header: TokenHeader,
+    mode: ServerMode,
+}
+
+impl<Ep, E> Middleware<Ep> for AuthServiceLayer<ServerMode, E>
+where
+    Ep: Endpoint,
+{
+    type Output = PoemAuthEndpoint<Ep>;
+
+    #[inline]
+    fn transform(&self, inner: Ep) -> Self::Output {
+        PoemAuthEndpoint {
+            kc: self.kc.clone(),
+            inner,
+            header: self.header.clone(),
+            mode: self.mode.clone(),
+        }
+    }
+}
+
+impl<Ep> Endpoint for PoemAuthEndpoint<Ep>
+where
+    Ep: Endpoint,
+{
+    type Output = Response;
+
+    async fn call(&self, mut req: Request) -> poem::Result<Self::Output> {
+        if self.mode.is_preflight(req.method(), req.headers()) {
+            return self.inner.call(req).await.map(IntoResponse::into_response);
+        }
+
+        let auth = self
+            .mode
+            .authorize(
+                &self.kc,
+                &self.header,
+                req.method(),
+                req.uri(),
+                req.headers(),
+                self.mode
+                    .proxies
+                    .as_ref()
+                    .and_then(|_| req.remote_addr().as_socket_addr())
+                    .map(std::net::SocketAddr::ip),
+                None,
+            )
+            .await;
+
+        let auth = match auth {
+            | Ok(auth) => auth,
+            | Err(err) => {
+                let err = match self.mode.rejection {
+                    | Some(ref handler) => {
+                        match handler.handle::<poem::Body>(err) {
+                            | Ok(res) => {
+                                let mut res = into_response(res);
+                                self.mode.append_vary(
+                                    &self.header,
+                                    res.headers_mut(),
+                                );
+
+                                return Ok(res);
+                            }
+                            | Err(err) => err,
+                        }
+                    }
+                    | None => err,
+                };
+
+                let mut res = Response::builder()
+                    .status(err.status_code())
+                    .body(err.to_string());
+                self.mode.append_vary(&self.header, res.headers_mut());
+
+                return Err(poem::Error::from_response(res));
+            }
+        };
+
+        if self.mode.strip_header {
+            req.headers_mut().remove(&self.header.name);
+        }
+
+        if let Some(principal) = self.kc.request_principal(auth.claims()) {
+            req.extensions_mut().insert(principal);
+        }
+        req.extensions_mut().insert(auth);
+
+        let mut res = self.inner.call(req).await?.into_response();
+        self.mode.append_vary(&self.header, res.headers_mut());
+
+        Ok(res)
+    }
+}
+
+fn into_response(res: http::Response<poem::Body>) -> Response {
+    let (parts, body) = res.into_parts();
+
+    Response::from_parts(
+        poem::ResponseParts {
+            status: parts.status,
+            version: parts.version,
+            headers: parts.headers,
+            extensions: parts.extensions,
+        },
+        body,
+    )
+}
@@ -0,0 +1,342 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    str::FromStr,
+};
+
+use http::{HeaderMap, HeaderName};
+
+use super::http::{TokenHeader, ValidatedHeaderValue};
+use crate::{
+    telemetry::{self, MIDDLEWARE_TARGET},
+    Error,
+    Result,
+};
+
+const IDENTITY_LIFETIME_SECS: i64 = 60;
+
+const USER_HEADERS: [HeaderName; 2] = [
+    HeaderName::from_static("x-forwarded-user"),
+    HeaderName::from_static("x-auth-request-user"),
+];
+const USERNAME_HEADERS: [HeaderName; 2] = [
+    HeaderName::from_static("x-forwarded-preferred-username"),
+    HeaderName::from_static("x-auth-request-preferred-username"),
+];
+const EMAIL_HEADERS: [HeaderName; 2] = [
+    HeaderName::from_static("x-forwarded-email"),
+    HeaderName::from_static("x-auth-request-email"),
+];
+const GROUPS_HEADERS: [HeaderName; 2] = [
+    HeaderName::from_static("x-forwarded-groups"),
+    HeaderName::from_static("x-auth-request-groups"),
+];
+const ACCESS_TOKEN_HEADERS: [HeaderName; 2] = [
+    HeaderName::from_static("x-forwarded-access-token"),
+    HeaderName::from_static("x-auth-request-access-token"),
+];
+
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    networks: Vec<ProxyNetwork>,
+    issuer: Option<arcstr::ArcStr>,
+    groups_as_roles: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProxyNetwork {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeerAddr(pub IpAddr);
+
+pub(super) struct ProxiedIdentity {
+    pub(super) claims: crate::Claims,
+    pub(super) auth_header: Option<ValidatedHeaderValue>,
+}
+
+impl TrustedProxies {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn loopback() -> Self {
+        Self::new()
+            .trust(IpAddr::V4(Ipv4Addr::LOCALHOST))
+            .trust(IpAddr::V6(Ipv6Addr::LOCALHOST))
+    }
+
+    #[inline]
+    pub fn trust(mut self, network: impl Into<ProxyNetwork>) -> Self {
+        self.networks.push(network.into());
+        self
+    }
+
+    pub fn trust_all<I>(mut self, networks: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<ProxyNetwork>,
+    {
+        self.networks.extend(networks.into_iter().map(Into::into));
+        self
+    }
+
+    #[inline]
+    pub fn issuer(mut self, issuer: impl Into<arcstr::ArcStr>) -> Self {
+        self.issuer = Some(issuer.into());
+        self
+    }
+
+    #[inline]
+    pub const fn groups_as_roles(mut self, enabled: bool) -> Self {
+        self.groups_as_roles = enabled;
+        self
+    }
+
+    #[inline]
+    pub fn trusts(&self, peer: IpAddr) -> bool {
+        self.networks.iter().any(|network| network.contains(peer))
+    }
+
+    pub(super) fn identity(
+        &self,
+        kc: &crate::ReCloak,
+        peer: Option<IpAddr>,
+        headers: &HeaderMap,
+    ) -> Option<ProxiedIdentity> {
+        let peer = peer.filter(|&peer| self.trusts(peer))?;
+        let user = first_header(headers, &USER_HEADERS)?;
+
+        telemetry::trace_debug!(target: MIDDLEWARE_TARGET, %peer, "accepting identity from trusted proxy");
+
+        let issuer = match self.issuer {
+            | Some(ref issuer) => issuer.clone(),
+            | None => match kc.config.load().token.issuer.as_deref() {
+                | Some([issuer, ..]) => issuer.as_str().into(),
+                | _ => kc.urls.load().issuer.as_str().into(),
+            },
+        };
+
+        let now = kc.clock.now();
+        let mut claims = crate::Claims::new(
+            issuer,
+            user,
+            kc.config.load().client.id.as_str(),
+            now,
+            now + chrono::Duration::seconds(IDENTITY_LIFETIME_SECS),
+        );
+
+        claims.username = Some(
+            first_header(headers, &USERNAME_HEADERS)
+                .unwrap_or(user)
+                .into(),
+        );
+        claims.email = first_header(headers, &EMAIL_HEADERS).map(Into::into);
+
+        if self.groups_as_roles {
+            if let Some(groups) = first_header(headers, &GROUPS_HEADERS) {
+                claims.realm = crate::RolesClaim::new(
+                    groups
+                        .split(',')
+                        .map(|group| group.trim().trim_start_matches('/'))
+                        .filter(|group| !group.is_empty()),
+                );
+            }
+        }
+
+        let auth_header = first_header(headers, &ACCESS_TOKEN_HEADERS)
+            .and_then(|token| TokenHeader::DEFAULT.value(token));
+
+        Some(ProxiedIdentity {
+            claims,
+            auth_header,
+        })
+    }
+}
+
+impl ProxyNetwork {
+    #[inline]
+    pub const fn host(addr: IpAddr) -> Self {
+        let prefix = match addr {
+            | IpAddr::V4(_) => 32,
+            | IpAddr::V6(_) => 128,
+        };
+
+        Self { addr, prefix }
+    }
+
+    pub fn new(addr: IpAddr, prefix: u8) -> Result<Self> {
+        if prefix > Self::host(addr).prefix {
+            return Err(invalid_network(&format!("{addr}/{prefix}")));
+        }
+
+        Ok(Self { addr, prefix })
+    }
+
+    pub fn contains(&self, peer: IpAddr) -> bool {
+        let peer = match peer {
+            | IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(peer, IpAddr::V4),
+            | v4 => v4,
+        };
+
+        match (self.addr, peer) {
+            | (IpAddr::V4(net), IpAddr::V4(peer)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32);
+                let mask = mask.unwrap_or_default();
+
+                u32::from(net) & mask == u32::from(peer) & mask
+            }
+            | (IpAddr::V6(net), IpAddr::V6(peer)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32);
+                let mask = mask.unwrap_or_default();
+
+                u128::from(net) & mask == u128::from(peer) & mask
+            }
+            | _ => false,
+        }
+    }
+}
+
+impl From<IpAddr> for ProxyNetwork {
+    #[inline]
+    fn from(addr: IpAddr) -> Self {
+        Self::host(addr)
+    }
+}
+
+impl FromStr for ProxyNetwork {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let parsed = match value.split_once('/') {
+            | Some((addr, prefix)) => addr
+                .parse()
+                .ok()
+                .zip(prefix.parse().ok())
+                .map(|(addr, prefix)| Self::new(addr, prefix)),
+            | None => value.parse().ok().map(Self::host).map(Ok),
+        };
+
+        parsed.unwrap_or_else(|| Err(invalid_network(value)))
+    }
+}
+
+impl From<SocketAddr> for PeerAddr {
+    #[inline]
+    fn from(addr: SocketAddr) -> Self {
+        Self(addr.ip())
+    }
+}
+
+pub(super) fn peer_addr(extensions: &http::Extensions) -> Option<IpAddr> {
+    if let Some(PeerAddr(addr)) = extensions.get() {
+        return Some(*addr);
+    }
+
+    extensions.get::<SocketAddr>().map(SocketAddr::ip)
+}
+
+fn first_header<'a>(
+    headers: &'a HeaderMap,
+    names: &[HeaderName],
+) -> Option<&'a str> {
+    names
+        .iter()
+        .filter_map(|name| headers.get(name)?.to_str().ok())
+        .map(str::trim)
+        .find(|value| !value.is_empty())
+}
+
+#[inline]
+fn invalid_network(value: &str) -> Error {
+    Error::Config(format!("invalid trusted proxy network `{value}`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderValue;
+
+    use super::*;
+    use crate::testing;
+
+    fn network(value: &str) -> ProxyNetwork {
+        value.parse().unwrap()
+    }
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    fn forwarded(user: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(&USER_HEADERS[0], HeaderValue::from_str(user).unwrap());
+        headers
+    }
+
+    #[test]
+    fn matches_ipv4_cidr() {
+        let net = network("10.1.0.0/16");
+
+        assert!(net.contains(ip("10.1.255.7")));
+        assert!(!net.contains(ip("10.2.0.1")));
+        assert!(net.contains(ip("::ffff:10.1.0.1")));
+        assert!(!net.contains(ip("fd00::1")));
+    }
+
+    #[test]
+    fn matches_ipv6_cidr() {
+        let net = network("fd00:1::/32");
+
+        assert!(net.contains(ip("fd00:1::42")));
+        assert!(!net.contains(ip("fd00:2::42")));
+        assert!(!net.contains(ip("10.0.0.1")));
+    }
+
+    #[test]
+    fn host_network_matches_only_that_address() {
+        let net = network("192.168.1.10");
+
+        assert!(net.contains(ip("192.168.1.10")));
+        assert!(!net.contains(ip("192.168.1.11")));
+    }
+
+    #[test]
+    fn zero_prefix_matches_whole_family() {
+        assert!(network("0.0.0.0/0").contains(ip("203.0.113.9")));
+        assert!(!network("0.0.0.0/0").contains(ip("2001:db8::1")));
+    }
+
+    #[test]
+    fn rejects_invalid_networks() {
+        for value in ["10.0.0.0/33", "fd00::/129", "10.0.0.0/x", "proxy"] {
+            assert!(value.parse::<ProxyNetwork>().is_err(), "{value}");
+        }
+    }
+
+    #[tokio::test]
+    async fn accepts_identity_from_trusted_peer() {
+        let kc = testing::recloak(testing::config()).await;
+        let proxies = TrustedProxies::new().trust(network("10.0.0.0/8"));
+
+        let identity = proxies
+            .identity(&kc, Some(ip("10.0.0.5")), &forwarded("alice"))
+            .unwrap();
+
+        assert_eq!(identity.claims.subject, "alice");
+        assert_eq!(identity.claims.username.as_deref(), Some("alice"));
+    }
+
+    #[tokio::test]
+    async fn ignores_identity_from_untrusted_peer() {
+        let kc = testing::recloak(testing::config()).await;
+        let proxies = TrustedProxies::new().trust(network("10.0.0.0/8"));
+        let headers = forwarded("alice");
+        let peer = Some(ip("192.0.2.1"));
+
+        assert!(proxies.identity(&kc, peer, &headers).is_none());
+        assert!(proxies.identity(&kc, None, &headers).is_none());
+    }
+}
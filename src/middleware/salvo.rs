@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use salvo::{
+    async_trait,
+    http::ResBody,
+    Depot,
+    FlowCtrl,
+    Handler,
+    Request,
+    Response,
+};
+
+use super::http::{AuthServiceLayer, ServerMode, TokenHeader};
+
+#[derive(Debug)]
+pub struct SalvoAuthHandler {
+    kc: Arc<crate::ReCloak>,
+    header: TokenHeader,
+    mode: ServerMode,
+}
+
+impl<E> From<AuthServiceLayer<ServerMode, E>> for SalvoAuthHandler {
+    #[inline]
+    fn from(layer: AuthServiceLayer<ServerMode, E>) -> Self {
+        Self {
+            kc: layer.kc,
+            header: layer.header,
+            mode: layer.mode,
+        }
+    }
+}
+
+#[async_trait]
+impl Handler for SalvoAuthHandler {
+    async fn handle(
+        &self,
+        req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+    ) {
+        if self.mode.is_preflight(req.method(), req.headers()) {
+            return;
+        }
+
+        let auth = self
+            .mode
+            .authorize(
+                &self.kc,
+                &self.header,
+                req.method(),
+                req.uri(),
+                req.headers(),
+                self.mode
+                    .proxies
+                    .as_ref()
+                    .and_then(|_| req.remote_addr().clone().into_std())
+                    .map(|addr| addr.ip()),
+                None,
+            )
+            .await;
+
+        let auth = match auth {
+            | Ok(auth) => auth,
+            | Err(err) => {
+                let err = match self.mode.rejection {
+                    | Some(ref handler) => {
+                        match handler.handle::<ResBody>(err) {
+                            | Ok(rejection) => {
+                                write_response(res, rejection);
+                                self.mode.append_vary(
+                                    &self.header,
+                                    res.headers_mut(),
+                                );
+                                ctrl.skip_rest();
+
+                                return;
+                            }
+                            | Err(err) => err,
+                        }
+                    }
+                    | None => err,
+                };
+
+                res.status_code(err.status_code());
+                res.body(err.to_string());
+                self.mode.append_vary(&self.header, res.headers_mut());
+                ctrl.skip_rest();
+
+                return;
+            }
+        };
+
+        if self.mode.strip_header {
+            req.headers_mut().remove(&self.header.name);
+        }
+
+        if let Some(principal) = self.kc.request_principal(auth.claims()) {
+            req.extensions_mut().insert(principal);
+        }
+        req.extensions_mut().insert(auth);
+
+        ctrl.call_next(req, depot, res).await;
+        self.mode.append_vary(&self.header, res.headers_mut());
+    }
+}
+
+fn write_response(res: &mut Response, from: http::Response<ResBody>) {
+    let (parts, body) = from.into_parts();
+
+    res.status_code(parts.status);
+    res.headers_mut().extend(parts.headers);
+    res.extensions.extend(parts.extensions);
+    res.body = body;
+}
@@ -0,0 +1,174 @@
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use http::Request;
+use tower::{Layer, Service};
+
+use super::http::RequestAuthorization;
+use crate::telemetry::{self, MIDDLEWARE_TARGET};
+
+const REALMS_PATH_SEGMENT: &str = "realms";
+
+pub trait TenantResolver: Send + Sync + 'static {
+    fn resolve(&self, claims: &crate::Claims) -> Option<Tenant>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Tenant(arcstr::ArcStr);
+
+#[derive(Debug, Clone)]
+pub enum TenantSource {
+    Realm,
+    Claim(arcstr::ArcStr),
+    Organization,
+}
+
+#[derive(Debug)]
+pub struct TenantService<S, R> {
+    inner: S,
+    resolver: Arc<R>,
+}
+
+#[derive(Debug)]
+pub struct TenantLayer<R> {
+    resolver: Arc<R>,
+}
+
+impl Tenant {
+    #[inline]
+    pub fn new(id: impl Into<arcstr::ArcStr>) -> Self {
+        Self(id.into())
+    }
+
+    #[inline]
+    pub fn id(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl TenantResolver for TenantSource {
+    fn resolve(&self, claims: &crate::Claims) -> Option<Tenant> {
+        match self {
+            | TenantSource::Realm => {
+                let mut segments = claims.issuer.rsplit('/');
+                let realm = segments.next().filter(|s| !s.is_empty())?;
+
+                (segments.next() == Some(REALMS_PATH_SEGMENT))
+                    .then(|| Tenant::new(realm))
+            }
+            | TenantSource::Claim(name) => match claims.claim(name)? {
+                | serde_json::Value::String(id) => {
+                    Some(Tenant::new(id.as_str()))
+                }
+                | serde_json::Value::Number(id) => {
+                    Some(Tenant::new(id.to_string()))
+                }
+                | _ => None,
+            },
+            | TenantSource::Organization => {
+                match claims.organizations.as_slice() {
+                    | [org] => Some(Tenant::new(org.alias())),
+                    | _ => None,
+                }
+            }
+        }
+    }
+}
+
+impl<F> TenantResolver for F
+where
+    F: Fn(&crate::Claims) -> Option<Tenant> + Send + Sync + 'static,
+{
+    #[inline]
+    fn resolve(&self, claims: &crate::Claims) -> Option<Tenant> {
+        self(claims)
+    }
+}
+
+impl<R> TenantLayer<R> {
+    #[inline]
+    pub fn new(resolver: R) -> Self {
+        Self {
+            resolver: Arc::new(resolver),
+        }
+    }
+}
+
+impl<S, R, B> Service<Request<B>> for TenantService<S, R>
+where
+    S: Service<Request<B>>,
+    R: TenantResolver,
+{
+    type Error = S::Error;
+    type Future = S::Future;
+    type Response = S::Response;
+
+    #[inline]
+    fn poll_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<B>) -> Self::Future {
+        let tenant = req
+            .extensions()
+            .get::<RequestAuthorization>()
+            .and_then(|auth| self.resolver.resolve(auth.claims()));
+
+        match tenant {
+            | Some(tenant) => {
+                req.extensions_mut().insert(tenant);
+            }
+            | None => {
+                telemetry::trace_debug!(target: MIDDLEWARE_TARGET, "no tenant resolved for request");
+            }
+        }
+
+        self.inner.call(req)
+    }
+}
+
+impl<S, R> Layer<S> for TenantLayer<R> {
+    type Service = TenantService<S, R>;
+
+    #[inline]
+    fn layer(&self, inner: S) -> Self::Service {
+        TenantService {
+            inner,
+            resolver: self.resolver.clone(),
+        }
+    }
+}
+
+impl<R> Clone for TenantLayer<R> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            resolver: self.resolver.clone(),
+        }
+    }
+}
+
+impl<S, R> Clone for TenantService<S, R>
+where
+    S: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            resolver: self.resolver.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for Tenant {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.id())
+    }
+}
@@ -0,0 +1,329 @@
+use std::{
+    convert::Infallible,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use http::{HeaderMap, HeaderName, Method, Request, Response, StatusCode};
+use http_body_util::{BodyExt, Empty, Limited};
+use tower::Service;
+
+use crate::{
+    representations::KeycloakEvent,
+    telemetry::{self, MIDDLEWARE_TARGET},
+};
+
+const DEFAULT_MAX_BODY_SIZE: usize = 1024 * 1024;
+
+pub const SIGNATURE_HEADER: HeaderName =
+    HeaderName::from_static("x-keycloak-signature");
+
+pub trait EventHandler: Send + Sync + 'static {
+    fn handle(&self, event: KeycloakEvent);
+}
+
+#[derive(Debug)]
+pub enum WebhookVerifier {
+    SharedSecret {
+        header: HeaderName,
+        secret: arcstr::ArcStr,
+    },
+    Signature {
+        header: HeaderName,
+        key: ring::hmac::Key,
+    },
+}
+
+#[derive(Debug)]
+pub struct WebhookService<H> {
+    verifier: Arc<WebhookVerifier>,
+    handler: Arc<H>,
+    max_body_size: usize,
+}
+
+impl WebhookVerifier {
+    #[inline]
+    pub fn shared_secret(
+        header: HeaderName,
+        secret: impl Into<arcstr::ArcStr>,
+    ) -> Self {
+        Self::SharedSecret {
+            header,
+            secret: secret.into(),
+        }
+    }
+
+    #[inline]
+    pub fn hmac_sha256(secret: impl AsRef<[u8]>) -> Self {
+        Self::Signature {
+            header: SIGNATURE_HEADER,
+            key: ring::hmac::Key::new(ring::hmac::HMAC_SHA256, secret.as_ref()),
+        }
+    }
+
+    fn verify(&self, headers: &HeaderMap, body: &[u8]) -> bool {
+        match self {
+            | WebhookVerifier::SharedSecret { header, secret } => headers
+                .get(header)
+                .is_some_and(|value| eq(value.as_bytes(), secret.as_bytes())),
+            | WebhookVerifier::Signature { header, key } => headers
+                .get(header)
+                .and_then(|value| decode_hex(value.as_bytes()))
+                .is_some_and(|tag| ring::hmac::verify(key, body, &tag).is_ok()),
+        }
+    }
+}
+
+impl<F> EventHandler for F
+where
+    F: Fn(KeycloakEvent) + Send + Sync + 'static,
+{
+    #[inline]
+    fn handle(&self, event: KeycloakEvent) {
+        self(event)
+    }
+}
+
+impl EventHandler for tokio::sync::mpsc::UnboundedSender<KeycloakEvent> {
+    #[inline]
+    fn handle(&self, event: KeycloakEvent) {
+        if self.send(event).is_err() {
+            telemetry::trace_warn!(target: MIDDLEWARE_TARGET, "event receiver dropped, discarding event");
+        }
+    }
+}
+
+impl<H> WebhookService<H> {
+    #[inline]
+    pub fn new(verifier: WebhookVerifier, handler: H) -> Self {
+        Self {
+            verifier: Arc::new(verifier),
+            handler: Arc::new(handler),
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+        }
+    }
+
+    #[inline]
+    pub const fn max_body_size(mut self, limit: usize) -> Self {
+        self.max_body_size = limit;
+        self
+    }
+}
+
+impl<H, B> Service<Request<B>> for WebhookService<H>
+where
+    H: EventHandler,
+    B: http_body::Body + Send + 'static,
+    B::Data: Send,
+    B::Error: std::error::Error + Send + Sync + 'static,
+{
+    type Error = Infallible;
+    type Response = Response<Empty<Bytes>>;
+
+    type Future = Pin<
+        Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>,
+    >;
+
+    #[inline]
+    fn poll_ready(
+        &mut self,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<B>) -> Self::Future {
+        let verifier = self.verifier.clone();
+        let handler = self.handler.clone();
+        let limit = self.max_body_size;
+
+        Box::pin(async move {
+            let (parts, body) = req.into_parts();
+
+            if parts.method != Method::POST {
+                return Ok(status(StatusCode::METHOD_NOT_ALLOWED));
+            }
+
+            let body = match Limited::new(body, limit).collect().await {
+                | Ok(body) => body.to_bytes(),
+                | Err(err) => {
+                    telemetry::trace_warn!(target: MIDDLEWARE_TARGET, error = %err, "failed to read webhook body");
+
+                    return Ok(status(StatusCode::BAD_REQUEST));
+                }
+            };
+
+            if !verifier.verify(&parts.headers, &body) {
+                telemetry::trace_warn!(target: MIDDLEWARE_TARGET, "rejected webhook with invalid credentials");
+
+                return Ok(status(StatusCode::UNAUTHORIZED));
+            }
+
+            match serde_json::from_slice::<KeycloakEvent>(&body) {
+                | Ok(event) => {
+                    handler.handle(event);
+
+                    Ok(status(StatusCode::NO_CONTENT))
+                }
+                | Err(err) => {
+                    telemetry::trace_warn!(target: MIDDLEWARE_TARGET, error = %err, "failed to parse webhook event");
+
+                    Ok(status(StatusCode::BAD_REQUEST))
+                }
+            }
+        })
+    }
+}
+
+impl<H> Clone for WebhookService<H> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            verifier: self.verifier.clone(),
+            handler: self.handler.clone(),
+            max_body_size: self.max_body_size,
+        }
+    }
+}
+
+#[inline]
+fn status(code: StatusCode) -> Response<Empty<Bytes>> {
+    let mut resp = Response::new(Empty::new());
+    *resp.status_mut() = code;
+    resp
+}
+
+fn eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len()
+        && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn decode_hex(value: &[u8]) -> Option<Vec<u8>> {
+    let value = value.strip_prefix(b"sha256=").unwrap_or(value);
+    if !value.len().is_multiple_of(2) {
+        return None;
+    }
+
+    value
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use http_body_util::Full;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    const SECRET: &[u8] = b"webhook-secret";
+    const EVENT: &[u8] = br#"{"type":"LOGIN","realmId":"test"}"#;
+
+    type Received = Arc<Mutex<Vec<KeycloakEvent>>>;
+
+    fn service(
+        verifier: WebhookVerifier,
+    ) -> (WebhookService<impl EventHandler>, Received) {
+        let received = Received::default();
+        let sink = received.clone();
+
+        let service = WebhookService::new(verifier, move |event| {
+            sink.lock().unwrap().push(event);
+        });
+
+        (service, received)
+    }
+
+    fn request(header: Option<(&HeaderName, String)>) -> Request<Full<Bytes>> {
+        let mut req = Request::post("/events");
+        if let Some((name, value)) = header {
+            req = req.header(name, value);
+        }
+
+        req.body(Full::new(Bytes::from_static(EVENT))).unwrap()
+    }
+
+    fn sign(secret: &[u8], body: &[u8]) -> String {
+        let key = ring::hmac::Key::new(ring::hmac::HMAC_SHA256, secret);
+        let tag = ring::hmac::sign(&key, body);
+
+        tag.as_ref().iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    #[tokio::test]
+    async fn accepts_valid_signature() {
+        let verifier = WebhookVerifier::hmac_sha256(SECRET);
+        let (service, received) = service(verifier);
+        let signature = format!("sha256={}", sign(SECRET, EVENT));
+
+        let res = service
+            .oneshot(request(Some((&SIGNATURE_HEADER, signature))))
+            .await
+            .unwrap();
+
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+        assert_eq!(received.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn rejects_bad_signature() {
+        let verifier = WebhookVerifier::hmac_sha256(SECRET);
+        let (service, received) = service(verifier);
+        let signature = sign(b"another-secret", EVENT);
+
+        let res = service
+            .oneshot(request(Some((&SIGNATURE_HEADER, signature))))
+            .await
+            .unwrap();
+
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+        assert!(received.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn rejects_malformed_or_missing_signature() {
+        for header in [Some((&SIGNATURE_HEADER, "zz".to_owned())), None] {
+            let verifier = WebhookVerifier::hmac_sha256(SECRET);
+            let (service, _) = service(verifier);
+
+            let res = service.oneshot(request(header)).await.unwrap();
+
+            assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_wrong_shared_secret() {
+        let header = HeaderName::from_static("x-webhook-secret");
+        let verifier = WebhookVerifier::shared_secret(header.clone(), "right");
+        let (service, received) = service(verifier);
+
+        let res = service
+            .oneshot(request(Some((&header, "wrong".to_owned()))))
+            .await
+            .unwrap();
+
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+        assert!(received.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn rejects_non_post_requests() {
+        let (service, _) = service(WebhookVerifier::hmac_sha256(SECRET));
+        let req = Request::get("/events").body(Full::<Bytes>::default());
+
+        let res = service.oneshot(req.unwrap()).await.unwrap();
+
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
+}
@@ -0,0 +1,139 @@
+use std::sync::Arc;
+
+use http::{header::SEC_WEBSOCKET_PROTOCOL, HeaderMap, Uri};
+use serde::Deserialize;
+
+use super::http::{RequestAuthorization, TokenHeader};
+use crate::{
+    telemetry::{self, MIDDLEWARE_TARGET},
+    Error,
+    Result,
+};
+
+const DEFAULT_PROTOCOL: arcstr::ArcStr = arcstr::literal!("bearer");
+
+#[derive(Debug, Clone)]
+pub struct HandshakeAuth {
+    kc: Arc<crate::ReCloak>,
+    protocol: arcstr::ArcStr,
+    query_param: Option<arcstr::ArcStr>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AuthMessage {
+    #[serde(alias = "token")]
+    pub access_token: String,
+}
+
+impl HandshakeAuth {
+    #[inline]
+    pub const fn new(kc: Arc<crate::ReCloak>) -> Self {
+        Self {
+            kc,
+            protocol: DEFAULT_PROTOCOL,
+            query_param: None,
+        }
+    }
+
+    #[inline]
+    pub fn protocol(mut self, protocol: impl Into<arcstr::ArcStr>) -> Self {
+        self.protocol = protocol.into();
+        self
+    }
+
+    #[inline]
+    pub fn query_param(mut self, name: impl Into<arcstr::ArcStr>) -> Self {
+        self.query_param = Some(name.into());
+        self
+    }
+
+    #[inline]
+    pub fn selected_protocol(&self) -> &str {
+        &self.protocol
+    }
+
+    pub async fn authorize_handshake(
+        &self,
+        headers: &HeaderMap,
+        uri: &Uri,
+    ) -> Result<RequestAuthorization> {
+        let header = headers
+            .get(&TokenHeader::DEFAULT.name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| TokenHeader::DEFAULT.extract(value));
+
+        if let Some(token) = header {
+            return self.authorize_token(token).await;
+        }
+
+        if let Some(token) = protocol_token(headers, &self.protocol) {
+            return self.authorize_token(token).await;
+        }
+
+        let token = self
+            .query_param
+            .as_deref()
+            .and_then(|name| query_token(uri, name))
+            .ok_or_else(missing_token)?;
+
+        self.authorize_token(&token).await
+    }
+
+    pub async fn authorize_message(
+        &self,
+        message: &str,
+    ) -> Result<RequestAuthorization> {
+        let message = message.trim();
+
+        match serde_json::from_str::<AuthMessage>(message) {
+            | Ok(message) => self.authorize_token(&message.access_token).await,
+            | Err(_) if !message.starts_with('{') => {
+                self.authorize_token(message).await
+            }
+            | Err(err) => {
+                telemetry::trace_error!(target: MIDDLEWARE_TARGET, error = %err, "failed to parse authentication message");
+
+                Err(missing_token())
+            }
+        }
+    }
+
+    pub async fn authorize_token(
+        &self,
+        token: &str,
+    ) -> Result<RequestAuthorization> {
+        let auth_header = TokenHeader::DEFAULT
+            .value(token)
+            .ok_or_else(missing_token)?;
+        let token = self.kc.validate_token(token).await?;
+
+        Ok(RequestAuthorization::new(token.claims, auth_header))
+    }
+}
+
+pub fn protocol_token<'a>(
+    headers: &'a HeaderMap,
+    protocol: &str,
+) -> Option<&'a str> {
+    let mut protocols = headers
+        .get_all(SEC_WEBSOCKET_PROTOCOL)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim);
+
+    protocols.find(|value| value.eq_ignore_ascii_case(protocol))?;
+    protocols.next().filter(|token| !token.is_empty())
+}
+
+pub fn query_token(uri: &Uri, name: &str) -> Option<String> {
+    url::form_urlencoded::parse(uri.query()?.as_bytes())
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.into_owned())
+        .filter(|token| !token.is_empty())
+}
+
+#[inline]
+fn missing_token() -> Error {
+    Error::Jwt(jsonwebtoken::errors::ErrorKind::InvalidToken.into())
+}
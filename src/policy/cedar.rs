@@ -0,0 +1,177 @@
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
+
+use cedar_policy::{
+    Authorizer,
+    Context,
+    Entities,
+    Entity,
+    EntityId,
+    EntityTypeName,
+    EntityUid,
+    PolicySet,
+    Request,
+    RestrictedExpression,
+};
+
+use super::{PolicyBackend, PolicyFuture, RequestDescriptor};
+use crate::{Claims, Decision, DenyReason, Error, Result};
+
+const PRINCIPAL_TYPE: &str = "User";
+const ROLE_TYPE: &str = "Role";
+const CLIENT_ROLE_TYPE: &str = "ClientRole";
+const ACTION_TYPE: &str = "Action";
+const RESOURCE_TYPE: &str = "Route";
+
+#[derive(Debug)]
+pub struct CedarBackend {
+    policies: PolicySet,
+    authorizer: Authorizer,
+}
+
+impl CedarBackend {
+    pub fn new(policies: &str) -> Result<Self> {
+        let policies = PolicySet::from_str(policies).map_err(|err| {
+            Error::Config(format!("invalid cedar policies: {err}"))
+        })?;
+
+        Ok(Self::from_policy_set(policies))
+    }
+
+    #[inline]
+    pub fn from_policy_set(policies: PolicySet) -> Self {
+        Self {
+            policies,
+            authorizer: Authorizer::new(),
+        }
+    }
+
+    fn decide(
+        &self,
+        claims: &Claims,
+        request: &RequestDescriptor,
+    ) -> Result<Decision> {
+        let principal = uid(PRINCIPAL_TYPE, &claims.subject)?;
+
+        let mut parents = claims
+            .realm
+            .roles
+            .iter()
+            .map(|role| uid(ROLE_TYPE, role))
+            .collect::<Result<HashSet<_>>>()?;
+
+        for (client, roles) in &claims.resource {
+            for role in &roles.roles {
+                parents.insert(uid(
+                    CLIENT_ROLE_TYPE,
+                    &format!("{client}:{role}"),
+                )?);
+            }
+        }
+
+        let mut attrs = HashMap::new();
+        if let Some(ref username) = claims.username {
+            attrs.insert(
+                "username".to_owned(),
+                RestrictedExpression::new_string(username.to_string()),
+            );
+        }
+
+        if let Some(client_id) = claims.client_id() {
+            attrs.insert(
+                "client_id".to_owned(),
+                RestrictedExpression::new_string(client_id.to_owned()),
+            );
+        }
+
+        let roles = parents
+            .iter()
+            .cloned()
+            .map(|parent| Entity::new(parent, HashMap::new(), HashSet::new()));
+        let entities = Entities::from_entities(
+            std::iter::once(Entity::new(
+                principal.clone(),
+                attrs,
+                parents.clone(),
+            ))
+            .chain(roles),
+        )
+        .map_err(|err| Error::Policy(err.to_string()))?;
+
+        let context = Context::from_pairs([
+            (
+                "method".to_owned(),
+                RestrictedExpression::new_string(request.method.clone()),
+            ),
+            (
+                "path".to_owned(),
+                RestrictedExpression::new_string(request.path.clone()),
+            ),
+            (
+                "scope".to_owned(),
+                RestrictedExpression::new_string(
+                    super::scope(claims).to_owned(),
+                ),
+            ),
+            (
+                "acr".to_owned(),
+                RestrictedExpression::new_string(
+                    claims.auth_class_reference.clone().unwrap_or_default(),
+                ),
+            ),
+        ]);
+
+        let request = Request::new(
+            Some(principal),
+            Some(uid(ACTION_TYPE, &request.method)?),
+            Some(uid(RESOURCE_TYPE, &request.path)?),
+            context,
+        );
+
+        let response =
+            self.authorizer
+                .is_authorized(&request, &self.policies, &entities);
+
+        let decision = match response.decision() {
+            | cedar_policy::Decision::Allow => Decision::Allow,
+            | cedar_policy::Decision::Deny => {
+                let reason = response
+                    .diagnostics()
+                    .reason()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(",");
+
+                Decision::Deny(DenyReason::Policy(if reason.is_empty() {
+                    arcstr::literal!("cedar")
+                } else {
+                    reason.into()
+                }))
+            }
+        };
+
+        Ok(decision)
+    }
+}
+
+impl PolicyBackend for CedarBackend {
+    #[inline]
+    fn evaluate<'a>(
+        &'a self,
+        claims: &'a Claims,
+        request: &'a RequestDescriptor,
+    ) -> PolicyFuture<'a> {
+        Box::pin(async move { self.decide(claims, request) })
+    }
+}
+
+fn uid(kind: &str, id: &str) -> Result<EntityUid> {
+    let kind = EntityTypeName::from_str(kind)
+        .map_err(|err| Error::Policy(err.to_string()))?;
+    let id =
+        EntityId::from_str(id).map_err(|err| Error::Policy(err.to_string()))?;
+
+    Ok(EntityUid::from_type_name_and_id(kind, id))
+}
@@ -0,0 +1,77 @@
+#[cfg(feature = "cedar")]
+mod cedar;
+#[cfg(feature = "opa")]
+mod opa;
+mod routes;
+
+use std::{fmt, future::Future, pin::Pin};
+
+#[cfg(feature = "cedar")]
+pub use self::cedar::CedarBackend;
+#[cfg(feature = "opa")]
+pub use self::opa::OpaBackend;
+pub use self::routes::{
+    RouteFallback,
+    RoutePermissions,
+    RouteRule,
+    RouteTable,
+};
+use crate::{Claims, Decision, Result};
+
+pub type PolicyFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Decision>> + Send + 'a>>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestDescriptor {
+    pub method: String,
+    pub path: String,
+    pub query: Option<String>,
+}
+
+pub trait PolicyBackend: fmt::Debug + Send + Sync + 'static {
+    fn evaluate<'a>(
+        &'a self,
+        claims: &'a Claims,
+        request: &'a RequestDescriptor,
+    ) -> PolicyFuture<'a>;
+}
+
+impl PolicyBackend for crate::Requirement {
+    #[inline]
+    fn evaluate<'a>(
+        &'a self,
+        claims: &'a Claims,
+        _request: &'a RequestDescriptor,
+    ) -> PolicyFuture<'a> {
+        Box::pin(async move { Ok(crate::Authorizer.decide(claims, self)) })
+    }
+}
+
+impl RequestDescriptor {
+    #[inline]
+    pub fn new(method: impl Into<String>, path: impl Into<String>) -> Self {
+        Self {
+            method: method.into(),
+            path: path.into(),
+            query: None,
+        }
+    }
+
+    #[cfg(feature = "middleware")]
+    #[inline]
+    pub fn from_http(method: &http::Method, uri: &http::Uri) -> Self {
+        Self {
+            method: method.as_str().to_owned(),
+            path: uri.path().to_owned(),
+            query: uri.query().map(ToOwned::to_owned),
+        }
+    }
+}
+
+#[cfg(any(feature = "opa", feature = "cedar"))]
+fn scope(claims: &Claims) -> &str {
+    claims
+        .claim("scope")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+}
@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use reqwest::{
+    header::{HeaderValue, CONTENT_TYPE},
+    Method,
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::{PolicyBackend, PolicyFuture, RequestDescriptor};
+use crate::{
+    Claims,
+    Decision,
+    DenyReason,
+    Error,
+    HttpTransport,
+    Result,
+    TransportRequest,
+};
+
+const DATA_PATH: &str = "v1/data/";
+
+#[derive(Debug, Clone)]
+pub struct OpaBackend {
+    transport: Arc<dyn HttpTransport>,
+    url: url::Url,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpaResponse {
+    result: Option<OpaResult>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OpaResult {
+    Allowed(bool),
+    Detailed { allow: bool, reason: Option<String> },
+}
+
+impl OpaBackend {
+    pub fn new(server: &url::Url, rule: impl AsRef<str>) -> Result<Self> {
+        let rule = rule.as_ref().trim_matches('/').replace('.', "/");
+        let url = server.join(DATA_PATH)?.join(&rule)?;
+
+        Ok(Self {
+            transport: Arc::new(reqwest::Client::new()),
+            url,
+        })
+    }
+
+    #[inline]
+    pub fn transport(mut self, transport: impl HttpTransport) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    async fn query(
+        &self,
+        claims: &Claims,
+        request: &RequestDescriptor,
+    ) -> Result<Decision> {
+        let mut req = TransportRequest::new(Method::POST, self.url.clone());
+        req.headers
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        req.body = Some(
+            json!({ "input": input(claims, request) })
+                .to_string()
+                .into(),
+        );
+
+        let resp = self.transport.execute(req).await?;
+        if !resp.status.is_success() {
+            return Err(Error::Policy(format!(
+                "opa responded with status {}",
+                resp.status
+            )));
+        }
+
+        let decision = match resp.json::<OpaResponse>()?.result {
+            | Some(OpaResult::Allowed(true))
+            | Some(OpaResult::Detailed { allow: true, .. }) => Decision::Allow,
+            | Some(OpaResult::Detailed {
+                reason: Some(reason),
+                ..
+            }) => Decision::Deny(DenyReason::Policy(reason.into())),
+            | Some(_) => {
+                Decision::Deny(DenyReason::Policy(arcstr::literal!("opa")))
+            }
+            | None => Decision::Deny(DenyReason::Policy(arcstr::literal!(
+                "opa rule undefined"
+            ))),
+        };
+
+        Ok(decision)
+    }
+}
+
+impl PolicyBackend for OpaBackend {
+    #[inline]
+    fn evaluate<'a>(
+        &'a self,
+        claims: &'a Claims,
+        request: &'a RequestDescriptor,
+    ) -> PolicyFuture<'a> {
+        Box::pin(self.query(claims, request))
+    }
+}
+
+fn input(claims: &Claims, request: &RequestDescriptor) -> serde_json::Value {
+    let client_roles = claims
+        .resource
+        .iter()
+        .map(|(client, roles)| (client.to_string(), json!(roles.roles)))
+        .collect::<serde_json::Map<_, _>>();
+
+    let organizations = claims
+        .organizations
+        .iter()
+        .map(|org| org.alias())
+        .collect::<Vec<_>>();
+
+    json!({
+        "subject": claims.subject,
+        "username": claims.username,
+        "client_id": claims.client_id(),
+        "realm_roles": claims.realm.roles,
+        "client_roles": client_roles,
+        "scope": super::scope(claims),
+        "acr": claims.auth_class_reference,
+        "organizations": organizations,
+        "request": {
+            "method": request.method,
+            "path": request.path,
+            "query": request.query,
+        },
+    })
+}
@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use super::{PolicyBackend, PolicyFuture, RequestDescriptor};
+use crate::{Claims, Decision, DenyReason, Requirement};
+
+const ANY_METHOD: &str = "*";
+const ANY_SEGMENT: &str = "*";
+const ANY_SUFFIX: &str = "**";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(from = "RouteTable")]
+pub struct RoutePermissions {
+    rules: Vec<CompiledRule>,
+    fallback: RouteFallback,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RouteTable {
+    #[serde(default)]
+    pub fallback: RouteFallback,
+
+    #[serde(default)]
+    pub routes: Vec<RouteRule>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RouteRule {
+    pub path: String,
+
+    #[serde(default)]
+    pub methods: Vec<String>,
+
+    #[serde(default)]
+    pub roles: Vec<String>,
+
+    #[serde(default)]
+    pub client_roles: HashMap<String, Vec<String>>,
+
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RouteFallback {
+    Allow,
+    #[default]
+    Deny,
+}
+
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    segments: Vec<Segment>,
+    methods: Vec<String>,
+    requirement: Option<Requirement>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Any,
+    Rest,
+}
+
+impl RoutePermissions {
+    #[cfg(feature = "figment")]
+    pub fn load(path: impl AsRef<std::path::Path>) -> crate::Result<Self> {
+        use figment::providers::{Format, Toml, Yaml};
+
+        let path = path.as_ref();
+        let figment = match path.extension().and_then(|ext| ext.to_str()) {
+            | Some("yaml" | "yml") => figment::Figment::from(Yaml::file(path)),
+            | _ => figment::Figment::from(Toml::file(path)),
+        };
+
+        figment
+            .extract()
+            .map_err(|err| crate::Error::Config(err.to_string()))
+    }
+
+    pub fn decide(
+        &self,
+        claims: &Claims,
+        request: &RequestDescriptor,
+    ) -> Decision {
+        let rule = self
+            .rules
+            .iter()
+            .find(|rule| rule.matches(&request.method, &request.path));
+
+        match rule {
+            | Some(CompiledRule {
+                requirement: Some(requirement),
+                ..
+            }) => crate::Authorizer.decide(claims, requirement),
+            | Some(_) => Decision::Allow,
+            | None if self.fallback == RouteFallback::Allow => Decision::Allow,
+            | None => Decision::Deny(DenyReason::Policy(arcstr::literal!(
+                "no matching route"
+            ))),
+        }
+    }
+}
+
+impl PolicyBackend for RoutePermissions {
+    #[inline]
+    fn evaluate<'a>(
+        &'a self,
+        claims: &'a Claims,
+        request: &'a RequestDescriptor,
+    ) -> PolicyFuture<'a> {
+        Box::pin(async move { Ok(self.decide(claims, request)) })
+    }
+}
+
+impl From<RouteTable> for RoutePermissions {
+    fn from(table: RouteTable) -> Self {
+        Self {
+            rules: table.routes.into_iter().map(CompiledRule::from).collect(),
+            fallback: table.fallback,
+        }
+    }
+}
+
+impl From<RouteRule> for CompiledRule {
+    fn from(rule: RouteRule) -> Self {
+        let segments = rule
+            .path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| match segment {
+                | ANY_SUFFIX => Segment::Rest,
+                | ANY_SEGMENT => Segment::Any,
+                | _ if segment.starts_with(':')
+                    || (segment.starts_with('{') && segment.ends_with('}')) =>
+                {
+                    Segment::Any
+                }
+                | _ => Segment::Literal(segment.to_owned()),
+            })
+            .collect();
+
+        let alternatives = rule
+            .roles
+            .into_iter()
+            .map(Requirement::realm_role)
+            .chain(rule.client_roles.into_iter().flat_map(|(client, roles)| {
+                roles.into_iter().map(move |role| {
+                    Requirement::client_role(client.as_str(), role)
+                })
+            }))
+            .chain(rule.scopes.into_iter().map(Requirement::scope))
+            .collect::<Vec<_>>();
+
+        Self {
+            segments,
+            methods: rule.methods,
+            requirement: (!alternatives.is_empty())
+                .then(|| Requirement::any_of(alternatives)),
+        }
+    }
+}
+
+impl CompiledRule {
+    fn matches(&self, method: &str, path: &str) -> bool {
+        let method_matches = self.methods.is_empty()
+            || self
+                .methods
+                .iter()
+                .any(|m| m == ANY_METHOD || m.eq_ignore_ascii_case(method));
+
+        method_matches && self.matches_path(path)
+    }
+
+    fn matches_path(&self, path: &str) -> bool {
+        let mut path = path.split('/').filter(|segment| !segment.is_empty());
+
+        for segment in &self.segments {
+            match segment {
+                | Segment::Rest => return true,
+                | Segment::Any => {
+                    if path.next().is_none() {
+                        return false;
+                    }
+                }
+                | Segment::Literal(literal) => {
+                    if path.next() != Some(literal.as_str()) {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        path.next().is_none()
+    }
+}
@@ -0,0 +1,266 @@
+use std::{collections::HashMap, fmt};
+
+use arcstr::ArcStr;
+use smallvec::SmallVec;
+
+use crate::{config::PrincipalConfig, Claims, Error, ReCloak, Result};
+
+const MAX_KNOWN_ROLES: usize = u64::BITS as usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KnownRole(u8);
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct RoleSet(u64);
+
+#[derive(Debug, Clone)]
+pub struct Principal {
+    subject: ArcStr,
+    username: Option<ArcStr>,
+    realm_roles: SmallVec<[ArcStr; 4]>,
+    client_roles: SmallVec<[(ArcStr, SmallVec<[ArcStr; 4]>); 2]>,
+    known: RoleSet,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct RoleRegistry {
+    realm: HashMap<ArcStr, KnownRole>,
+    clients: HashMap<ArcStr, HashMap<ArcStr, KnownRole>>,
+}
+
+impl RoleSet {
+    #[inline]
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    #[inline]
+    pub const fn with(self, role: KnownRole) -> Self {
+        Self(self.0 | 1 << role.0)
+    }
+
+    #[inline]
+    pub const fn contains(self, role: KnownRole) -> bool {
+        self.0 & 1 << role.0 != 0
+    }
+
+    #[inline]
+    pub const fn contains_all(self, roles: RoleSet) -> bool {
+        self.0 & roles.0 == roles.0
+    }
+
+    #[inline]
+    pub const fn intersects(self, roles: RoleSet) -> bool {
+        self.0 & roles.0 != 0
+    }
+
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    #[inline]
+    pub const fn len(self) -> u32 {
+        self.0.count_ones()
+    }
+}
+
+impl FromIterator<KnownRole> for RoleSet {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = KnownRole>>(iter: I) -> Self {
+        iter.into_iter().fold(Self::empty(), Self::with)
+    }
+}
+
+impl fmt::Debug for RoleSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RoleSet({:#b})", self.0)
+    }
+}
+
+impl Principal {
+    #[inline]
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    #[inline]
+    pub fn subject_id(&self) -> Option<uuid::Uuid> {
+        uuid::Uuid::try_parse(&self.subject).ok()
+    }
+
+    #[inline]
+    pub fn username(&self) -> Option<&str> {
+        self.username.as_deref()
+    }
+
+    #[inline]
+    pub fn realm_roles(&self) -> &[ArcStr] {
+        &self.realm_roles
+    }
+
+    #[inline]
+    pub fn client_roles(&self, client_id: &str) -> &[ArcStr] {
+        self.client_roles
+            .iter()
+            .find(|(client, _)| client == client_id)
+            .map_or(&[], |(_, roles)| roles)
+    }
+
+    #[inline]
+    pub const fn known_roles(&self) -> RoleSet {
+        self.known
+    }
+
+    #[inline]
+    pub const fn has(&self, role: KnownRole) -> bool {
+        self.known.contains(role)
+    }
+
+    #[inline]
+    pub const fn has_all(&self, roles: RoleSet) -> bool {
+        self.known.contains_all(roles)
+    }
+
+    #[inline]
+    pub const fn has_any(&self, roles: RoleSet) -> bool {
+        self.known.intersects(roles)
+    }
+
+    #[inline]
+    pub fn has_realm_role(&self, role: &str) -> bool {
+        self.realm_roles.iter().any(|r| r == role)
+    }
+
+    #[inline]
+    pub fn has_role(&self, client_id: &str, role: &str) -> bool {
+        self.client_roles(client_id).iter().any(|r| r == role)
+    }
+}
+
+impl RoleRegistry {
+    pub(crate) fn new(config: &PrincipalConfig) -> Result<Self> {
+        let mut registry = Self::default();
+        let mut next = 0;
+        let mut assign = || {
+            if next >= MAX_KNOWN_ROLES {
+                return Err(Error::Config(format!(
+                    "at most {MAX_KNOWN_ROLES} well-known roles can be \
+                     configured"
+                )));
+            }
+
+            next += 1;
+            Ok(KnownRole(next as u8 - 1))
+        };
+
+        for role in &config.realm_roles {
+            if !registry.realm.contains_key(role.as_str()) {
+                registry.realm.insert(role.into(), assign()?);
+            }
+        }
+
+        for (client_id, roles) in &config.client_roles {
+            let known = registry.clients.entry(client_id.into()).or_default();
+
+            for role in roles {
+                if !known.contains_key(role.as_str()) {
+                    known.insert(role.into(), assign()?);
+                }
+            }
+        }
+
+        Ok(registry)
+    }
+
+    #[cfg(feature = "middleware")]
+    #[inline]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.realm.is_empty() && self.clients.is_empty()
+    }
+
+    #[inline]
+    fn realm_role(&self, role: &str) -> Option<KnownRole> {
+        self.realm.get(role).copied()
+    }
+
+    #[inline]
+    fn client_role(&self, client_id: &str, role: &str) -> Option<KnownRole> {
+        self.clients
+            .get(client_id)
+            .and_then(|roles| roles.get(role))
+            .copied()
+    }
+
+    fn principal(&self, claims: &Claims) -> Principal {
+        let mut known = RoleSet::empty();
+
+        let realm_roles = claims
+            .realm
+            .roles
+            .iter()
+            .inspect(|role| {
+                if let Some(&bit) = self.realm.get(role.as_str()) {
+                    known = known.with(bit);
+                }
+            })
+            .cloned()
+            .collect();
+
+        let client_roles = claims
+            .resource
+            .iter()
+            .map(|(client_id, claim)| {
+                if let Some(roles) = self.clients.get(client_id.as_str()) {
+                    for role in &claim.roles {
+                        if let Some(&bit) = roles.get(role.as_str()) {
+                            known = known.with(bit);
+                        }
+                    }
+                }
+
+                (client_id.clone(), claim.roles.iter().cloned().collect())
+            })
+            .collect();
+
+        Principal {
+            subject: claims.subject.clone(),
+            username: claims.username.clone(),
+            realm_roles,
+            client_roles,
+            known,
+        }
+    }
+}
+
+impl ReCloak {
+    #[inline]
+    pub fn principal(&self, claims: &Claims) -> Principal {
+        self.principals.load().principal(claims)
+    }
+
+    #[cfg(feature = "middleware")]
+    #[inline]
+    pub(crate) fn request_principal(
+        &self,
+        claims: &Claims,
+    ) -> Option<Principal> {
+        let registry = self.principals.load();
+
+        (!registry.is_empty()).then(|| registry.principal(claims))
+    }
+
+    #[inline]
+    pub fn known_realm_role(&self, role: &str) -> Option<KnownRole> {
+        self.principals.load().realm_role(role)
+    }
+
+    #[inline]
+    pub fn known_client_role(
+        &self,
+        client_id: &str,
+        role: &str,
+    ) -> Option<KnownRole> {
+        self.principals.load().client_role(client_id, role)
+    }
+}
@@ -0,0 +1,77 @@
+use std::{fmt, future::Future, pin::Pin, sync::Arc};
+
+use tokio::sync::watch;
+
+use crate::{OAuthBearerProvider, Result};
+
+pub type CredentialFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<PasswordCredentials>> + Send + 'a>>;
+
+#[derive(Debug, Clone)]
+pub struct PasswordCredentials {
+    pub username: String,
+    pub password: arcstr::ArcStr,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+pub trait CredentialProvider: fmt::Debug + Send + Sync + 'static {
+    fn credentials(&self) -> CredentialFuture<'_>;
+
+    #[inline]
+    fn rotations(&self) -> Option<watch::Receiver<Option<arcstr::ArcStr>>> {
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AccessTokenPassword {
+    kc: Arc<crate::ReCloak>,
+    username: Option<String>,
+}
+
+impl AccessTokenPassword {
+    #[inline]
+    pub const fn new(kc: Arc<crate::ReCloak>) -> Self {
+        Self { kc, username: None }
+    }
+
+    #[inline]
+    pub fn username(mut self, username: impl Into<String>) -> Self {
+        self.username = Some(username.into());
+        self
+    }
+}
+
+impl CredentialProvider for AccessTokenPassword {
+    fn credentials(&self) -> CredentialFuture<'_> {
+        Box::pin(async move {
+            let token = self.kc.bearer_token().await?;
+
+            Ok(PasswordCredentials {
+                username: self.username.clone().unwrap_or(token.principal),
+                password: token.token,
+                expires_at: token.expires_at,
+            })
+        })
+    }
+
+    #[inline]
+    fn rotations(&self) -> Option<watch::Receiver<Option<arcstr::ArcStr>>> {
+        Some(self.kc.token_updates())
+    }
+}
+
+impl<P> CredentialProvider for Arc<P>
+where
+    P: CredentialProvider + ?Sized,
+{
+    #[inline]
+    fn credentials(&self) -> CredentialFuture<'_> {
+        (**self).credentials()
+    }
+
+    #[inline]
+    fn rotations(&self) -> Option<watch::Receiver<Option<arcstr::ArcStr>>> {
+        (**self).rotations()
+    }
+}
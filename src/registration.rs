@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+use reqwest::{Method, RequestBuilder, StatusCode};
+use url::Url;
+
+use crate::{Error, ReCloak, Result, TransportResponse};
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct ClientMetadata {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redirect_uris: Vec<Url>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_endpoint_auth_method: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub grant_types: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub response_types: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_uri: Option<Url>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logo_uri: Option<Url>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contacts: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jwks_uri: Option<Url>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ClientRegistration {
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub client_id_issued_at: Option<i64>,
+    pub client_secret_expires_at: Option<i64>,
+    pub registration_access_token: Option<String>,
+    pub registration_client_uri: Option<Url>,
+
+    #[serde(flatten)]
+    pub metadata: ClientMetadata,
+}
+
+impl ReCloak {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self, initial_access_token, metadata))
+    )]
+    pub async fn register_client(
+        &self,
+        initial_access_token: &str,
+        metadata: &ClientMetadata,
+    ) -> Result<ClientRegistration> {
+        let url = self
+            .urls
+            .load()
+            .registration
+            .clone()
+            .ok_or(Error::Unsupported("dynamic client registration"))?;
+        let req = self
+            .client
+            .post(url)
+            .bearer_auth(initial_access_token)
+            .json(metadata);
+
+        self.send_registration(req).await?.json()
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self, metadata), fields(client_id = %registration.client_id))
+    )]
+    pub async fn update_registration(
+        &self,
+        registration: &ClientRegistration,
+        metadata: &ClientMetadata,
+    ) -> Result<ClientRegistration> {
+        #[derive(serde::Serialize)]
+        struct UpdateDto<'a> {
+            client_id: &'a str,
+
+            #[serde(flatten)]
+            metadata: &'a ClientMetadata,
+        }
+
+        let req = self.registration_request(Method::PUT, registration)?.json(
+            &UpdateDto {
+                client_id: &registration.client_id,
+                metadata,
+            },
+        );
+
+        self.send_registration(req).await?.json()
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip(self), fields(client_id = %registration.client_id))
+    )]
+    pub async fn delete_registration(
+        &self,
+        registration: &ClientRegistration,
+    ) -> Result<()> {
+        let req = self.registration_request(Method::DELETE, registration)?;
+
+        self.send_registration(req).await.map(|_| ())
+    }
+
+    fn registration_request(
+        &self,
+        method: Method,
+        registration: &ClientRegistration,
+    ) -> Result<RequestBuilder> {
+        let missing = || crate::Error::Registration {
+            code: "invalid_client_metadata".to_owned(),
+            description: Some(
+                "registration is missing its management credentials".to_owned(),
+            ),
+        };
+
+        let url = registration
+            .registration_client_uri
+            .clone()
+            .ok_or_else(missing)?;
+        let token = registration
+            .registration_access_token
+            .as_deref()
+            .ok_or_else(missing)?;
+
+        Ok(self.client.request(method, url).bearer_auth(token))
+    }
+
+    async fn send_registration(
+        &self,
+        req: RequestBuilder,
+    ) -> Result<TransportResponse> {
+        #[derive(serde::Deserialize)]
+        struct ErrorDto {
+            error: String,
+            error_description: Option<String>,
+        }
+
+        let resp = self.governor.send(req).await?;
+
+        let status = resp.status;
+        if status.is_success() {
+            return Ok(resp);
+        }
+
+        match resp.json::<ErrorDto>() {
+            | Ok(err) => Err(crate::Error::Registration {
+                code: err.error,
+                description: err.error_description,
+            }),
+            | Err(_) => Err(crate::Error::Registration {
+                code: match status {
+                    | StatusCode::UNAUTHORIZED => "invalid_token",
+                    | StatusCode::FORBIDDEN => "insufficient_scope",
+                    | _ => "server_error",
+                }
+                .to_owned(),
+                description: status.canonical_reason().map(str::to_owned),
+            }),
+        }
+    }
+}
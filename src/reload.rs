@@ -0,0 +1,133 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use notify::{Event, RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+
+use crate::{
+    config::ClientSecret,
+    secret::{FileSecret, SecretSource},
+    telemetry,
+    Error,
+    ReCloak,
+    Result,
+};
+
+const SETTLE_DELAY: Duration = Duration::from_millis(100);
+
+impl ReCloak {
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn watch_credentials(&self) -> Result<()> {
+        let files = self.credential_files();
+        if files.is_empty() {
+            return Err(Error::Config(
+                "no credential files are configured".to_owned(),
+            ));
+        }
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(
+            move |event: notify::Result<Event>| {
+                let _ = tx.send(event);
+            },
+        )?;
+
+        let dirs = files
+            .iter()
+            .map(|path| match path.parent() {
+                | Some(dir) if !dir.as_os_str().is_empty() => dir,
+                | _ => Path::new("."),
+            })
+            .collect::<HashSet<_>>();
+        for dir in dirs {
+            watcher.watch(dir, RecursiveMode::NonRecursive)?;
+        }
+
+        telemetry::trace_info!(?files, "watching credential files");
+
+        let mut signing_key = self.signing_key_pem();
+
+        while let Some(event) = rx.recv().await {
+            match event {
+                | Ok(event) if event.kind.is_access() => continue,
+                | Ok(_) => {}
+                | Err(err) => {
+                    telemetry::trace_warn!(error = %err, "credential file watch error");
+                    continue;
+                }
+            }
+
+            tokio::time::sleep(SETTLE_DELAY).await;
+            while rx.try_recv().is_ok() {}
+
+            self.reload_client_secret().await;
+
+            let pem = self.signing_key_pem();
+            if pem != signing_key {
+                self.reload_signing_key();
+                signing_key = pem;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn credential_files(&self) -> Vec<PathBuf> {
+        let config = self.config.load();
+        let mut files = Vec::new();
+
+        if let Some(ref path) = config.client.secret_file {
+            files.push(path.clone());
+        }
+        if let ClientSecret::Jwt(ref key) = config.client.secret {
+            files.push(key.private_key.clone());
+        }
+
+        files
+    }
+
+    async fn reload_client_secret(&self) {
+        let config = self.config.load_full();
+        let Some(ref path) = config.client.secret_file else {
+            return;
+        };
+
+        match FileSecret::new(path).client_secret().await {
+            | Ok(secret) => {
+                if !matches!(config.client.secret, ClientSecret::Basic(ref current) if *current == secret)
+                {
+                    self.update_secret(secret).await;
+                }
+            }
+            | Err(err) => {
+                telemetry::trace_warn!(error = %err, "failed to reload client secret, keeping the current one");
+            }
+        }
+    }
+
+    fn signing_key_pem(&self) -> Option<Vec<u8>> {
+        match self.config.load().client.secret {
+            | ClientSecret::Jwt(ref key) => {
+                std::fs::read(&key.private_key).ok()
+            }
+            | _ => None,
+        }
+    }
+
+    fn reload_signing_key(&self) {
+        let config = self.config.load();
+        let ClientSecret::Jwt(ref key) = config.client.secret else {
+            return;
+        };
+
+        match key.load() {
+            | Ok(_) => telemetry::trace_info!("client signing key reloaded"),
+            | Err(err) => {
+                telemetry::trace_warn!(error = %err, "reloaded client signing key is invalid");
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticationFlowRepresentation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    pub alias: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_id: Option<String>,
+
+    #[serde(default)]
+    pub top_level: bool,
+
+    #[serde(default)]
+    pub built_in: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticationExecutionInfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requirement: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_id: Option<String>,
+
+    #[serde(default)]
+    pub requirement_choices: Vec<String>,
+
+    #[serde(default)]
+    pub configurable: bool,
+
+    #[serde(default)]
+    pub authentication_flow: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flow_id: Option<String>,
+
+    #[serde(default)]
+    pub level: u32,
+
+    #[serde(default)]
+    pub index: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequiredActionProviderRepresentation {
+    pub alias: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_id: Option<String>,
+
+    #[serde(default)]
+    pub enabled: bool,
+
+    #[serde(default)]
+    pub default_action: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+
+    #[serde(default)]
+    pub config: HashMap<String, String>,
+}
@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentRepresentation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_type: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_type: Option<String>,
+
+    #[serde(default)]
+    pub config: HashMap<String, Vec<String>>,
+}
+
+impl ComponentRepresentation {
+    #[inline]
+    pub fn config_value(&self, key: impl AsRef<str>) -> Option<&str> {
+        self.config
+            .get(key.as_ref())
+            .and_then(|values| values.first())
+            .map(String::as_str)
+    }
+
+    #[inline]
+    pub fn set_config_value(
+        &mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) {
+        self.config.insert(key.into(), vec![value.into()]);
+    }
+}
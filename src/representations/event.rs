@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeycloakEvent {
+    Admin(AdminEventRepresentation),
+    User(EventRepresentation),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventRepresentation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    #[serde(default)]
+    pub time: i64,
+
+    #[serde(rename = "type")]
+    pub kind: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub realm_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_address: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub details: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminEventRepresentation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    #[serde(default)]
+    pub time: i64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub realm_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_details: Option<AuthDetailsRepresentation>,
+
+    pub operation_type: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_type: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_path: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub representation: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthDetailsRepresentation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub realm_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_address: Option<String>,
+}
+
+impl KeycloakEvent {
+    #[inline]
+    pub fn realm_id(&self) -> Option<&str> {
+        match self {
+            | KeycloakEvent::Admin(event) => event.realm_id.as_deref(),
+            | KeycloakEvent::User(event) => event.realm_id.as_deref(),
+        }
+    }
+
+    #[inline]
+    pub fn occurred_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        match self {
+            | KeycloakEvent::Admin(event) => timestamp(event.time),
+            | KeycloakEvent::User(event) => timestamp(event.time),
+        }
+    }
+
+    #[inline]
+    pub const fn is_error(&self) -> bool {
+        match self {
+            | KeycloakEvent::Admin(event) => event.error.is_some(),
+            | KeycloakEvent::User(event) => event.error.is_some(),
+        }
+    }
+}
+
+fn timestamp(ms: i64) -> Option<chrono::DateTime<chrono::Utc>> {
+    (ms > 0)
+        .then(|| {
+            chrono::DateTime::from_timestamp(
+                ms.div_euclid(1000),
+                ms.rem_euclid(1000) as u32 * 1_000_000,
+            )
+        })
+        .flatten()
+}
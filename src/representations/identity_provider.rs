@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdentityProviderRepresentation {
+    pub alias: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub internal_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trust_email: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_only: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hide_on_login: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_broker_login_flow_alias: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub organization_id: Option<String>,
+
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub config: HashMap<String, String>,
+}
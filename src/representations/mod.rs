@@ -0,0 +1,49 @@
+mod authentication;
+mod client;
+mod client_scope;
+mod component;
+mod event;
+mod group;
+mod identity_provider;
+mod organization;
+mod realm;
+mod role;
+mod user;
+mod user_profile;
+
+pub use self::{
+    authentication::{
+        AuthenticationExecutionInfo,
+        AuthenticationFlowRepresentation,
+        RequiredActionProviderRepresentation,
+    },
+    client::ClientRepresentation,
+    client_scope::{ClientScopeRepresentation, ProtocolMapperRepresentation},
+    component::ComponentRepresentation,
+    event::{
+        AdminEventRepresentation,
+        AuthDetailsRepresentation,
+        EventRepresentation,
+        KeycloakEvent,
+    },
+    group::GroupRepresentation,
+    identity_provider::IdentityProviderRepresentation,
+    organization::{
+        MemberRepresentation,
+        MembershipType,
+        OrganizationDomainRepresentation,
+        OrganizationRepresentation,
+    },
+    realm::{RealmRepresentation, RolesRepresentation},
+    role::RoleRepresentation,
+    user::{CredentialRepresentation, UserRepresentation},
+    user_profile::{
+        AttributePermissions,
+        AttributeRequired,
+        AttributeSelector,
+        UnmanagedAttributePolicy,
+        UserProfileAttribute,
+        UserProfileConfig,
+        UserProfileGroup,
+    },
+};
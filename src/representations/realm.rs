@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    ClientRepresentation,
+    ClientScopeRepresentation,
+    GroupRepresentation,
+    RoleRepresentation,
+    UserRepresentation,
+};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RealmRepresentation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    pub realm: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ssl_required: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registration_allowed: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub login_with_email_allowed: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password_policy: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roles: Option<RolesRepresentation>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub client_scopes: Vec<ClientScopeRepresentation>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub clients: Vec<ClientRepresentation>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<GroupRepresentation>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub users: Vec<UserRepresentation>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub identity_providers: Vec<serde_json::Value>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RolesRepresentation {
+    #[serde(default)]
+    pub realm: Vec<RoleRepresentation>,
+
+    #[serde(default)]
+    pub client: HashMap<String, Vec<RoleRepresentation>>,
+}
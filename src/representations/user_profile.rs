@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserProfileConfig {
+    #[serde(default)]
+    pub attributes: Vec<UserProfileAttribute>,
+
+    #[serde(default)]
+    pub groups: Vec<UserProfileGroup>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unmanaged_attribute_policy: Option<UnmanagedAttributePolicy>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserProfileAttribute {
+    pub name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub validations: HashMap<String, HashMap<String, serde_json::Value>>,
+
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub annotations: HashMap<String, serde_json::Value>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required: Option<AttributeRequired>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<AttributePermissions>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<AttributeSelector>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+
+    #[serde(default)]
+    pub multivalued: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeRequired {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roles: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributePermissions {
+    #[serde(default)]
+    pub view: Vec<String>,
+
+    #[serde(default)]
+    pub edit: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributeSelector {
+    #[serde(default)]
+    pub scopes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserProfileGroup {
+    pub name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_header: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_description: Option<String>,
+
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub annotations: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum UnmanagedAttributePolicy {
+    Enabled,
+    AdminView,
+    AdminEdit,
+}
+
+impl UserProfileConfig {
+    #[inline]
+    pub fn attribute(
+        &self,
+        name: impl AsRef<str>,
+    ) -> Option<&UserProfileAttribute> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.name == name.as_ref())
+    }
+
+    pub fn set_attribute(&mut self, attribute: UserProfileAttribute) {
+        match self
+            .attributes
+            .iter_mut()
+            .find(|existing| existing.name == attribute.name)
+        {
+            | Some(existing) => *existing = attribute,
+            | None => self.attributes.push(attribute),
+        }
+    }
+
+    #[inline]
+    pub fn remove_attribute(&mut self, name: impl AsRef<str>) -> bool {
+        let len = self.attributes.len();
+        self.attributes
+            .retain(|attribute| attribute.name != name.as_ref());
+
+        self.attributes.len() != len
+    }
+
+    #[inline]
+    pub fn group(&self, name: impl AsRef<str>) -> Option<&UserProfileGroup> {
+        self.groups.iter().find(|group| group.name == name.as_ref())
+    }
+
+    pub fn set_group(&mut self, group: UserProfileGroup) {
+        match self
+            .groups
+            .iter_mut()
+            .find(|existing| existing.name == group.name)
+        {
+            | Some(existing) => *existing = group,
+            | None => self.groups.push(group),
+        }
+    }
+}
+
+impl UserProfileAttribute {
+    #[inline]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    #[inline]
+    pub fn validation(
+        mut self,
+        validator: impl Into<String>,
+        options: HashMap<String, serde_json::Value>,
+    ) -> Self {
+        self.validations.insert(validator.into(), options);
+        self
+    }
+}
@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use ::reqwest_middleware::{Middleware, Next};
+use http::Extensions;
+use reqwest::{header::AUTHORIZATION, Request, Response, StatusCode};
+
+use crate::{telemetry, TokenRequirements};
+
+#[derive(Debug, Clone)]
+pub struct KeycloakAuth {
+    kc: Arc<crate::ReCloak>,
+    retry_unauthorized: bool,
+}
+
+impl KeycloakAuth {
+    #[inline]
+    pub const fn new(kc: Arc<crate::ReCloak>) -> Self {
+        Self {
+            kc,
+            retry_unauthorized: true,
+        }
+    }
+
+    #[inline]
+    pub const fn retry_unauthorized(mut self, retry: bool) -> Self {
+        self.retry_unauthorized = retry;
+        self
+    }
+
+    async fn authorize(&self, req: &mut Request, extensions: &Extensions) {
+        let token = match extensions.get::<TokenRequirements>() {
+            | Some(requirements) => {
+                self.kc.authenticate_for(requirements).await
+            }
+            | None => self.kc.authenticate().await,
+        };
+
+        match token {
+            | Ok(token) => match format!("Bearer {token}").try_into() {
+                | Ok(value) => {
+                    req.headers_mut().insert(AUTHORIZATION, value);
+                }
+                | Err(err) => {
+                    telemetry::trace_error!(error = %err, "token is not a valid header value, proceeding without token");
+                }
+            },
+            | Err(err) => {
+                telemetry::trace_error!(error = %err, "failed to authenticate, proceeding without token");
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for KeycloakAuth {
+    async fn handle(
+        &self,
+        mut req: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> ::reqwest_middleware::Result<Response> {
+        self.authorize(&mut req, extensions).await;
+
+        let retry = self.retry_unauthorized.then(|| req.try_clone()).flatten();
+
+        let resp = next.clone().run(req, extensions).await?;
+        if resp.status() != StatusCode::UNAUTHORIZED {
+            return Ok(resp);
+        }
+
+        match retry {
+            | Some(mut retry) => {
+                telemetry::trace_debug!(
+                    "request unauthorized, retrying with a fresh token"
+                );
+
+                self.kc.invalidate_token().await;
+                self.authorize(&mut retry, extensions).await;
+
+                next.run(retry, extensions).await
+            }
+            | None => Ok(resp),
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use tokio::time::{Instant, MissedTickBehavior};
+
+use crate::{config::ClientSecret, telemetry, Error, ReCloak, Result};
+
+impl ReCloak {
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub async fn rotate_client_secret(&self, grace: Duration) -> Result<()> {
+        let config = self.config.load_full();
+        if !matches!(config.client.secret, ClientSecret::Basic(_)) {
+            return Err(Error::Config(
+                "client does not authenticate with a client secret".to_owned(),
+            ));
+        }
+
+        if let Some(ref source) = self.secret_source {
+            if !source.is_writable() {
+                return Err(Error::SecretSource(
+                    "secret source is read-only".to_owned(),
+                ));
+            }
+        }
+
+        let admin = self.admin();
+        let client_uuid =
+            admin.client_uuid(&config.client.id).await?.ok_or_else(|| {
+                Error::Config(format!(
+                    "client `{}` does not exist",
+                    config.client.id
+                ))
+            })?;
+
+        let secret = admin
+            .regenerate_client_secret(&client_uuid)
+            .await?
+            .value
+            .ok_or_else(|| {
+                Error::SecretSource(
+                    "keycloak returned no client secret".to_owned(),
+                )
+            })?;
+
+        if let Some(ref source) = self.secret_source {
+            source.store_client_secret(&secret).await?;
+        }
+
+        self.update_secret(secret).await;
+
+        telemetry::trace_info!(
+            ?grace,
+            "client secret regenerated, retiring the previous one after the \
+             grace window"
+        );
+
+        tokio::time::sleep(grace).await;
+
+        match self
+            .admin()
+            .invalidate_rotated_client_secret(&client_uuid)
+            .await
+        {
+            | Ok(()) => telemetry::trace_info!("previous client secret retired"),
+            | Err(Error::Admin {
+                status: StatusCode::NOT_FOUND,
+                ..
+            }) => telemetry::trace_debug!("no rotated client secret to retire"),
+            | Err(err) => return Err(err),
+        }
+
+        Ok(())
+    }
+
+    pub async fn run_secret_rotation(&self, every: Duration, grace: Duration) {
+        let mut interval =
+            tokio::time::interval_at(Instant::now() + every, every);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(err) = self.rotate_client_secret(grace).await {
+                telemetry::trace_error!(error = %err, "client secret rotation failed");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use http::{Method, StatusCode};
+    use serde_json::json;
+
+    use crate::{
+        config::ClientSecret,
+        secret::{SecretFuture, SecretSource},
+        testing,
+        Error,
+        TransportResponse,
+    };
+
+    const TOKEN_PATH: &str = "/realms/test/protocol/openid-connect/token";
+    const CLIENTS_PATH: &str = "/admin/realms/test/clients";
+    const SECRET_PATH: &str = "/admin/realms/test/clients/c0ffee/client-secret";
+    const ROTATED_PATH: &str =
+        "/admin/realms/test/clients/c0ffee/client-secret/rotated";
+
+    #[derive(Debug, Default)]
+    struct MemorySecret {
+        secret: Mutex<Option<String>>,
+        writable: bool,
+    }
+
+    impl SecretSource for MemorySecret {
+        fn client_secret(&self) -> SecretFuture<'_> {
+            let secret = self.secret.lock().unwrap().clone();
+
+            Box::pin(async move {
+                Ok(secret.unwrap_or_else(|| testing::SECRET.to_owned()))
+            })
+        }
+
+        fn is_writable(&self) -> bool {
+            self.writable
+        }
+
+        fn store_client_secret<'a>(
+            &'a self,
+            secret: &'a str,
+        ) -> SecretFuture<'a, ()> {
+            *self.secret.lock().unwrap() = Some(secret.to_owned());
+
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    fn admin_routes(transport: &testing::MockTransport) {
+        transport.route(
+            TOKEN_PATH,
+            json!({ "access_token": "admin-token", "expires_in": 300 }),
+        );
+        transport.route(CLIENTS_PATH, json!([{ "id": "c0ffee" }]));
+        transport.route(
+            SECRET_PATH,
+            json!({ "type": "secret", "value": "rotated-secret" }),
+        );
+        transport.respond(
+            ROTATED_PATH,
+            TransportResponse::new(StatusCode::NO_CONTENT, ""),
+        );
+    }
+
+    #[tokio::test]
+    async fn rotates_stores_and_retires_the_secret() {
+        let source = Arc::new(MemorySecret {
+            writable: true,
+            ..Default::default()
+        });
+        let (builder, transport) = testing::builder(testing::config());
+        let kc = builder.secret_source(source.clone()).build().await.unwrap();
+        admin_routes(&transport);
+
+        kc.rotate_client_secret(Duration::ZERO).await.unwrap();
+
+        assert!(matches!(
+            kc.config.load().client.secret,
+            ClientSecret::Basic(ref secret) if secret == "rotated-secret"
+        ));
+        assert_eq!(
+            source.secret.lock().unwrap().as_deref(),
+            Some("rotated-secret")
+        );
+
+        let requests = transport.requests();
+        let regenerated = requests
+            .iter()
+            .position(|req| req.url.path() == SECRET_PATH)
+            .unwrap();
+        let retired = requests
+            .iter()
+            .position(|req| req.url.path() == ROTATED_PATH)
+            .unwrap();
+        assert_eq!(requests[regenerated].method, Method::POST);
+        assert_eq!(requests[retired].method, Method::DELETE);
+
+        // the retirement call authenticates with the new secret.
+        let reauthenticated = requests[regenerated..retired]
+            .iter()
+            .filter(|req| req.url.path() == TOKEN_PATH)
+            .any(|req| {
+                let body = req.body.as_deref().unwrap_or_default();
+                String::from_utf8_lossy(body).contains("rotated-secret")
+            });
+        assert!(reauthenticated);
+    }
+
+    #[tokio::test]
+    async fn tolerates_a_missing_rotated_secret() {
+        let (builder, transport) = testing::builder(testing::config());
+        let kc = builder.build().await.unwrap();
+        admin_routes(&transport);
+        transport.respond(
+            ROTATED_PATH,
+            TransportResponse::new(StatusCode::NOT_FOUND, ""),
+        );
+
+        kc.rotate_client_secret(Duration::ZERO).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn refuses_read_only_sources() {
+        let (builder, transport) = testing::builder(testing::config());
+        let kc = builder
+            .secret_source(MemorySecret::default())
+            .build()
+            .await
+            .unwrap();
+        admin_routes(&transport);
+
+        let err = kc.rotate_client_secret(Duration::ZERO).await.unwrap_err();
+        assert!(matches!(err, Error::SecretSource(_)));
+        assert!(transport
+            .requests()
+            .iter()
+            .all(|req| req.url.path() != SECRET_PATH));
+    }
+}
@@ -0,0 +1,425 @@
+use std::{
+    fmt::{self, Write},
+    sync::Arc,
+};
+
+use reqwest::{
+    header::{HeaderName, HeaderValue, AUTHORIZATION},
+    Method,
+};
+use ring::{digest, hmac};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::{SecretFuture, SecretSource};
+use crate::{
+    Clock,
+    Error,
+    HttpTransport,
+    Result,
+    SystemClock,
+    TransportRequest,
+};
+
+const SERVICE: &str = "secretsmanager";
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+const CONTENT_TYPE: &str = "application/x-amz-json-1.1";
+const TARGET: &str = "secretsmanager.GetSecretValue";
+
+#[derive(Clone)]
+pub struct AwsCredentials {
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AwsSecretsManager {
+    transport: Arc<dyn HttpTransport>,
+    endpoint: url::Url,
+    region: String,
+    secret_id: String,
+    field: Option<String>,
+    credentials: AwsCredentials,
+    clock: Arc<dyn Clock>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SecretValue {
+    #[serde(rename = "SecretString")]
+    secret_string: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AwsError {
+    #[serde(rename = "__type")]
+    kind: Option<String>,
+    #[serde(alias = "Message")]
+    message: Option<String>,
+}
+
+impl AwsCredentials {
+    #[inline]
+    pub fn new(
+        access_key_id: impl Into<String>,
+        secret_access_key: impl Into<String>,
+    ) -> Self {
+        Self {
+            access_key_id: access_key_id.into(),
+            secret_access_key: secret_access_key.into(),
+            session_token: None,
+        }
+    }
+
+    #[inline]
+    pub fn session_token(mut self, token: impl Into<String>) -> Self {
+        self.session_token = Some(token.into());
+        self
+    }
+
+    pub fn from_env() -> Result<Self> {
+        let credentials =
+            Self::new(env("AWS_ACCESS_KEY_ID")?, env("AWS_SECRET_ACCESS_KEY")?);
+
+        Ok(match std::env::var("AWS_SESSION_TOKEN") {
+            | Ok(token) => credentials.session_token(token),
+            | Err(_) => credentials,
+        })
+    }
+}
+
+impl fmt::Debug for AwsCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AwsCredentials")
+            .field("access_key_id", &self.access_key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AwsSecretsManager {
+    pub fn new(
+        region: impl Into<String>,
+        secret_id: impl Into<String>,
+        credentials: AwsCredentials,
+    ) -> Result<Self> {
+        let region = region.into();
+        let endpoint =
+            format!("https://{SERVICE}.{region}.amazonaws.com/").parse()?;
+
+        Ok(Self {
+            transport: Arc::new(reqwest::Client::new()),
+            endpoint,
+            region,
+            secret_id: secret_id.into(),
+            field: None,
+            credentials,
+            clock: Arc::new(SystemClock),
+        })
+    }
+
+    pub fn from_env(secret_id: impl Into<String>) -> Result<Self> {
+        let region =
+            env("AWS_REGION").or_else(|_| env("AWS_DEFAULT_REGION"))?;
+
+        Self::new(region, secret_id, AwsCredentials::from_env()?)
+    }
+
+    #[inline]
+    pub fn transport(mut self, transport: impl HttpTransport) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    #[inline]
+    pub fn clock(mut self, clock: impl Clock) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    #[inline]
+    pub fn endpoint(mut self, endpoint: url::Url) -> Self {
+        self.endpoint = endpoint;
+        self
+    }
+
+    #[inline]
+    pub fn field(mut self, field: impl Into<String>) -> Self {
+        self.field = Some(field.into());
+        self
+    }
+
+    async fn read(&self) -> Result<String> {
+        let body = json!({ "SecretId": self.secret_id }).to_string();
+        let now = self.clock.now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let host = match self.endpoint.port() {
+            | Some(port) => {
+                format!(
+                    "{}:{port}",
+                    self.endpoint.host_str().unwrap_or_default()
+                )
+            }
+            | None => self.endpoint.host_str().unwrap_or_default().to_owned(),
+        };
+
+        let mut headers: Vec<(&'static str, &str)> = vec![
+            ("content-type", CONTENT_TYPE),
+            ("host", host.as_str()),
+            ("x-amz-date", amz_date.as_str()),
+        ];
+        if let Some(ref token) = self.credentials.session_token {
+            headers.push(("x-amz-security-token", token));
+        }
+        headers.push(("x-amz-target", TARGET));
+
+        let authorization = authorization(
+            &self.credentials,
+            &self.region,
+            SERVICE,
+            self.endpoint.path(),
+            &now,
+            &headers,
+            &body,
+        );
+        let mut req =
+            TransportRequest::new(Method::POST, self.endpoint.clone());
+        req.headers
+            .insert(AUTHORIZATION, header_value(&authorization)?);
+        for (name, value) in headers.iter().filter(|(name, _)| *name != "host")
+        {
+            req.headers
+                .insert(HeaderName::from_static(name), header_value(value)?);
+        }
+        req.body = Some(body.into());
+
+        let resp = self.transport.execute(req).await?;
+        if !resp.status.is_success() {
+            let status = resp.status;
+            let err = resp.json::<AwsError>().ok();
+
+            return Err(Error::SecretSource(format!(
+                "secrets manager responded with status {status}: {}",
+                err.and_then(|err| err.message.or(err.kind))
+                    .unwrap_or_default()
+            )));
+        }
+
+        let secret =
+            resp.json::<SecretValue>()?.secret_string.ok_or_else(|| {
+                Error::SecretSource("secret has no string value".to_owned())
+            })?;
+
+        match self.field {
+            | Some(ref field) => {
+                let value = serde_json::from_str(&secret).map_err(|err| {
+                    Error::SecretSource(format!(
+                        "secret is not a json object: {err}"
+                    ))
+                })?;
+
+                super::field(&value, field)
+            }
+            | None => Ok(secret),
+        }
+    }
+}
+
+impl SecretSource for AwsSecretsManager {
+    #[inline]
+    fn client_secret(&self) -> SecretFuture<'_> {
+        Box::pin(self.read())
+    }
+}
+
+fn env(name: &str) -> Result<String> {
+    std::env::var(name).map_err(|_| {
+        Error::Config(format!("missing environment variable {name}"))
+    })
+}
+
+fn authorization(
+    credentials: &AwsCredentials,
+    region: &str,
+    service: &str,
+    path: &str,
+    now: &chrono::DateTime<chrono::Utc>,
+    headers: &[(&str, &str)],
+    body: &str,
+) -> String {
+    let date = now.format("%Y%m%d").to_string();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ");
+    let scope = format!("{date}/{region}/{service}/aws4_request");
+
+    let signed_headers = headers
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(";");
+    let canonical_headers =
+        headers
+            .iter()
+            .fold(String::new(), |mut acc, (name, value)| {
+                let _ = writeln!(acc, "{name}:{}", value.trim());
+                acc
+            });
+
+    let canonical_request = format!(
+        "POST\n{path}\n\n{canonical_headers}\n{signed_headers}\n{}",
+        hex(digest::digest(&digest::SHA256, body.as_bytes()).as_ref()),
+    );
+    let string_to_sign = format!(
+        "{ALGORITHM}\n{amz_date}\n{scope}\n{}",
+        hex(
+            digest::digest(&digest::SHA256, canonical_request.as_bytes())
+                .as_ref()
+        ),
+    );
+
+    let key = [date.as_str(), region, service, "aws4_request"]
+        .into_iter()
+        .fold(
+            format!("AWS4{}", credentials.secret_access_key).into_bytes(),
+            |key, part| sign(&key, part.as_bytes()),
+        );
+    let signature = hex(&sign(&key, string_to_sign.as_bytes()));
+
+    format!(
+        "{ALGORITHM} Credential={}/{scope}, \
+         SignedHeaders={signed_headers}, Signature={signature}",
+        credentials.access_key_id,
+    )
+}
+
+fn header_value(value: &str) -> Result<HeaderValue> {
+    HeaderValue::from_str(value).map_err(|_| {
+        Error::SecretSource("aws header is not a valid value".to_owned())
+    })
+}
+
+fn sign(key: &[u8], data: &[u8]) -> Vec<u8> {
+    hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, key), data)
+        .as_ref()
+        .to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut acc, byte| {
+        let _ = write!(acc, "{byte:02x}");
+        acc
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::TimeZone;
+    use reqwest::{header::AUTHORIZATION, StatusCode};
+    use serde_json::json;
+
+    use super::{authorization, AwsCredentials, AwsSecretsManager};
+    use crate::{
+        secret::SecretSource,
+        testing::{FixedClock, MockTransport},
+        Clock,
+        Error,
+        TransportResponse,
+    };
+
+    fn credentials() -> AwsCredentials {
+        AwsCredentials::new(
+            "AKIDEXAMPLE",
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+        )
+    }
+
+    fn source(transport: &Arc<MockTransport>) -> AwsSecretsManager {
+        AwsSecretsManager::new("eu-west-1", "kc/app", credentials())
+            .unwrap()
+            .endpoint("https://secretsmanager.test/".parse().unwrap())
+            .transport(transport.clone())
+    }
+
+    // post-vanilla from the aws signature version 4 test suite
+    #[test]
+    fn signs_post_vanilla() {
+        let now = chrono::Utc
+            .with_ymd_and_hms(2015, 8, 30, 12, 36, 0)
+            .unwrap();
+        let headers = [
+            ("host", "example.amazonaws.com"),
+            ("x-amz-date", "20150830T123600Z"),
+        ];
+
+        assert_eq!(
+            authorization(
+                &credentials(),
+                "us-east-1",
+                "service",
+                "/",
+                &now,
+                &headers,
+                "",
+            ),
+            "AWS4-HMAC-SHA256 \
+             Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+             SignedHeaders=host;x-amz-date, \
+             Signature=5da7c1a2acd57cee7505fc6676e4e544\
+             621c30862966e37dddb68e92efbe5d6b"
+        );
+    }
+
+    #[tokio::test]
+    async fn reads_signed_secret_field() {
+        let transport = Arc::new(MockTransport::new());
+        transport.route(
+            "/",
+            json!({ "SecretString": r#"{"client_secret":"rotated"}"# }),
+        );
+        let clock = FixedClock::new();
+        let source = source(&transport)
+            .clock(clock.clone())
+            .field("client_secret");
+
+        assert_eq!(source.client_secret().await.unwrap(), "rotated");
+
+        let now = clock.now();
+        let requests = transport.requests();
+        let headers = &requests[0].headers;
+        assert_eq!(
+            headers["x-amz-date"],
+            now.format("%Y%m%dT%H%M%SZ").to_string()
+        );
+        assert_eq!(headers["x-amz-target"], "secretsmanager.GetSecretValue");
+        assert!(headers[AUTHORIZATION].to_str().unwrap().starts_with(
+            &format!(
+                "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/{}/eu-west-1/\
+                 secretsmanager/aws4_request, \
+                 SignedHeaders=content-type;host;x-amz-date;x-amz-target, ",
+                now.format("%Y%m%d")
+            )
+        ));
+    }
+
+    #[tokio::test]
+    async fn reports_service_errors() {
+        let transport = Arc::new(MockTransport::new());
+        transport.respond(
+            "/",
+            TransportResponse::new(
+                StatusCode::BAD_REQUEST,
+                json!({
+                    "__type": "ResourceNotFoundException",
+                    "Message": "secret not found",
+                })
+                .to_string(),
+            ),
+        );
+
+        let err = source(&transport).client_secret().await.unwrap_err();
+        let Error::SecretSource(message) = err else {
+            panic!("unexpected error: {err}");
+        };
+        assert!(message.ends_with("secret not found"));
+    }
+}
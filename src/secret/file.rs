@@ -0,0 +1,47 @@
+use std::path::{Path, PathBuf};
+
+use super::{SecretFuture, SecretSource};
+use crate::{Error, Result};
+
+#[derive(Debug, Clone)]
+pub struct FileSecret {
+    path: PathBuf,
+}
+
+impl FileSecret {
+    #[inline]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl SecretSource for FileSecret {
+    #[inline]
+    fn client_secret(&self) -> SecretFuture<'_> {
+        Box::pin(async { read(&self.path) })
+    }
+}
+
+pub(crate) fn read(path: &Path) -> Result<String> {
+    let secret = std::fs::read_to_string(path).map_err(|err| {
+        Error::SecretSource(format!(
+            "failed to read `{}`: {err}",
+            path.display()
+        ))
+    })?;
+
+    let secret = secret.trim();
+    if secret.is_empty() {
+        return Err(Error::SecretSource(format!(
+            "`{}` is empty",
+            path.display()
+        )));
+    }
+
+    Ok(secret.to_owned())
+}
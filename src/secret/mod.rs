@@ -0,0 +1,105 @@
+#[cfg(feature = "aws-secrets-manager")]
+mod aws;
+mod file;
+#[cfg(feature = "vault")]
+mod vault;
+
+use std::{fmt, future::Future, pin::Pin, sync::Arc};
+
+#[cfg(feature = "aws-secrets-manager")]
+pub use self::aws::{AwsCredentials, AwsSecretsManager};
+pub use self::file::FileSecret;
+#[cfg(feature = "vault")]
+pub use self::vault::VaultSecret;
+use crate::{config::ClientSecret, telemetry, Result};
+
+pub type SecretFuture<'a, T = String> =
+    Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>>;
+
+pub trait SecretSource: fmt::Debug + Send + Sync + 'static {
+    fn client_secret(&self) -> SecretFuture<'_>;
+
+    #[inline]
+    fn is_writable(&self) -> bool {
+        false
+    }
+
+    fn store_client_secret<'a>(
+        &'a self,
+        _secret: &'a str,
+    ) -> SecretFuture<'a, ()> {
+        Box::pin(async {
+            Err(crate::Error::SecretSource(
+                "secret source is read-only".to_owned(),
+            ))
+        })
+    }
+}
+
+impl<S> SecretSource for Arc<S>
+where
+    S: SecretSource + ?Sized,
+{
+    #[inline]
+    fn client_secret(&self) -> SecretFuture<'_> {
+        (**self).client_secret()
+    }
+
+    #[inline]
+    fn is_writable(&self) -> bool {
+        (**self).is_writable()
+    }
+
+    #[inline]
+    fn store_client_secret<'a>(
+        &'a self,
+        secret: &'a str,
+    ) -> SecretFuture<'a, ()> {
+        (**self).store_client_secret(secret)
+    }
+}
+
+#[cfg(any(feature = "vault", feature = "aws-secrets-manager"))]
+fn field(value: &serde_json::Value, field: &str) -> Result<String> {
+    value
+        .get(field)
+        .and_then(serde_json::Value::as_str)
+        .map(ToOwned::to_owned)
+        .ok_or_else(|| {
+            crate::Error::SecretSource(format!(
+                "secret has no string field `{field}`"
+            ))
+        })
+}
+
+impl crate::ReCloak {
+    pub(crate) async fn refresh_secret(&self) -> Result<Arc<crate::Config>> {
+        let config = self.config.load_full();
+        let Some(ref source) = self.secret_source else {
+            return Ok(config);
+        };
+
+        let secret = match source.client_secret().await {
+            | Ok(secret) => secret,
+            | Err(err) if !config.client.secret.is_public() => {
+                telemetry::trace_warn!(error = %err, "failed to fetch client secret, using the current one");
+                return Ok(config);
+            }
+            | Err(err) => return Err(err),
+        };
+
+        if matches!(config.client.secret, ClientSecret::Basic(ref current) if *current == secret)
+        {
+            return Ok(config);
+        }
+
+        let mut updated = crate::Config::clone(&config);
+        updated.client.secret = ClientSecret::Basic(secret);
+        let updated = Arc::new(updated);
+        self.config.store(updated.clone());
+
+        telemetry::trace_info!("client secret rotated from secret source");
+
+        Ok(updated)
+    }
+}
@@ -0,0 +1,237 @@
+use std::sync::Arc;
+
+use reqwest::{
+    header::{HeaderName, HeaderValue, CONTENT_TYPE},
+    Method,
+};
+use serde::Deserialize;
+
+use super::{SecretFuture, SecretSource};
+use crate::{Error, HttpTransport, Result, TransportRequest};
+
+const DEFAULT_MOUNT: &str = "secret";
+const DEFAULT_FIELD: &str = "client_secret";
+const TOKEN_HEADER: HeaderName = HeaderName::from_static("x-vault-token");
+const NAMESPACE_HEADER: HeaderName =
+    HeaderName::from_static("x-vault-namespace");
+
+#[derive(Debug, Clone)]
+pub struct VaultSecret {
+    transport: Arc<dyn HttpTransport>,
+    server: url::Url,
+    token: String,
+    namespace: Option<String>,
+    mount: String,
+    path: String,
+    field: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct KvResponse {
+    data: KvData,
+}
+
+#[derive(Debug, Deserialize)]
+struct KvData {
+    data: serde_json::Value,
+}
+
+impl VaultSecret {
+    #[inline]
+    pub fn new(
+        server: &url::Url,
+        token: impl Into<String>,
+        path: impl Into<String>,
+    ) -> Self {
+        Self {
+            transport: Arc::new(reqwest::Client::new()),
+            server: server.clone(),
+            token: token.into(),
+            namespace: None,
+            mount: DEFAULT_MOUNT.to_owned(),
+            path: path.into(),
+            field: DEFAULT_FIELD.to_owned(),
+        }
+    }
+
+    #[inline]
+    pub fn transport(mut self, transport: impl HttpTransport) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    #[inline]
+    pub fn namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    #[inline]
+    pub fn mount(mut self, mount: impl Into<String>) -> Self {
+        self.mount = mount.into();
+        self
+    }
+
+    #[inline]
+    pub fn field(mut self, field: impl Into<String>) -> Self {
+        self.field = field.into();
+        self
+    }
+
+    fn request(&self, method: Method) -> Result<TransportRequest> {
+        let mut url = self.server.clone();
+        url.path_segments_mut()
+            .map_err(|_| url::ParseError::RelativeUrlWithoutBase)?
+            .pop_if_empty()
+            .extend(["v1", self.mount.trim_matches('/'), "data"])
+            .extend(self.path.split('/').filter(|s| !s.is_empty()));
+
+        let mut req = TransportRequest::new(method, url);
+        req.headers.insert(TOKEN_HEADER, header_value(&self.token)?);
+        if let Some(ref namespace) = self.namespace {
+            req.headers
+                .insert(NAMESPACE_HEADER, header_value(namespace)?);
+        }
+
+        Ok(req)
+    }
+
+    async fn read_data(&self) -> Result<serde_json::Value> {
+        let resp = self.transport.execute(self.request(Method::GET)?).await?;
+        if !resp.status.is_success() {
+            return Err(Error::SecretSource(format!(
+                "vault responded with status {}",
+                resp.status
+            )));
+        }
+
+        let kv = resp.json::<KvResponse>()?;
+
+        Ok(kv.data.data)
+    }
+
+    async fn read(&self) -> Result<String> {
+        super::field(&self.read_data().await?, &self.field)
+    }
+
+    async fn write(&self, secret: &str) -> Result<()> {
+        let mut data = self.read_data().await?;
+        let Some(fields) = data.as_object_mut() else {
+            return Err(Error::SecretSource(
+                "vault secret data is not an object".to_owned(),
+            ));
+        };
+        fields.insert(self.field.clone(), secret.into());
+
+        let mut req = self.request(Method::POST)?;
+        req.headers
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        req.body = Some(serde_json::json!({ "data": data }).to_string().into());
+
+        let resp = self.transport.execute(req).await?;
+        if !resp.status.is_success() {
+            return Err(Error::SecretSource(format!(
+                "vault responded with status {}",
+                resp.status
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl SecretSource for VaultSecret {
+    #[inline]
+    fn client_secret(&self) -> SecretFuture<'_> {
+        Box::pin(self.read())
+    }
+
+    #[inline]
+    fn is_writable(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn store_client_secret<'a>(
+        &'a self,
+        secret: &'a str,
+    ) -> SecretFuture<'a, ()> {
+        Box::pin(self.write(secret))
+    }
+}
+
+fn header_value(value: &str) -> Result<HeaderValue> {
+    HeaderValue::from_str(value).map_err(|_| {
+        Error::SecretSource("vault header is not a valid value".to_owned())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use reqwest::{Method, StatusCode};
+    use serde_json::{json, Value};
+
+    use super::VaultSecret;
+    use crate::{
+        secret::SecretSource,
+        testing::MockTransport,
+        Error,
+        TransportResponse,
+    };
+
+    const DATA_PATH: &str = "/v1/secret/data/kc/app";
+
+    fn source(transport: &Arc<MockTransport>) -> VaultSecret {
+        VaultSecret::new(
+            &"https://vault.test".parse().unwrap(),
+            "vault-token",
+            "kc/app",
+        )
+        .namespace("team")
+        .transport(transport.clone())
+    }
+
+    #[tokio::test]
+    async fn round_trips_the_client_secret() {
+        let transport = Arc::new(MockTransport::new());
+        transport.route(
+            DATA_PATH,
+            json!({
+                "data": { "data": { "client_secret": "s1", "other": "x" } },
+            }),
+        );
+        let source = source(&transport);
+
+        assert_eq!(source.client_secret().await.unwrap(), "s1");
+        source.store_client_secret("s2").await.unwrap();
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests.iter().all(|req| {
+            req.headers["x-vault-token"] == "vault-token"
+                && req.headers["x-vault-namespace"] == "team"
+        }));
+
+        let write = &requests[2];
+        assert_eq!(write.method, Method::POST);
+        assert_eq!(
+            serde_json::from_slice::<Value>(write.body.as_deref().unwrap())
+                .unwrap(),
+            json!({ "data": { "client_secret": "s2", "other": "x" } })
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_vault_errors() {
+        let transport = Arc::new(MockTransport::new());
+        transport.respond(
+            DATA_PATH,
+            TransportResponse::new(StatusCode::FORBIDDEN, ""),
+        );
+
+        let err = source(&transport).client_secret().await.unwrap_err();
+        assert!(matches!(err, Error::SecretSource(_)));
+    }
+}
@@ -0,0 +1,136 @@
+use std::fmt;
+
+use crate::{config::LoggingConfig, Claims};
+
+pub(crate) const TOKEN_TARGET: &str = "kc_rs::token";
+#[cfg(any(feature = "middleware", feature = "lambda"))]
+pub(crate) const MIDDLEWARE_TARGET: &str = "kc_rs::middleware";
+
+macro_rules! trace_error {
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "tracing")]
+        tracing::error!($($arg)+);
+        #[cfg(not(feature = "tracing"))]
+        crate::telemetry::discard!($($arg)+);
+    }};
+}
+
+macro_rules! trace_warn {
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "tracing")]
+        tracing::warn!($($arg)+);
+        #[cfg(not(feature = "tracing"))]
+        crate::telemetry::discard!($($arg)+);
+    }};
+}
+
+macro_rules! trace_info {
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "tracing")]
+        tracing::info!($($arg)+);
+        #[cfg(not(feature = "tracing"))]
+        crate::telemetry::discard!($($arg)+);
+    }};
+}
+
+macro_rules! trace_debug {
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)+);
+        #[cfg(not(feature = "tracing"))]
+        crate::telemetry::discard!($($arg)+);
+    }};
+}
+
+// consumes event arguments so they stay used when tracing is compiled out.
+#[cfg(not(feature = "tracing"))]
+macro_rules! discard {
+    (target: $target:expr, $($rest:tt)+) => {
+        let _ = &$target;
+        crate::telemetry::discard!($($rest)+);
+    };
+    ($field:ident = % $value:expr, $($rest:tt)+) => {
+        let _ = &$value;
+        crate::telemetry::discard!($($rest)+);
+    };
+    ($field:ident = ? $value:expr, $($rest:tt)+) => {
+        let _ = &$value;
+        crate::telemetry::discard!($($rest)+);
+    };
+    ($field:ident = $value:expr, $($rest:tt)+) => {
+        let _ = &$value;
+        crate::telemetry::discard!($($rest)+);
+    };
+    (% $($field:ident).+, $($rest:tt)+) => {
+        let _ = &$($field).+;
+        crate::telemetry::discard!($($rest)+);
+    };
+    (? $($field:ident).+, $($rest:tt)+) => {
+        let _ = &$($field).+;
+        crate::telemetry::discard!($($rest)+);
+    };
+    ($($field:ident).+, $($rest:tt)+) => {
+        let _ = &$($field).+;
+        crate::telemetry::discard!($($rest)+);
+    };
+    ($message:literal $(, $arg:expr)* $(,)?) => {
+        $(let _ = &$arg;)*
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) use discard;
+pub(crate) use trace_debug;
+pub(crate) use trace_error;
+pub(crate) use trace_info;
+pub(crate) use trace_warn;
+
+const REDACTED: &str = "[redacted]";
+const MISSING: &str = "-";
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Redacted<T> {
+    Shown(T),
+    Hidden,
+    Missing,
+}
+
+impl crate::ReCloak {
+    #[inline]
+    pub(crate) fn logging(&self) -> LoggingConfig {
+        self.config.load().logging
+    }
+}
+
+impl LoggingConfig {
+    #[inline]
+    pub(crate) fn subject(self, claims: &Claims) -> Redacted<&str> {
+        Redacted::new(Some(&claims.subject), self.redact_subject)
+    }
+
+    #[inline]
+    pub(crate) fn username(self, claims: &Claims) -> Redacted<&str> {
+        Redacted::new(claims.username.as_deref(), self.redact_username)
+    }
+}
+
+impl<T> Redacted<T> {
+    #[inline]
+    fn new(value: Option<T>, redact: bool) -> Self {
+        match value {
+            | Some(_) if redact => Self::Hidden,
+            | Some(value) => Self::Shown(value),
+            | None => Self::Missing,
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Redacted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            | Self::Shown(value) => value.fmt(f),
+            | Self::Hidden => f.write_str(REDACTED),
+            | Self::Missing => f.write_str(MISSING),
+        }
+    }
+}
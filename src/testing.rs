@@ -0,0 +1,132 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    Clock,
+    Config,
+    HttpTransport,
+    ReCloak,
+    ReCloakBuilder,
+    TransportFuture,
+    TransportRequest,
+    TransportResponse,
+};
+
+pub(crate) const SECRET: &str = "test-secret";
+pub(crate) const ISSUER: &str = "https://sso.internal:8443/realms/test";
+pub(crate) const AUDIENCE: &str = "app";
+
+#[derive(Debug, Default)]
+pub(crate) struct MockTransport {
+    routes: Mutex<HashMap<String, TransportResponse>>,
+    requests: Mutex<Vec<TransportRequest>>,
+}
+
+#[derive(Debug)]
+pub(crate) struct FixedClock(Mutex<chrono::DateTime<chrono::Utc>>);
+
+impl MockTransport {
+    pub(crate) fn new() -> Self {
+        let transport = Self::default();
+        transport.route(
+            "/realms/test/protocol/openid-connect/certs",
+            json!({ "keys": [] }),
+        );
+
+        transport
+    }
+
+    pub(crate) fn route(&self, path: &str, body: Value) {
+        self.respond(
+            path,
+            TransportResponse::new(http::StatusCode::OK, body.to_string()),
+        );
+    }
+
+    pub(crate) fn respond(&self, path: &str, resp: TransportResponse) {
+        self.routes.lock().unwrap().insert(path.to_owned(), resp);
+    }
+
+    pub(crate) fn requests(&self) -> Vec<TransportRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl HttpTransport for Arc<MockTransport> {
+    fn execute(&self, req: TransportRequest) -> TransportFuture<'_> {
+        let resp = self.routes.lock().unwrap().get(req.url.path()).cloned();
+        self.requests.lock().unwrap().push(req);
+
+        Box::pin(async move {
+            Ok(resp.unwrap_or_else(|| {
+                TransportResponse::new(http::StatusCode::NOT_FOUND, "")
+            }))
+        })
+    }
+}
+
+impl FixedClock {
+    pub(crate) fn new() -> Arc<Self> {
+        Arc::new(Self(Mutex::new(chrono::Utc::now())))
+    }
+
+    pub(crate) fn advance(&self, by: chrono::Duration) {
+        *self.0.lock().unwrap() += by;
+    }
+}
+
+impl Clock for Arc<FixedClock> {
+    fn now(&self) -> chrono::DateTime<chrono::Utc> {
+        *self.0.lock().unwrap()
+    }
+}
+
+pub(crate) fn config() -> Config {
+    let mut config = Config::for_tests();
+    config.token.hs_secret = Some(SECRET.into());
+    config.client.secret = crate::config::ClientSecret::Basic(SECRET.into());
+
+    config
+}
+
+pub(crate) fn builder(config: Config) -> (ReCloakBuilder, Arc<MockTransport>) {
+    let transport = Arc::new(MockTransport::new());
+
+    (
+        ReCloak::builder(config).transport(transport.clone()),
+        transport,
+    )
+}
+
+#[cfg(feature = "middleware")]
+pub(crate) async fn recloak(config: Config) -> Arc<ReCloak> {
+    builder(config).0.build().await.unwrap()
+}
+
+pub(crate) fn claims(now: chrono::DateTime<chrono::Utc>) -> Value {
+    json!({
+        "iss": ISSUER,
+        "sub": "d5b9e3a4-3f4c-4a39-9c39-3b7b0bbab5f3",
+        "aud": AUDIENCE,
+        "exp": (now + chrono::Duration::minutes(5)).timestamp(),
+        "iat": now.timestamp(),
+        "jti": uuid::Uuid::new_v4().to_string(),
+        "typ": "Bearer",
+        "azp": AUDIENCE,
+        "preferred_username": "alice",
+        "realm_access": { "roles": ["user"] },
+    })
+}
+
+pub(crate) fn sign(claims: &Value) -> String {
+    jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        claims,
+        &jsonwebtoken::EncodingKey::from_secret(SECRET.as_bytes()),
+    )
+    .unwrap()
+}
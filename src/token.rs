@@ -1,6 +1,16 @@
-use std::collections::HashMap;
+use std::{borrow::Cow, collections::HashMap};
 
+use arcstr::ArcStr;
 use serde_with::TimestampSeconds;
+use smallvec::SmallVec;
+
+use crate::{
+    intern::{Interned, InternedOneOrMany},
+    ResourceAccess,
+};
+
+const SERVICE_ACCOUNT_USERNAME_PREFIX: &str = "service-account-";
+const CLIENT_ID_CLAIMS: &[&str] = &["client_id", "clientId"];
 
 pub type TokenData = jsonwebtoken::TokenData<Claims>;
 
@@ -15,17 +25,19 @@ pub struct UserInfo {
 }
 
 #[serde_with::serde_as]
-#[derive(Debug, Clone, serde::Deserialize)]
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 pub struct Claims {
     #[serde(rename = "iss")]
-    pub issuer: String,
+    #[serde_as(as = "Interned")]
+    pub issuer: ArcStr,
 
     #[serde(rename = "sub")]
-    pub subject: uuid::Uuid,
+    pub subject: ArcStr,
 
     #[serde(rename = "aud")]
-    #[serde_as(as = "serde_with::OneOrMany<_>")]
-    pub audience: Vec<String>,
+    #[serde_as(as = "InternedOneOrMany")]
+    pub audience: SmallVec<[ArcStr; 1]>,
 
     #[serde(rename = "exp")]
     #[serde_as(as = "TimestampSeconds<i64>")]
@@ -35,40 +47,282 @@ pub struct Claims {
     #[serde_as(as = "TimestampSeconds<i64>")]
     pub issued_at: chrono::DateTime<chrono::Utc>,
 
-    #[serde(rename = "jti")]
-    pub id: uuid::Uuid,
+    #[serde(rename = "jti", default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<ArcStr>,
 
     #[serde(rename = "acr")]
     pub auth_class_reference: Option<String>,
 
+    #[serde(rename = "amr", default, skip_serializing_if = "Vec::is_empty")]
+    pub auth_methods_reference: Vec<String>,
+
+    #[serde(rename = "auth_time", default)]
+    #[serde_as(as = "Option<TimestampSeconds<i64>>")]
+    pub authenticated_at: Option<chrono::DateTime<chrono::Utc>>,
+
+    #[serde(rename = "azp")]
+    pub authorized_party: Option<String>,
+
+    #[serde(rename = "typ")]
+    pub token_type: Option<String>,
+
     #[serde(rename = "preferred_username")]
-    pub username: String,
+    pub username: Option<ArcStr>,
+
+    #[serde(rename = "email")]
+    pub email: Option<String>,
 
-    #[serde(rename = "realm_access")]
+    #[serde(rename = "email_verified")]
+    pub email_verified: Option<bool>,
+
+    #[serde(rename = "name")]
+    pub name: Option<String>,
+
+    #[serde(rename = "given_name")]
+    pub given_name: Option<String>,
+
+    #[serde(rename = "family_name")]
+    pub family_name: Option<String>,
+
+    #[serde(rename = "locale")]
+    pub locale: Option<String>,
+
+    #[serde(
+        rename = "realm_access",
+        default,
+        skip_serializing_if = "RolesClaim::is_absent"
+    )]
     pub realm: RolesClaim,
 
-    #[serde(rename = "resource_access")]
-    pub resource: HashMap<String, RolesClaim>,
+    #[serde(
+        rename = "resource_access",
+        default,
+        skip_serializing_if = "ResourceAccess::is_empty"
+    )]
+    pub resource: ResourceAccess,
+
+    #[serde(
+        rename = "organization",
+        default,
+        deserialize_with = "deserialize_organizations",
+        serialize_with = "serialize_organizations",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub organizations: Vec<Organization>,
+
+    #[cfg(feature = "authz")]
+    #[serde(rename = "authorization")]
+    pub authorization: Option<crate::AuthorizationClaim>,
+
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Clone, serde::Deserialize)]
+#[serde_with::serde_as]
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
 pub struct RolesClaim {
-    #[serde(rename = "roles")]
-    pub roles: Vec<String>,
+    #[serde(rename = "roles", default)]
+    #[serde_as(as = "Vec<Interned>")]
+    pub roles: Vec<ArcStr>,
+
+    #[serde(skip, default = "present")]
+    present: bool,
+}
+
+#[serde_with::serde_as]
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ClaimsRef<'a> {
+    #[serde(rename = "iss", borrow)]
+    pub issuer: Cow<'a, str>,
+
+    #[serde(rename = "sub", borrow)]
+    pub subject: Cow<'a, str>,
+
+    #[serde(rename = "aud", borrow)]
+    #[serde_as(as = "serde_with::OneOrMany<serde_with::BorrowCow>")]
+    pub audience: Vec<Cow<'a, str>>,
+
+    #[serde(rename = "exp")]
+    #[serde_as(as = "TimestampSeconds<i64>")]
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+
+    #[serde(rename = "iat")]
+    #[serde_as(as = "TimestampSeconds<i64>")]
+    pub issued_at: chrono::DateTime<chrono::Utc>,
+
+    #[serde(rename = "jti", default, borrow)]
+    #[serde_as(as = "Option<serde_with::BorrowCow>")]
+    pub id: Option<Cow<'a, str>>,
+
+    #[serde(rename = "acr", borrow)]
+    #[serde_as(as = "Option<serde_with::BorrowCow>")]
+    pub auth_class_reference: Option<Cow<'a, str>>,
+
+    #[serde(rename = "azp", borrow)]
+    #[serde_as(as = "Option<serde_with::BorrowCow>")]
+    pub authorized_party: Option<Cow<'a, str>>,
+
+    #[serde(rename = "typ", borrow)]
+    #[serde_as(as = "Option<serde_with::BorrowCow>")]
+    pub token_type: Option<Cow<'a, str>>,
+
+    #[serde(rename = "scope", borrow)]
+    #[serde_as(as = "Option<serde_with::BorrowCow>")]
+    pub scope: Option<Cow<'a, str>>,
+
+    #[serde(rename = "preferred_username", borrow)]
+    #[serde_as(as = "Option<serde_with::BorrowCow>")]
+    pub username: Option<Cow<'a, str>>,
+
+    #[serde(rename = "email", borrow)]
+    #[serde_as(as = "Option<serde_with::BorrowCow>")]
+    pub email: Option<Cow<'a, str>>,
+
+    #[serde(rename = "email_verified")]
+    pub email_verified: Option<bool>,
+
+    #[serde(rename = "realm_access", borrow)]
+    pub realm: Option<RolesRef<'a>>,
+
+    #[serde(rename = "resource_access", default, borrow)]
+    #[serde_as(as = "serde_with::Map<serde_with::BorrowCow, _>")]
+    pub resource: Vec<(Cow<'a, str>, RolesRef<'a>)>,
+}
+
+#[serde_with::serde_as]
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct RolesRef<'a> {
+    #[serde(rename = "roles", default, borrow)]
+    #[serde_as(as = "Vec<serde_with::BorrowCow>")]
+    pub roles: Vec<Cow<'a, str>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Organization {
+    alias: String,
+    id: Option<String>,
+}
+
+impl RolesClaim {
+    pub fn new<I>(roles: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<ArcStr>,
+    {
+        Self {
+            roles: roles.into_iter().map(Into::into).collect(),
+            present: true,
+        }
+    }
+
+    #[inline]
+    pub(crate) const fn is_present(&self) -> bool {
+        self.present
+    }
+
+    #[inline]
+    const fn is_absent(&self) -> bool {
+        !self.present
+    }
 }
 
 impl Claims {
+    pub fn new(
+        issuer: impl Into<ArcStr>,
+        subject: impl Into<ArcStr>,
+        audience: impl Into<ArcStr>,
+        issued_at: chrono::DateTime<chrono::Utc>,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Self {
+        Self {
+            issuer: issuer.into(),
+            subject: subject.into(),
+            audience: smallvec::smallvec![audience.into()],
+            expires_at,
+            issued_at,
+            id: Some(uuid::Uuid::new_v4().to_string().into()),
+            auth_class_reference: None,
+            auth_methods_reference: Vec::new(),
+            authenticated_at: None,
+            authorized_party: None,
+            token_type: Some(crate::jwt::BEARER_TOKEN_TYPE.to_owned()),
+            username: None,
+            email: None,
+            email_verified: None,
+            name: None,
+            given_name: None,
+            family_name: None,
+            locale: None,
+            realm: RolesClaim::default(),
+            resource: ResourceAccess::new(),
+            organizations: Vec::new(),
+            #[cfg(feature = "authz")]
+            authorization: None,
+            extra: HashMap::new(),
+        }
+    }
+
     #[inline]
-    pub fn is_subject(&self, rhs_id: impl TryInto<uuid::Uuid>) -> bool {
-        rhs_id
-            .try_into()
-            .map(|id| self.subject == id)
-            .unwrap_or(false)
+    pub fn is_subject(&self, subject: impl AsRef<str>) -> bool {
+        self.subject == subject.as_ref()
+    }
+
+    #[inline]
+    pub fn is_subject_id(&self, id: uuid::Uuid) -> bool {
+        self.subject_id() == Some(id)
+    }
+
+    // always `Some` for tokens validated under `Provider::Keycloak`.
+    #[inline]
+    pub fn subject_id(&self) -> Option<uuid::Uuid> {
+        uuid::Uuid::try_parse(&self.subject).ok()
     }
 
     #[inline]
     pub fn is_user(&self, username: impl AsRef<str>) -> bool {
-        self.username == username.as_ref()
+        self.username.as_deref() == Some(username.as_ref())
+    }
+
+    pub fn is_service_account(&self) -> bool {
+        let has_prefix = self
+            .username
+            .as_deref()
+            .is_some_and(|u| u.starts_with(SERVICE_ACCOUNT_USERNAME_PREFIX));
+
+        has_prefix
+            || CLIENT_ID_CLAIMS.iter().any(|c| self.extra.contains_key(*c))
+    }
+
+    pub fn client_id(&self) -> Option<&str> {
+        self.authorized_party.as_deref().or_else(|| {
+            CLIENT_ID_CLAIMS
+                .iter()
+                .find_map(|c| self.claim(c).and_then(|v| v.as_str()))
+        })
+    }
+
+    pub fn meets_acr(&self, level: impl AsRef<str>) -> bool {
+        let Some(acr) = self.auth_class_reference.as_deref() else {
+            return false;
+        };
+
+        let level = level.as_ref();
+
+        match (acr.parse::<u32>(), level.parse::<u32>()) {
+            | (Ok(acr), Ok(level)) => acr >= level,
+            | _ => acr == level,
+        }
+    }
+
+    #[inline]
+    pub fn authenticated_within(
+        &self,
+        duration: chrono::Duration,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> bool {
+        self.authenticated_at
+            .map(|at| at + duration >= now)
+            .unwrap_or(false)
     }
 
     #[inline]
@@ -87,4 +341,212 @@ impl Claims {
             .map(|r| r.roles.iter().any(|r| r == role.as_ref()))
             .unwrap_or(false)
     }
+
+    #[inline]
+    pub fn has_scope(&self, scope: impl AsRef<str>) -> bool {
+        self.claim("scope")
+            .and_then(|v| v.as_str())
+            .is_some_and(|s| s.split_whitespace().any(|s| s == scope.as_ref()))
+    }
+
+    #[inline]
+    pub fn claim(&self, name: impl AsRef<str>) -> Option<&serde_json::Value> {
+        self.extra.get(name.as_ref())
+    }
+
+    #[inline]
+    pub fn organization(
+        &self,
+        alias: impl AsRef<str>,
+    ) -> Option<&Organization> {
+        self.organizations
+            .iter()
+            .find(|o| o.alias == alias.as_ref())
+    }
+
+    #[inline]
+    pub fn has_organization(&self, alias: impl AsRef<str>) -> bool {
+        self.organization(alias).is_some()
+    }
+
+    #[inline]
+    pub fn is_lightweight(&self) -> bool {
+        self.username.is_none() || !self.realm.is_present()
+    }
+
+    pub(crate) fn hydrate(
+        &mut self,
+        source: serde_json::Map<String, serde_json::Value>,
+    ) -> serde_json::Result<()> {
+        #[serde_with::serde_as]
+        #[derive(serde::Deserialize)]
+        struct Hydration {
+            #[serde(rename = "preferred_username")]
+            username: Option<ArcStr>,
+
+            email: Option<String>,
+            email_verified: Option<bool>,
+            name: Option<String>,
+            given_name: Option<String>,
+            family_name: Option<String>,
+            locale: Option<String>,
+
+            #[serde(rename = "realm_access")]
+            realm: Option<RolesClaim>,
+
+            #[serde(rename = "resource_access", default)]
+            resource: ResourceAccess,
+
+            #[serde(
+                rename = "organization",
+                default,
+                deserialize_with = "deserialize_organizations"
+            )]
+            organizations: Vec<Organization>,
+
+            #[serde(flatten)]
+            extra: HashMap<String, serde_json::Value>,
+        }
+
+        let source: Hydration = serde_json::from_value(source.into())?;
+
+        self.username = self.username.take().or(source.username);
+        self.email = self.email.take().or(source.email);
+        self.email_verified = self.email_verified.or(source.email_verified);
+        self.name = self.name.take().or(source.name);
+        self.given_name = self.given_name.take().or(source.given_name);
+        self.family_name = self.family_name.take().or(source.family_name);
+        self.locale = self.locale.take().or(source.locale);
+
+        if let Some(realm) = source.realm.filter(|_| !self.realm.is_present()) {
+            self.realm = realm;
+        }
+
+        for (client, roles) in source.resource {
+            if !self.resource.contains_key(&client) {
+                self.resource.insert(client, roles);
+            }
+        }
+
+        if self.organizations.is_empty() {
+            self.organizations = source.organizations;
+        }
+
+        for (name, value) in source.extra {
+            self.extra.entry(name).or_insert(value);
+        }
+
+        Ok(())
+    }
+}
+
+impl ClaimsRef<'_> {
+    #[inline]
+    pub fn is_user(&self, username: impl AsRef<str>) -> bool {
+        self.username.as_deref() == Some(username.as_ref())
+    }
+
+    #[inline]
+    pub fn has_realm_role(&self, role: impl AsRef<str>) -> bool {
+        self.realm
+            .as_ref()
+            .is_some_and(|realm| realm.roles.iter().any(|r| r == role.as_ref()))
+    }
+
+    #[inline]
+    pub fn has_role(
+        &self,
+        client_id: impl AsRef<str>,
+        role: impl AsRef<str>,
+    ) -> bool {
+        self.resource
+            .iter()
+            .find(|(client, _)| client == client_id.as_ref())
+            .is_some_and(|(_, r)| r.roles.iter().any(|r| r == role.as_ref()))
+    }
+
+    #[inline]
+    pub fn has_scope(&self, scope: impl AsRef<str>) -> bool {
+        self.scope
+            .as_deref()
+            .is_some_and(|s| s.split_whitespace().any(|s| s == scope.as_ref()))
+    }
+}
+
+impl Organization {
+    #[inline]
+    pub fn new(alias: impl Into<String>, id: Option<String>) -> Self {
+        Self {
+            alias: alias.into(),
+            id,
+        }
+    }
+
+    #[inline]
+    pub fn alias(&self) -> &str {
+        &self.alias
+    }
+
+    #[inline]
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+}
+
+fn deserialize_organizations<'de, D>(
+    deserializer: D,
+) -> Result<Vec<Organization>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum OrganizationsDto {
+        Aliases(Vec<String>),
+        Detailed(HashMap<String, OrganizationDto>),
+    }
+
+    #[derive(serde::Deserialize)]
+    struct OrganizationDto {
+        id: Option<String>,
+    }
+
+    let orgs = match serde::Deserialize::deserialize(deserializer)? {
+        | OrganizationsDto::Aliases(aliases) => aliases
+            .into_iter()
+            .map(|alias| Organization { alias, id: None })
+            .collect(),
+        | OrganizationsDto::Detailed(orgs) => orgs
+            .into_iter()
+            .map(|(alias, dto)| Organization { alias, id: dto.id })
+            .collect(),
+    };
+
+    Ok(orgs)
+}
+
+fn serialize_organizations<S>(
+    orgs: &[Organization],
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    use serde::ser::SerializeMap;
+
+    if orgs.iter().all(|org| org.id.is_none()) {
+        return serializer.collect_seq(orgs.iter().map(Organization::alias));
+    }
+
+    let mut map = serializer.serialize_map(Some(orgs.len()))?;
+    for org in orgs {
+        map.serialize_entry(&org.alias, &serde_json::json!({ "id": org.id }))?;
+    }
+
+    map.end()
+}
+
+#[inline]
+const fn present() -> bool {
+    true
 }
@@ -0,0 +1,154 @@
+use std::{fmt, future::Future, pin::Pin};
+
+use bytes::Bytes;
+use http::{HeaderMap, Method, StatusCode};
+use serde::de::DeserializeOwned;
+
+use crate::{telemetry, Error, Result};
+
+pub type TransportFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<TransportResponse>> + Send + 'a>>;
+
+pub trait HttpTransport: fmt::Debug + Send + Sync + 'static {
+    fn execute(&self, req: TransportRequest) -> TransportFuture<'_>;
+}
+
+#[derive(Debug, Clone)]
+pub struct TransportRequest {
+    pub method: Method,
+    pub url: url::Url,
+    pub headers: HeaderMap,
+    pub body: Option<Bytes>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TransportResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl TransportRequest {
+    #[inline]
+    pub fn new(method: Method, url: url::Url) -> Self {
+        Self {
+            method,
+            url,
+            headers: HeaderMap::new(),
+            body: None,
+        }
+    }
+
+    pub(crate) fn from_reqwest(req: reqwest::Request) -> Result<Self> {
+        let body = match req.body() {
+            | Some(body) => match body.as_bytes() {
+                | Some(bytes) => Some(Bytes::copy_from_slice(bytes)),
+                | None => {
+                    return Err(Error::Transport(
+                        "streaming request bodies are not supported".into(),
+                    ));
+                }
+            },
+            | None => None,
+        };
+
+        Ok(Self {
+            method: req.method().clone(),
+            url: req.url().clone(),
+            headers: req.headers().clone(),
+            body,
+        })
+    }
+}
+
+impl TransportResponse {
+    #[inline]
+    pub fn new(status: StatusCode, body: impl Into<Bytes>) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: body.into(),
+        }
+    }
+
+    pub(crate) fn json<T>(&self) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        serde_json::from_slice(&self.body).map_err(|err| {
+            telemetry::trace_debug!(error = %err, "failed to parse response body");
+
+            Error::unexpected_response(self.status, &self.body)
+        })
+    }
+
+    #[inline]
+    pub(crate) fn error_for_status(self) -> Result<Self> {
+        if self.status.is_client_error() || self.status.is_server_error() {
+            return Err(Error::unexpected_response(self.status, &self.body));
+        }
+
+        Ok(self)
+    }
+}
+
+impl HttpTransport for reqwest::Client {
+    fn execute(&self, req: TransportRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let mut request = reqwest::Request::new(req.method, req.url);
+            *request.headers_mut() = req.headers;
+            *request.body_mut() = req.body.map(From::from);
+
+            let resp = reqwest::Client::execute(self, request).await?;
+
+            Ok(TransportResponse {
+                status: resp.status(),
+                headers: resp.headers().clone(),
+                body: resp.bytes().await?,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{HeaderValue, Method};
+
+    use super::TransportRequest;
+    use crate::testing;
+
+    #[tokio::test]
+    async fn decorator_sees_every_request() {
+        let (builder, transport) = testing::builder(testing::config());
+        builder
+            .decorator(|req| {
+                req.headers
+                    .insert("x-request-id", HeaderValue::from_static("test"));
+            })
+            .build()
+            .await
+            .unwrap();
+
+        let requests = transport.requests();
+        assert!(!requests.is_empty());
+        assert!(requests.iter().all(|req| {
+            req.method == Method::GET && req.headers["x-request-id"] == "test"
+        }));
+    }
+
+    #[test]
+    fn keeps_buffered_bodies() {
+        let req = reqwest::Client::new()
+            .post("https://sso.internal/token")
+            .form(&[("grant_type", "client_credentials")])
+            .build()
+            .unwrap();
+
+        let req = TransportRequest::from_reqwest(req).unwrap();
+        assert_eq!(req.method, Method::POST);
+        assert_eq!(
+            req.body.as_deref(),
+            Some(b"grant_type=client_credentials".as_slice())
+        );
+    }
+}
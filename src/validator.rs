@@ -0,0 +1,85 @@
+use std::{fmt, sync::Arc};
+
+use crate::{
+    telemetry::{self, TOKEN_TARGET},
+    Claims,
+    DenyReason,
+    Error,
+    LoggingConfig,
+    Result,
+};
+
+pub trait ClaimsValidator: Send + Sync + 'static {
+    fn validate(&self, claims: &Claims) -> std::result::Result<(), DenyReason>;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequireEmailVerified;
+
+#[derive(Clone)]
+pub(crate) struct ClaimsValidators(Vec<Arc<dyn ClaimsValidator>>);
+
+impl ClaimsValidator for RequireEmailVerified {
+    #[inline]
+    fn validate(&self, claims: &Claims) -> std::result::Result<(), DenyReason> {
+        match claims.email_verified {
+            | Some(true) => Ok(()),
+            | _ => Err(DenyReason::EmailNotVerified),
+        }
+    }
+}
+
+impl<F> ClaimsValidator for F
+where
+    F: Fn(&Claims) -> std::result::Result<(), DenyReason>
+        + Send
+        + Sync
+        + 'static,
+{
+    #[inline]
+    fn validate(&self, claims: &Claims) -> std::result::Result<(), DenyReason> {
+        self(claims)
+    }
+}
+
+impl ClaimsValidators {
+    #[inline]
+    pub(crate) const fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    #[inline]
+    pub(crate) fn push(&mut self, validator: impl ClaimsValidator) {
+        self.0.push(Arc::new(validator));
+    }
+
+    pub(crate) fn check(
+        &self,
+        claims: &Claims,
+        logging: LoggingConfig,
+    ) -> Result<()> {
+        self.0
+            .iter()
+            .try_for_each(|validator| validator.validate(claims))
+            .map_err(|reason| {
+                telemetry::trace_warn!(
+                    target: TOKEN_TARGET,
+                    jti = ?claims.id,
+                    subject = %logging.subject(claims),
+                    username = %logging.username(claims),
+                    %reason,
+                    "token claims rejected"
+                );
+
+                Error::ClaimsRejected(reason)
+            })
+    }
+}
+
+impl fmt::Debug for ClaimsValidators {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ClaimsValidators")
+            .field(&self.0.len())
+            .finish()
+    }
+}
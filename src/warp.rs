@@ -0,0 +1,198 @@
+use std::sync::Arc;
+
+use warp::{
+    http::{header::AUTHORIZATION, StatusCode},
+    reject::Reject,
+    Filter,
+    Rejection,
+    Reply,
+};
+
+use crate::{
+    middleware::http::TokenHeader,
+    telemetry::{self, MIDDLEWARE_TARGET},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthRejection {
+    MissingHeader,
+    InvalidToken,
+    InsufficientAuthentication,
+    MissingRole,
+}
+
+pub fn kc_auth(
+    kc: Arc<crate::ReCloak>,
+) -> impl Filter<Extract = (crate::Claims,), Error = Rejection> + Clone {
+    warp::header::optional::<String>(AUTHORIZATION.as_str()).and_then(
+        move |header: Option<String>| {
+            let kc = kc.clone();
+
+            async move {
+                authorize(&kc, header.as_deref())
+                    .await
+                    .map_err(warp::reject::custom)
+            }
+        },
+    )
+}
+
+pub fn with_requirement(
+    kc: Arc<crate::ReCloak>,
+    requirement: crate::Requirement,
+) -> impl Filter<Extract = (crate::Claims,), Error = Rejection> + Clone {
+    let requirement = Arc::new(requirement);
+
+    kc_auth(kc).and_then(move |claims: crate::Claims| {
+        let decision = crate::Authorizer.decide(&claims, &requirement);
+
+        async move { guard(claims, decision) }
+    })
+}
+
+pub fn with_realm_role(
+    kc: Arc<crate::ReCloak>,
+    role: impl Into<arcstr::ArcStr>,
+) -> impl Filter<Extract = (crate::Claims,), Error = Rejection> + Clone {
+    with_requirement(kc, crate::Requirement::realm_role(role))
+}
+
+pub fn with_client_role(
+    kc: Arc<crate::ReCloak>,
+    client_id: impl Into<arcstr::ArcStr>,
+    role: impl Into<arcstr::ArcStr>,
+) -> impl Filter<Extract = (crate::Claims,), Error = Rejection> + Clone {
+    with_requirement(kc, crate::Requirement::client_role(client_id, role))
+}
+
+pub async fn handle_rejection(
+    rejection: Rejection,
+) -> Result<impl Reply, Rejection> {
+    match rejection.find::<AuthRejection>() {
+        | Some(err) => {
+            Ok(warp::reply::with_status(err.to_string(), err.status()))
+        }
+        | None => Err(rejection),
+    }
+}
+
+async fn authorize(
+    kc: &crate::ReCloak,
+    header: Option<&str>,
+) -> Result<crate::Claims, AuthRejection> {
+    let header = header.ok_or(AuthRejection::MissingHeader)?;
+    let token = TokenHeader::DEFAULT
+        .extract(header)
+        .ok_or(AuthRejection::InvalidToken)?;
+
+    let data = kc.validate_token(token).await.map_err(|err| {
+        telemetry::trace_error!(target: MIDDLEWARE_TARGET, error = %err, "failed to validate bearer token");
+
+        AuthRejection::InvalidToken
+    })?;
+
+    kc.enrich_claims(token, data.claims).await.map_err(|err| {
+        telemetry::trace_error!(target: MIDDLEWARE_TARGET, error = %err, "failed to enrich token claims");
+
+        AuthRejection::InvalidToken
+    })
+}
+
+#[inline]
+fn guard(
+    claims: crate::Claims,
+    decision: crate::Decision,
+) -> Result<crate::Claims, Rejection> {
+    match decision {
+        | crate::Decision::Allow => Ok(claims),
+        | crate::Decision::Deny(reason) => {
+            telemetry::trace_debug!(target: MIDDLEWARE_TARGET, %reason, "request denied");
+
+            let rejection = if reason.is_authentication() {
+                AuthRejection::InsufficientAuthentication
+            } else {
+                AuthRejection::MissingRole
+            };
+
+            Err(warp::reject::custom(rejection))
+        }
+    }
+}
+
+impl AuthRejection {
+    #[inline]
+    pub const fn status(&self) -> StatusCode {
+        match self {
+            | AuthRejection::MissingRole => StatusCode::FORBIDDEN,
+            | _ => StatusCode::UNAUTHORIZED,
+        }
+    }
+}
+
+impl Reject for AuthRejection {}
+
+impl std::fmt::Display for AuthRejection {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            | AuthRejection::MissingHeader => {
+                write!(f, "missing authorization header")
+            }
+            | AuthRejection::InvalidToken => write!(f, "invalid token"),
+            | AuthRejection::InsufficientAuthentication => {
+                write!(f, "insufficient authentication level")
+            }
+            | AuthRejection::MissingRole => write!(f, "insufficient role"),
+        }
+    }
+}
+
+impl std::error::Error for AuthRejection {}
+
+#[cfg(test)]
+mod tests {
+    use warp::http::StatusCode;
+
+    use super::{authorize, guard, handle_rejection, AuthRejection};
+    use crate::{testing, Decision, DenyReason};
+
+    #[tokio::test]
+    async fn parses_the_bearer_header() {
+        let kc = testing::recloak(testing::config()).await;
+        let token = testing::sign(&testing::claims(chrono::Utc::now()));
+
+        let header = format!("bearer  {token}");
+        assert!(authorize(&kc, Some(&header)).await.is_ok());
+
+        let rejections = [
+            (None, AuthRejection::MissingHeader),
+            (Some(format!("Basic {token}")), AuthRejection::InvalidToken),
+            (Some("Bearer ".to_owned()), AuthRejection::InvalidToken),
+        ];
+        for (header, expected) in rejections {
+            let err = authorize(&kc, header.as_deref()).await.unwrap_err();
+            assert_eq!(err, expected);
+        }
+    }
+
+    async fn status(decision: Decision) -> StatusCode {
+        let claims =
+            serde_json::from_value(testing::claims(chrono::Utc::now()))
+                .unwrap();
+        let rejection = guard(claims, decision).unwrap_err();
+
+        match handle_rejection(rejection).await {
+            | Ok(reply) => warp::Reply::into_response(reply).status(),
+            | Err(rejection) => panic!("unhandled rejection: {rejection:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn maps_deny_reasons_to_status() {
+        let role = DenyReason::MissingRealmRole("admin".into());
+        let acr = DenyReason::InsufficientAcr("gold".into());
+
+        assert_eq!(status(Decision::Deny(role)).await, StatusCode::FORBIDDEN);
+        assert_eq!(status(Decision::Deny(acr)).await, StatusCode::UNAUTHORIZED);
+    }
+}
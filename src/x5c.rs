@@ -0,0 +1,132 @@
+use std::{fmt, path::Path};
+
+use jsonwebtoken::jwk::{AlgorithmParameters, Jwk};
+use openssl::{
+    base64,
+    bn::BigNumContext,
+    error::ErrorStack,
+    stack::Stack,
+    x509::{
+        store::{X509Store, X509StoreBuilder},
+        X509StoreContext,
+        X509,
+    },
+};
+
+use crate::{Error, Result};
+
+pub(crate) struct TrustStore(X509Store);
+
+impl TrustStore {
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let pem = std::fs::read(path).map_err(|err| {
+            Error::Config(format!(
+                "failed to read trusted ca `{}`: {err}",
+                path.display()
+            ))
+        })?;
+
+        let mut builder = X509StoreBuilder::new()?;
+        for cert in X509::stack_from_pem(&pem)? {
+            builder.add_cert(cert)?;
+        }
+
+        Ok(Self(builder.build()))
+    }
+
+    pub(crate) fn verify(&self, jwk: &Jwk) -> Result<()> {
+        let chain = jwk
+            .common
+            .x509_chain
+            .as_deref()
+            .filter(|chain| !chain.is_empty())
+            .ok_or_else(|| {
+                Error::Certificate("key has no x5c certificate chain".into())
+            })?;
+
+        let mut certs = chain
+            .iter()
+            .map(|cert| X509::from_der(&base64::decode_block(cert)?))
+            .collect::<std::result::Result<Vec<_>, ErrorStack>>()?
+            .into_iter();
+
+        let leaf = certs.next().unwrap();
+        let mut intermediates = Stack::new()?;
+        for cert in certs {
+            intermediates.push(cert)?;
+        }
+
+        let mut ctx = X509StoreContext::new()?;
+        let failure = ctx.init(&self.0, &leaf, &intermediates, |ctx| {
+            Ok((!ctx.verify_cert()?).then(|| ctx.error()))
+        })?;
+
+        if let Some(err) = failure {
+            return Err(Error::Certificate(err.to_string()));
+        }
+
+        if !matches_key(&leaf, jwk)? {
+            return Err(Error::Certificate(
+                "x5c certificate does not match the published key".into(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for TrustStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TrustStore")
+    }
+}
+
+impl From<ErrorStack> for Error {
+    #[inline]
+    fn from(value: ErrorStack) -> Self {
+        Error::Certificate(value.to_string())
+    }
+}
+
+fn matches_key(cert: &X509, jwk: &Jwk) -> Result<bool> {
+    let key = cert.public_key()?;
+
+    let matches = match jwk.algorithm {
+        | AlgorithmParameters::RSA(ref params) => {
+            let rsa = key.rsa()?;
+
+            rsa.n().to_vec() == decode_uint(&params.n)?
+                && rsa.e().to_vec() == decode_uint(&params.e)?
+        }
+        | AlgorithmParameters::EllipticCurve(ref params) => {
+            let ec = key.ec_key()?;
+            let mut ctx = BigNumContext::new()?;
+            let mut x = openssl::bn::BigNum::new()?;
+            let mut y = openssl::bn::BigNum::new()?;
+            ec.public_key().affine_coordinates(
+                ec.group(),
+                &mut x,
+                &mut y,
+                &mut ctx,
+            )?;
+
+            x.to_vec() == decode_uint(&params.x)?
+                && y.to_vec() == decode_uint(&params.y)?
+        }
+        | _ => false,
+    };
+
+    Ok(matches)
+}
+
+fn decode_uint(value: &str) -> Result<Vec<u8>> {
+    let mut encoded = value.replace('-', "+").replace('_', "/");
+    while !encoded.len().is_multiple_of(4) {
+        encoded.push('=');
+    }
+
+    let bytes = base64::decode_block(&encoded)?;
+    let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+
+    Ok(bytes[start..].to_vec())
+}